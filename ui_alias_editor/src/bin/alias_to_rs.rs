@@ -0,0 +1,110 @@
+// Headless conversion of alias assets to Rust source, for CI.
+//
+// Usage: alias_to_rs <file-or-directory>...
+//        alias_to_rs --validate <project-dir>...
+//        alias_to_rs --deps <file>...
+// Files are printed to stdout, directories are converted in place.
+// With --validate nothing is written; every alias is checked and errors fail the run.
+// With --deps each file's alias references and external paths are printed, one per line.
+
+use std::path::Path;
+use ui_alias_editor::{codegen, project, validation};
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mode = args.first().filter(|arg| *arg == "--validate" || *arg == "--deps").cloned();
+    if mode.is_some() {
+        args.remove(0);
+    }
+    let validate_only = mode.as_deref() == Some("--validate");
+    if args.is_empty() {
+        eprintln!("usage: alias_to_rs [--validate | --deps] <file-or-directory>...");
+        std::process::exit(2);
+    }
+
+    if mode.as_deref() == Some("--deps") {
+        let failed = args.iter().fold(false, |failed, arg| print_dependencies(Path::new(arg)) || failed);
+        if failed {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if validate_only {
+        let failed = args.iter().fold(false, |failed, arg| validate(Path::new(arg)) || failed);
+        if failed {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut failed = false;
+    for arg in &args {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            for (source, result) in codegen::convert_directory(path) {
+                match result {
+                    Ok(output) => eprintln!("✅ {} -> {}", source.display(), output.display()),
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        failed = true;
+                    }
+                }
+            }
+        } else {
+            match codegen::convert_alias_file(path) {
+                Ok(code) => print!("{}", code),
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    failed = true;
+                }
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Print a tab-separated `<file> alias <name>` or `<file> path <path>` line per dependency
+/// of one alias file, returning whether it failed
+fn print_dependencies(path: &Path) -> bool {
+    match project::load_alias_asset(path) {
+        Ok(asset) => {
+            let (aliases, paths) = codegen::dependencies(&asset);
+            for alias in aliases {
+                println!("{}\talias\t{}", path.display(), alias);
+            }
+            for external in paths {
+                println!("{}\tpath\t{}", path.display(), external);
+            }
+            false
+        }
+        Err(e) => {
+            eprintln!("❌ {}: {}", path.display(), e);
+            true
+        }
+    }
+}
+
+/// Print the project report for `root`, returning whether any file has errors
+fn validate(root: &Path) -> bool {
+    let mut failed = false;
+    for (path, diagnostics) in validation::validate_project(root) {
+        if diagnostics.is_empty() {
+            eprintln!("✅ {}", path.display());
+            continue;
+        }
+        for diagnostic in diagnostics {
+            let marker = match diagnostic.severity() {
+                validation::Severity::Error => "❌",
+                validation::Severity::Warning => "⚠",
+                validation::Severity::Info => "ℹ",
+            };
+            eprintln!("{} {}: {}", marker, path.display(), diagnostic);
+            failed |= diagnostic.is_error();
+        }
+    }
+    failed
+}
@@ -1,8 +1,12 @@
 use gpui::{prelude::*, *};
 use ui::{h_flex, v_flex, ActiveTheme, StyledExt};
-use crate::type_block::{TypeBlock, BlockId};
-use std::collections::HashMap;
-use std::sync::Arc;
+use crate::type_block::{TypeBlock, BlockId, BlockLayout, BlockTheme, FocusTarget, LayoutDirection, SlotHandler, BlockHandler, AliasHandler, ConstructorHandler};
+use ui_types_common::TypeAstNode;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of undo snapshots kept
+const UNDO_LIMIT: usize = 100;
 
 /// Drag state for blocks
 #[derive(Clone, Debug)]
@@ -25,6 +29,61 @@ pub enum DropTarget {
     },
 }
 
+/// What the user picked on the empty canvas
+#[derive(Clone, Debug, PartialEq)]
+pub enum EmptyStateChoice {
+    /// Open the type picker for the root
+    PickRoot,
+    /// Start from `examples::templates()[index]`
+    Template(usize),
+    /// Load `examples::EXAMPLE_ALIASES[index]`
+    Example(usize),
+    /// Import aliases from a `.rs` file
+    ImportRust,
+    /// Build a fn-pointer callback with the guided wizard
+    Callback,
+}
+
+pub type EmptyStateHandler = Arc<dyn Fn(EmptyStateChoice, &mut Window, &mut App) + 'static>;
+
+/// Callbacks the canvas hands to its blocks. Unset handlers leave the matching
+/// interaction off, so `CanvasHandlers::default()` draws an inert canvas.
+#[derive(Clone, Default)]
+pub struct CanvasHandlers {
+    pub slot_click: Option<SlotHandler>,
+    pub empty_click: Option<EmptyStateHandler>,
+    pub dismiss_warning: Option<BlockHandler>,
+    pub alias_open: Option<AliasHandler>,
+    pub slot_clear: Option<SlotHandler>,
+    pub block_select: Option<BlockHandler>,
+    pub constructor_swap: Option<ConstructorHandler>,
+    pub slot_insert: Option<SlotHandler>,
+    pub lifetime_edit: Option<SlotHandler>,
+}
+
+/// One step on the way from the root to a slot: a block and which of its slots to enter
+#[derive(Clone, Debug)]
+pub struct SlotPathSegment {
+    pub block_id: BlockId,
+    pub block_name: String,
+    pub slot_index: usize,
+    /// The slot's hint or param label (`value`, `T`)
+    pub slot_label: String,
+    /// Whether the block has other slots, so the label is worth showing
+    pub has_siblings: bool,
+}
+
+/// Size and shape of the tree on the canvas
+#[derive(Clone, Debug, Default)]
+pub struct TreeStats {
+    pub blocks: usize,
+    pub empty_slots: usize,
+    /// Constructors on the most deeply nested path (`Option<Option<T>>` is 2)
+    pub max_depth: usize,
+    /// That path, outermost first: (block, name)
+    pub deepest_path: Vec<(BlockId, String)>,
+}
+
 /// Canvas for visually composing type blocks with drag-and-drop
 pub struct BlockCanvas {
     /// The root block being edited (the main type expression)
@@ -41,6 +100,38 @@ pub struct BlockCanvas {
     
     /// Selected block for keyboard operations
     selected_block: Option<BlockId>,
+    
+    /// Block or empty slot with keyboard focus, moved with Tab
+    focused: Option<FocusTarget>,
+    
+    /// Lint warnings shown as badges on blocks
+    block_warnings: Arc<HashMap<BlockId, String>>,
+    
+    /// Blocks unchanged since a Rust import, shown with a marker
+    imported_blocks: Arc<HashSet<BlockId>>,
+    
+    /// Whole-tree snapshots taken before each edit, newest last. Full clones so
+    /// undoing a destructive edit restores nested children intact. Each snapshot
+    /// carries a serial so the editor can tie its own undo state to one.
+    undo_stack: Vec<(u64, Option<TypeBlock>)>,
+    
+    /// Serial for the next undo snapshot
+    next_snapshot: u64,
+    
+    /// Render blocks in the accessibility high-contrast style
+    high_contrast: bool,
+    
+    /// How constructor slots are arranged
+    direction: LayoutDirection,
+    
+    /// Annotate primitive blocks with their size in bytes
+    show_sizes: bool,
+    
+    /// Bounds recorded by the rendered blocks during the last layout pass
+    layout: Arc<Mutex<BlockLayout>>,
+    
+    /// Editor named in `DraggedBlock`s; blocks get no drag grip while unset
+    drag_source: Option<EntityId>,
 }
 
 impl BlockCanvas {
@@ -51,6 +142,16 @@ impl BlockCanvas {
             hover_slot: None,
             canvas_bounds: None,
             selected_block: None,
+            focused: None,
+            block_warnings: Arc::new(HashMap::new()),
+            imported_blocks: Arc::new(HashSet::new()),
+            undo_stack: Vec::new(),
+            next_snapshot: 0,
+            high_contrast: false,
+            direction: LayoutDirection::default(),
+            show_sizes: false,
+            layout: Arc::new(Mutex::new(BlockLayout::default())),
+            drag_source: None,
         }
     }
 
@@ -61,6 +162,16 @@ impl BlockCanvas {
             hover_slot: None,
             canvas_bounds: None,
             selected_block: None,
+            focused: None,
+            block_warnings: Arc::new(HashMap::new()),
+            imported_blocks: Arc::new(HashSet::new()),
+            undo_stack: Vec::new(),
+            next_snapshot: 0,
+            high_contrast: false,
+            direction: LayoutDirection::default(),
+            show_sizes: false,
+            layout: Arc::new(Mutex::new(BlockLayout::default())),
+            drag_source: None,
         }
     }
 
@@ -68,19 +179,279 @@ impl BlockCanvas {
         self.root_block.as_ref()
     }
 
+    /// Count the blocks and empty slots and find the most deeply nested constructors
+    pub fn stats(&self) -> TreeStats {
+        let Some(root) = &self.root_block else {
+            return TreeStats::default();
+        };
+        let mut stats = TreeStats::default();
+        root.walk(&mut |block| {
+            stats.blocks += 1;
+            stats.empty_slots += (0..block.slot_count()).filter(|&i| !block.is_slot_filled(i)).count();
+        });
+        stats.deepest_path = root
+            .deepest_constructor_chain()
+            .into_iter()
+            .map(|block| (block.id().clone(), block.display_name()))
+            .collect();
+        stats.max_depth = stats.deepest_path.len();
+        stats
+    }
+
+    /// Blocks and slots from the root down to slot `slot_idx` of `parent_id`, outermost
+    /// first. Empty if the slot is not in the tree.
+    pub fn slot_path(&self, parent_id: &BlockId, slot_idx: usize) -> Vec<SlotPathSegment> {
+        let Some(root) = &self.root_block else {
+            return Vec::new();
+        };
+        let mut steps = vec![(parent_id.clone(), slot_idx)];
+        while let Some(step) = steps.last().and_then(|(id, _)| root.parent_of(id)) {
+            steps.push(step);
+        }
+        steps
+            .into_iter()
+            .rev()
+            .map(|(block_id, slot_index)| {
+                let block = root.find_block(&block_id)?;
+                let slot_label = block.slot_description(slot_index);
+                Some(SlotPathSegment {
+                    block_name: block.display_name(),
+                    has_siblings: block.slot_count() > 1,
+                    block_id,
+                    slot_index,
+                    slot_label,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    /// Replace the whole tree. The old tree is snapshotted for undo; callers in the
+    /// editor confirm with the user before discarding a non-empty tree.
     pub fn set_root_block(&mut self, block: Option<TypeBlock>) {
+        self.push_undo();
         self.root_block = block;
     }
+
+    /// Snapshot the current tree so the next edit can be undone
+    fn push_undo(&mut self) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push((self.next_snapshot, self.root_block.clone()));
+        self.next_snapshot += 1;
+    }
+
+    /// Serial of the snapshot the next undo restores
+    pub fn last_snapshot(&self) -> Option<u64> {
+        self.undo_stack.last().map(|(serial, _)| *serial)
+    }
+
+    /// Restore the tree as it was before the last edit
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some((_, snapshot)) => {
+                self.root_block = snapshot;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn selected_block(&self) -> Option<&BlockId> {
+        self.selected_block.as_ref()
+    }
+
+    /// Select a block for keyboard operations
+    pub fn select_block(&mut self, block_id: Option<BlockId>) {
+        self.selected_block = block_id;
+    }
+
+    pub fn focused(&self) -> Option<&FocusTarget> {
+        self.focused.as_ref()
+    }
+
+    /// Move keyboard focus `step` stops through the tree, wrapping at either end.
+    /// Focus that no longer exists, e.g. after an edit, restarts at the first stop.
+    pub fn move_focus(&mut self, step: isize) -> Option<&FocusTarget> {
+        let mut order = Vec::new();
+        if let Some(root) = &self.root_block {
+            root.focus_order(&mut order);
+        }
+        self.focused = match self.focused.as_ref().and_then(|f| order.iter().position(|stop| stop == f)) {
+            Some(current) => {
+                let next = (current as isize + step).rem_euclid(order.len() as isize) as usize;
+                order.into_iter().nth(next)
+            }
+            None if step < 0 => order.pop(),
+            None => order.into_iter().next(),
+        };
+        self.focused.as_ref()
+    }
+
+    /// Accessible label of the focused block or slot
+    pub fn focused_label(&self) -> Option<String> {
+        let root = self.root_block.as_ref()?;
+        match self.focused.as_ref()? {
+            FocusTarget::Block(id) => root.find_block(id).map(TypeBlock::accessible_label),
+            FocusTarget::Slot(parent_id, index) => {
+                root.find_block(parent_id).map(|parent| parent.slot_accessible_label(*index))
+            }
+        }
+    }
+
+    /// Cycle the primitive block `block_id` to the next type in its group
+    pub fn cycle_primitive(&mut self, block_id: &BlockId) -> bool {
+        self.push_undo();
+        let cycled = self
+            .root_block
+            .as_mut()
+            .and_then(|root| root.find_block_mut(block_id))
+            .map(TypeBlock::cycle_primitive)
+            .unwrap_or(false);
+        if !cycled {
+            self.undo_stack.pop();
+        }
+        cycled
+    }
+
+    /// Swap the constructor block `block_id` for `new_name`, keeping its params
+    pub fn replace_constructor(&mut self, block_id: &BlockId, new_name: &str) -> bool {
+        self.push_undo();
+        let replaced = self
+            .root_block
+            .as_mut()
+            .and_then(|root| root.find_block_mut(block_id))
+            .is_some_and(|block| block.replace_constructor(new_name));
+        if !replaced {
+            self.undo_stack.pop();
+        }
+        replaced
+    }
+
+    /// Rename lifetime param `index` of the constructor block `block_id`
+    pub fn set_lifetime(&mut self, block_id: &BlockId, index: usize, lifetime: &str) -> bool {
+        self.push_undo();
+        let renamed = self
+            .root_block
+            .as_mut()
+            .and_then(|root| root.find_block_mut(block_id))
+            .is_some_and(|block| block.set_lifetime(index, lifetime));
+        if !renamed {
+            self.undo_stack.pop();
+        }
+        renamed
+    }
+
+    /// Put `replacement` where the block `block_id` is, the root included
+    pub fn replace_block(&mut self, block_id: &BlockId, replacement: TypeBlock) -> bool {
+        self.push_undo();
+        match self.root_block.as_mut().and_then(|root| root.find_block_mut(block_id)) {
+            Some(block) => {
+                *block = replacement;
+                true
+            }
+            None => {
+                self.undo_stack.pop();
+                false
+            }
+        }
+    }
+
+    /// Insert an empty slot into the tuple or fn pointer `block_id` before `index`
+    pub fn insert_slot(&mut self, block_id: &BlockId, index: usize) -> bool {
+        self.push_undo();
+        let inserted = self
+            .root_block
+            .as_mut()
+            .and_then(|root| root.find_block_mut(block_id))
+            .is_some_and(|block| block.insert_slot(index));
+        if !inserted {
+            self.undo_stack.pop();
+        }
+        inserted
+    }
+
+    /// Empty a filled slot, returning the removed subtree
+    pub fn clear_slot(&mut self, parent_id: &BlockId, slot_idx: usize) -> Option<TypeBlock> {
+        self.push_undo();
+        let removed = self
+            .root_block
+            .as_mut()
+            .and_then(|root| root.find_block_mut(parent_id))
+            .and_then(|parent| parent.take_slot(slot_idx));
+        if removed.is_none() {
+            self.undo_stack.pop();
+        }
+        removed
+    }
+    
+    pub fn direction(&self) -> LayoutDirection {
+        self.direction
+    }
+
+    pub fn set_direction(&mut self, direction: LayoutDirection) {
+        self.direction = direction;
+    }
+
+    /// Let blocks be dragged out of the canvas, naming `source` as where they came from
+    pub fn set_drag_source(&mut self, source: Option<EntityId>) {
+        self.drag_source = source;
+    }
+
+    pub fn set_show_sizes(&mut self, show_sizes: bool) {
+        self.show_sizes = show_sizes;
+    }
+
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+    }
+
+    /// Replace the lint warnings shown on blocks
+    pub fn set_block_warnings(&mut self, warnings: HashMap<BlockId, String>) {
+        self.block_warnings = Arc::new(warnings);
+    }
+
+    /// Replace the blocks marked as imported
+    pub fn set_imported_blocks(&mut self, imported: HashSet<BlockId>) {
+        self.imported_blocks = Arc::new(imported);
+    }
     
     /// Fill a slot in a block with a new child block
     pub fn fill_slot(&mut self, parent_id: BlockId, slot_idx: usize, child: TypeBlock) -> bool {
-        if let Some(root) = &mut self.root_block {
-            Self::fill_slot_recursive(root, &parent_id, slot_idx, child)
-        } else {
-            false
+        self.push_undo();
+        let filled = match &mut self.root_block {
+            Some(root) => Self::fill_slot_recursive(root, &parent_id, slot_idx, child),
+            None => false,
+        };
+        if !filled {
+            self.undo_stack.pop();
         }
+        filled
     }
     
+    /// Fill several slots as one undo step, building a separate block for each.
+    /// Returns the slots that were filled.
+    pub fn fill_slots(&mut self, targets: &[(BlockId, usize)], make_block: impl Fn() -> TypeBlock) -> Vec<(BlockId, usize)> {
+        self.push_undo();
+        let mut filled = Vec::new();
+        if let Some(root) = &mut self.root_block {
+            for (parent_id, slot_idx) in targets {
+                if Self::fill_slot_recursive(root, parent_id, *slot_idx, make_block()) {
+                    filled.push((parent_id.clone(), *slot_idx));
+                }
+            }
+        }
+        if filled.is_empty() {
+            self.undo_stack.pop();
+        }
+        filled
+    }
+
     fn fill_slot_recursive(block: &mut TypeBlock, parent_id: &BlockId, slot_idx: usize, child: TypeBlock) -> bool {
         // Check if this is the parent block
         if block.id() == parent_id {
@@ -107,12 +478,54 @@ impl BlockCanvas {
                     }
                 }
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                for nested in params.iter_mut().chain(std::iter::once(return_type)).flatten() {
+                    if Self::fill_slot_recursive(nested, parent_id, slot_idx, child.clone()) {
+                        return true;
+                    }
+                }
+            }
             _ => {}
         }
         
         false
     }
 
+    /// Replace every subtree structurally equal to `pattern`, including the root.
+    /// Returns the number of replacements.
+    pub fn replace_matching(&mut self, pattern: &TypeAstNode, replacement: &TypeAstNode) -> usize {
+        self.push_undo();
+        let Some(root) = &mut self.root_block else {
+            self.undo_stack.pop();
+            return 0;
+        };
+
+        let root_matches = root
+            .to_ast()
+            .map(|ast| crate::type_ast::structural_eq(&ast, pattern))
+            .unwrap_or(false);
+        if root_matches {
+            *root = TypeBlock::from_ast(replacement);
+            return 1;
+        }
+
+        let count = root.replace_matching(pattern, replacement);
+        if count == 0 {
+            self.undo_stack.pop();
+        }
+        count
+    }
+
+    /// Rename a generic type param on every leaf that uses it. Always snapshots, so the
+    /// editor can undo the declaration rename even when the tree had no uses.
+    pub fn rename_type_param(&mut self, from: &str, to: &str) -> usize {
+        self.push_undo();
+        self.root_block
+            .as_mut()
+            .map(|root| root.rename_type_param(from, to))
+            .unwrap_or(0)
+    }
+
     /// Start dragging a block from the palette
     pub fn start_drag_from_palette(&mut self, block: TypeBlock, position: Point<Pixels>) {
         self.drag_state = Some(DragState {
@@ -171,12 +584,12 @@ impl BlockCanvas {
     }
 
     /// Find where a block would be dropped at the given position
-    fn find_drop_target(&self, _position: Point<Pixels>) -> Option<DropTarget> {
-        // If hover_slot is set, use that
-        if let Some((block_id, slot_index)) = &self.hover_slot {
+    fn find_drop_target(&self, position: Point<Pixels>) -> Option<DropTarget> {
+        // If hover_slot is set, use that, else the empty slot under the pointer
+        if let Some((block_id, slot_index)) = self.hover_slot.clone().or_else(|| self.slot_at(position)) {
             return Some(DropTarget::Slot {
-                parent_block_id: block_id.clone(),
-                slot_index: *slot_index,
+                parent_block_id: block_id,
+                slot_index,
             });
         }
 
@@ -225,14 +638,35 @@ impl BlockCanvas {
         }
     }
 
+    /// Bounds of every rendered block after the last layout pass, in window coordinates.
+    /// Lets layout and hit-testing be asserted in headless tests.
+    pub fn block_bounds(&self) -> HashMap<BlockId, Bounds<Pixels>> {
+        self.layout.lock().map(|layout| layout.blocks.clone()).unwrap_or_default()
+    }
+
+    /// Bounds of every rendered empty slot after the last layout pass, keyed by (parent_id, slot_index)
+    pub fn slot_bounds(&self) -> HashMap<(BlockId, usize), Bounds<Pixels>> {
+        self.layout.lock().map(|layout| layout.slots.clone()).unwrap_or_default()
+    }
+
+    /// Empty slot under a window position, using the last layout pass
+    pub fn slot_at(&self, position: Point<Pixels>) -> Option<(BlockId, usize)> {
+        self.layout
+            .lock()
+            .ok()?
+            .slots
+            .iter()
+            .find(|(_, bounds)| bounds.contains(&position))
+            .map(|(slot, _)| slot.clone())
+    }
+
     /// Render the canvas
-    pub fn render_with_handlers(
-        &self, 
-        cx: &App, 
-        on_slot_click: Option<Arc<dyn Fn(BlockId, usize) + Send + Sync + 'static>>,
-        on_empty_click: Option<Arc<dyn Fn() + Send + Sync + 'static>>
-    ) -> impl IntoElement {
+    pub fn render_with_handlers(&self, cx: &App, handlers: CanvasHandlers) -> impl IntoElement {
         let theme = cx.theme();
+        // Blocks re-record their bounds as they are laid out
+        if let Ok(mut layout) = self.layout.lock() {
+            *layout = BlockLayout::default();
+        }
         
         v_flex()
             .flex_1()
@@ -245,24 +679,58 @@ impl BlockCanvas {
             .p_6()
             .child(
                 if let Some(root) = &self.root_block {
-                    self.render_block_tree(root, cx, on_slot_click)
+                    self.render_block_tree(root, cx, handlers)
                 } else {
-                    self.render_empty_state(cx, on_empty_click)
+                    self.render_empty_state(cx, handlers.empty_click)
                 }
             )
+            .when(self.root_block.is_some(), |this| {
+                this.child(self.render_connectors(theme.border))
+            })
             .when(self.drag_state.is_some(), |this| {
                 this.child(self.render_drag_preview(cx))
             })
     }
     
     /// Render the canvas (convenience method without empty click handler)
-    pub fn render(&self, cx: &App, on_slot_click: Option<Arc<dyn Fn(BlockId, usize) + Send + Sync + 'static>>) -> impl IntoElement {
-        self.render_with_handlers(cx, on_slot_click, None)
+    pub fn render(&self, cx: &App, on_slot_click: Option<SlotHandler>) -> impl IntoElement {
+        self.render_with_handlers(cx, CanvasHandlers { slot_click: on_slot_click, ..Default::default() })
     }
 
-    fn render_empty_state(&self, cx: &App, on_empty_click: Option<Arc<dyn Fn() + Send + Sync + 'static>>) -> Div {
+    fn render_empty_state(&self, cx: &App, on_empty_click: Option<EmptyStateHandler>) -> Div {
         let is_drag_over = self.drag_state.is_some();
         
+        // Small clickable pill reporting `choice`
+        let chip = |label: String, choice: EmptyStateChoice| {
+            div()
+                .px_3()
+                .py_1()
+                .rounded(px(12.0))
+                .text_sm()
+                .bg(cx.theme().secondary.opacity(0.5))
+                .text_color(cx.theme().foreground)
+                .border_1()
+                .border_color(cx.theme().border)
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().secondary))
+                .child(label)
+                .when_some(on_empty_click.clone(), |this, handler| {
+                    this.on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                        handler(choice.clone(), window, cx);
+                        window.refresh();
+                        cx.stop_propagation();
+                    })
+                })
+        };
+        let section = |title: &'static str| {
+            div()
+                .text_xs()
+                .font_semibold()
+                .text_color(cx.theme().muted_foreground)
+                .child(title)
+        };
+        let pick_root = on_empty_click.clone();
+        
         v_flex()
             .size_full()
             .items_center()
@@ -295,9 +763,10 @@ impl BlockCanvas {
                         this.bg(cx.theme().secondary.opacity(0.5))
                             .border_color(cx.theme().muted_foreground.opacity(0.5))
                     })
-                    .when_some(on_empty_click, |this, handler| {
-                        this.on_mouse_down(MouseButton::Left, move |_, _, cx| {
-                            handler();
+                    .when_some(pick_root, |this, handler| {
+                        this.on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                            handler(EmptyStateChoice::PickRoot, window, cx);
+                            window.refresh();
                             cx.stop_propagation();
                         })
                     })
@@ -329,9 +798,46 @@ impl BlockCanvas {
                             })
                     )
             )
+            .when(on_empty_click.is_some() && !is_drag_over, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(chip("🎯 Pick a root type".to_string(), EmptyStateChoice::PickRoot))
+                        .child(chip("📥 Import from Rust".to_string(), EmptyStateChoice::ImportRust))
+                        .child(chip("🪝 Build a callback".to_string(), EmptyStateChoice::Callback))
+                )
+                .child(section("Start from template"))
+                .child(
+                    h_flex()
+                        .w(px(500.0))
+                        .flex_wrap()
+                        .justify_center()
+                        .gap_2()
+                        .children(crate::examples::templates().iter().enumerate().map(|(i, template)| {
+                            use ui_common::command_palette::PaletteItem;
+                            chip(template.name().to_string(), EmptyStateChoice::Template(i))
+                        }))
+                )
+                .child(section("Examples"))
+                .child(
+                    h_flex()
+                        .w(px(500.0))
+                        .flex_wrap()
+                        .justify_center()
+                        .gap_2()
+                        .children(crate::examples::EXAMPLE_ALIASES.iter().enumerate().map(|(i, (label, source))| {
+                            chip(format!("{} · {}", label, source), EmptyStateChoice::Example(i))
+                        }))
+                )
+            })
     }
 
-    fn render_block_tree(&self, block: &TypeBlock, _cx: &App, on_slot_click: Option<Arc<dyn Fn(BlockId, usize) + Send + Sync + 'static>>) -> Div {
+    fn render_block_tree(
+        &self,
+        block: &TypeBlock,
+        cx: &App,
+        handlers: CanvasHandlers,
+    ) -> Div {
         use crate::type_block::TypeBlockView;
         
         let mut view = TypeBlockView::new(
@@ -339,9 +845,24 @@ impl BlockCanvas {
             "canvas-root"
         );
         
-        if let Some(handler) = on_slot_click {
-            view = view.on_slot_click(move |id, idx| handler(id, idx));
-        }
+        view = view
+            .on_slot_click(handlers.slot_click)
+            .warnings(self.block_warnings.clone(), handlers.dismiss_warning)
+            .on_alias_open(handlers.alias_open)
+            .on_slot_clear(handlers.slot_clear)
+            .on_constructor_swap(handlers.constructor_swap)
+            .on_slot_insert(handlers.slot_insert)
+            .on_lifetime_edit(handlers.lifetime_edit)
+            .high_contrast(self.high_contrast)
+            .direction(self.direction)
+            .record_layout(Some(self.layout.clone()))
+            .on_block_select(handlers.block_select)
+            .selected(self.selected_block.clone())
+            .focused(self.focused.clone())
+            .show_sizes(self.show_sizes)
+            .imported(self.imported_blocks.clone())
+            .drag_source(self.drag_source)
+            .theme(BlockTheme::from_app(cx));
         
         v_flex()
             .h_full()
@@ -351,7 +872,39 @@ impl BlockCanvas {
             .child(view)
     }
 
-    fn render_drag_preview(&self, _cx: &App) -> Div {
+    /// Lines from each constructor to its slots, painted over the tree from the bounds the
+    /// blocks recorded this frame, so they follow the layout direction
+    fn render_connectors(&self, color: Hsla) -> impl IntoElement {
+        let layout = self.layout.clone();
+        let root = self.root_block.clone();
+        let direction = self.direction;
+        let thickness = px(1.5);
+        canvas(
+            |_, _, _| {},
+            move |_, _, window, _cx| {
+                let (Some(root), Ok(layout)) = (&root, layout.lock()) else {
+                    return;
+                };
+                // Both legs run right or down from their first point, whichever way they turn
+                for [start, corner, end] in layout.connectors(root, direction) {
+                    window.paint_quad(fill(
+                        Bounds::from_corners(start, point(corner.x + thickness, corner.y + thickness)),
+                        color,
+                    ));
+                    window.paint_quad(fill(
+                        Bounds::from_corners(corner, point(end.x + thickness, end.y + thickness)),
+                        color,
+                    ));
+                }
+            },
+        )
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+    }
+
+    fn render_drag_preview(&self, cx: &App) -> Div {
         if let Some(drag) = &self.drag_state {
             if let Some(block) = &drag.dragging_block {
                 use crate::type_block::TypeBlockView;
@@ -367,6 +920,7 @@ impl BlockCanvas {
                             block.clone(),
                             "drag-preview"
                         )
+                        .theme(BlockTheme::from_app(cx))
                     );
             }
         }
@@ -374,3 +928,77 @@ impl BlockCanvas {
         div()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen;
+
+    /// Every block id in the tree, in walk order
+    fn block_ids(block: &TypeBlock) -> Vec<BlockId> {
+        let mut ids = Vec::new();
+        block.walk(&mut |block| ids.push(block.id().clone()));
+        ids
+    }
+
+    #[test]
+    fn undo_restores_a_cleared_subtree() {
+        // Option<Vec<Box<u8>>>: the slot of Option holds a three level subtree
+        let mut boxed = TypeBlock::constructor("Box", 1);
+        boxed.set_slot(0, TypeBlock::primitive("u8"));
+        let mut vec = TypeBlock::constructor("Vec", 1);
+        vec.set_slot(0, boxed);
+        let mut option = TypeBlock::constructor("Option", 1);
+        option.set_slot(0, vec);
+        let root_id = option.id().clone();
+        let original_ids = block_ids(&option);
+        let mut canvas = BlockCanvas::with_root(option);
+
+        let removed = canvas.clear_slot(&root_id, 0).expect("slot was filled");
+        assert_eq!(codegen::ast_to_rust_string(&removed.to_ast_with_holes()), "Vec<Box<u8>>");
+        assert_eq!(block_ids(&removed), original_ids[1..]);
+        let root = canvas.root_block().unwrap();
+        assert!(!root.is_slot_filled(0));
+        assert_eq!(block_ids(root), [root_id.clone()]);
+
+        assert!(canvas.undo());
+        let root = canvas.root_block().unwrap();
+        assert_eq!(codegen::ast_to_rust_string(&root.to_ast_with_holes()), "Option<Vec<Box<u8>>>");
+        assert_eq!(block_ids(root), original_ids);
+        assert!(!canvas.can_undo());
+    }
+
+    #[test]
+    fn clearing_an_empty_slot_leaves_no_undo_step() {
+        let option = TypeBlock::constructor("Option", 1);
+        let root_id = option.id().clone();
+        let mut canvas = BlockCanvas::with_root(option);
+        assert!(canvas.clear_slot(&root_id, 0).is_none());
+        assert!(!canvas.can_undo());
+    }
+
+    #[gpui::test]
+    fn dragging_onto_an_empty_slot_drops_into_it(cx: &mut gpui::TestAppContext) {
+        cx.update(ui::init);
+        let result = TypeBlock::constructor("Result", 2);
+        let root_id = result.id().clone();
+        let mut canvas = BlockCanvas::with_root(result);
+
+        let cx = cx.add_empty_window();
+        cx.draw(point(px(0.0), px(0.0)), size(px(800.0), px(600.0)), |_, cx| {
+            canvas.render(cx, None).into_any_element()
+        });
+        let slots = canvas.slot_bounds();
+        assert_eq!(slots.len(), 2);
+        let err_slot = slots[&(root_id.clone(), 1)];
+        assert_eq!(canvas.slot_at(err_slot.center()), Some((root_id.clone(), 1)));
+        assert_eq!(canvas.slot_at(point(px(799.0), px(599.0))), None);
+
+        canvas.start_drag_from_palette(TypeBlock::primitive("String"), point(px(0.0), px(0.0)));
+        canvas.update_drag(err_slot.center());
+        assert!(canvas.end_drag());
+        let root = canvas.root_block().unwrap();
+        assert!(!root.is_slot_filled(0));
+        assert_eq!(root.get_slot(1).map(TypeBlock::display_name).as_deref(), Some("String"));
+    }
+}
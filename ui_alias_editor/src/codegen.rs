@@ -0,0 +1,1420 @@
+// Rust code generation for type alias assets
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::punctuated::Punctuated;
+use ui_types_common::{AliasAsset, TypeAstNode};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::project;
+use crate::type_ast::{self, SELF_TYPE};
+
+/// Key in `AliasAsset.meta` holding the export options
+const EXPORT_META_KEY: &str = "export";
+
+/// Key in `AliasAsset.meta` holding the shared output module path (`"src/types.rs"`)
+const OUTPUT_MODULE_META_KEY: &str = "outputModule";
+
+/// Key in `AliasAsset.meta` holding default values for const generics (`{ "BUF": "1024" }`)
+const CONST_DEFAULTS_META_KEY: &str = "constDefaults";
+
+/// Key in `AliasAsset.meta` listing the generic type params the alias declares (`["T"]`)
+const GENERICS_META_KEY: &str = "generics";
+
+/// Key in `AliasAsset.meta` holding trait bounds per generic param: `{"T": ["Clone", "Send"]}`
+const BOUNDS_META_KEY: &str = "bounds";
+
+/// Key in `AliasAsset.meta` naming the item the alias is declared in: `"trait"` or `"impl"`
+const CONTEXT_META_KEY: &str = "context";
+
+/// Keys in `AliasAsset.meta` marking API status: the stability level (`"unstable"`) and
+/// the version the alias appeared in (`"1.2.0"`)
+const STABILITY_META_KEY: &str = "stability";
+const SINCE_META_KEY: &str = "since";
+
+/// Key in `AliasAsset.meta` holding the `cfg` predicate the alias is compiled under,
+/// e.g. `"target_os = \"windows\""`
+const CFG_META_KEY: &str = "cfg";
+
+/// Start of the doc line that carries the stability marker, read back by the importer
+const STABILITY_DOC_PREFIX: &str = "Stability: ";
+
+/// Built-in layout of an alias item, see `ExportOptions::template`
+pub const DEFAULT_ALIAS_TEMPLATE: &str = "{docs}{attrs}{visibility}type {name} = {type};\n";
+
+/// Placeholders a template may use, and the ones it must use. A template without
+/// `{attrs}` gets the `#[cfg]` and stability attributes in front of its output instead.
+const TEMPLATE_PLACEHOLDERS: &[&str] = &["name", "type", "docs", "attrs", "visibility"];
+const REQUIRED_TEMPLATE_PLACEHOLDERS: &[&str] = &["name", "type"];
+
+/// Trait-object constructors whose params are associated type bindings
+/// (`dyn Future<Output = T>`) rather than positional generics
+pub const ASSOCIATED_TYPE_CONSTRUCTORS: &[(&str, &[&str])] = &[
+    ("dyn Future", &["Output"]),
+    ("dyn Iterator", &["Item"]),
+];
+
+/// Constructors outside the prelude that generated code imports when a tree uses them
+const STD_IMPORTS: &[(&str, &str)] = &[
+    ("Pin", "std::pin::Pin"),
+    ("dyn Future", "std::future::Future"),
+];
+
+/// Closure trait objects, written `dyn Fn(A, B) -> R`. Their params are the argument
+/// types followed by the return type.
+pub const FN_TRAIT_CONSTRUCTORS: &[&str] = &["dyn Fn", "dyn FnMut", "dyn FnOnce"];
+
+/// What kind of item an alias is exported as
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportMode {
+    /// `pub type Name = T;`
+    #[default]
+    Alias,
+    /// A tuple struct with one public field per tuple element, or a newtype
+    /// around a non-tuple root: `pub struct Name(pub T);`
+    Struct,
+}
+
+/// Visibility of the generated item
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Crate,
+    Super,
+    Private,
+    /// `pub(in path)`; the path must start with `crate`, `self` or `super`
+    In(String),
+}
+
+impl Visibility {
+    /// The choices offered by the editor's selector, in the order it cycles through them
+    pub const CHOICES: [Visibility; 4] = [Visibility::Public, Visibility::Crate, Visibility::Super, Visibility::Private];
+
+    /// Keyword to put before `type`/`struct`/`const`, with its trailing space
+    pub fn prefix(&self) -> String {
+        match self {
+            Visibility::Public => "pub ".to_string(),
+            Visibility::Crate => "pub(crate) ".to_string(),
+            Visibility::Super => "pub(super) ".to_string(),
+            Visibility::Private => String::new(),
+            Visibility::In(path) => format!("pub(in {}) ", path.trim()),
+        }
+    }
+
+    /// Short label for the selector
+    pub fn label(&self) -> String {
+        match self {
+            Visibility::Private => "private".to_string(),
+            other => other.prefix().trim_end().to_string(),
+        }
+    }
+
+    /// Check that a `pub(in path)` path is one Rust accepts
+    pub fn validate(&self) -> Result<(), String> {
+        let Visibility::In(path) = self else {
+            return Ok(());
+        };
+        let parsed = syn::parse_str::<syn::Path>(path.trim()).map_err(|_| format!("`{}` is not a module path", path.trim()))?;
+        match parsed.segments.first().map(|segment| segment.ident.to_string()).as_deref() {
+            Some("crate" | "self" | "super") => Ok(()),
+            _ => Err(format!("`pub(in {})` must start with `crate`, `self` or `super`", path.trim())),
+        }
+    }
+}
+
+/// Serde attributes for struct exports
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SerdeOptions {
+    /// `#[derive(serde::Serialize, serde::Deserialize)]`; the other options need it
+    pub derive: bool,
+    /// `#[serde(transparent)]`, only emitted for single-field structs
+    pub transparent: bool,
+    /// `#[serde(rename = "...")]`
+    pub rename: Option<String>,
+}
+
+/// API status of an alias, stored in its meta
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stability {
+    /// Free text; `"stable"`, `"unstable"`, `"experimental"` and `"deprecated"` are usual
+    pub level: Option<String>,
+    pub since: Option<String>,
+}
+
+impl Stability {
+    pub fn from_meta(meta: &serde_json::Value) -> Self {
+        let field = |key| {
+            meta.get(key)
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            level: field(STABILITY_META_KEY),
+            since: field(SINCE_META_KEY),
+        }
+    }
+
+    /// Store the marker in the meta, removing keys that are unset
+    pub fn write_to_meta(&self, meta: &mut serde_json::Value) {
+        let Some(map) = meta.as_object_mut() else {
+            return;
+        };
+        for (key, value) in [(STABILITY_META_KEY, &self.level), (SINCE_META_KEY, &self.since)] {
+            match value {
+                Some(value) => map.insert(key.to_string(), serde_json::json!(value)),
+                None => map.remove(key),
+            };
+        }
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.level.as_deref() == Some("deprecated")
+    }
+
+    /// `unstable since 1.2.0`, `since 1.2.0` or `unstable`; `None` when nothing is set
+    pub fn label(&self) -> Option<String> {
+        match (&self.level, &self.since) {
+            (Some(level), Some(since)) => Some(format!("{} since {}", level, since)),
+            (Some(level), None) => Some(level.clone()),
+            (None, Some(since)) => Some(format!("since {}", since)),
+            (None, None) => None,
+        }
+    }
+
+    /// Read a doc line written by `generate_alias_code`, e.g. `Stability: unstable since 1.2.0`
+    pub fn from_doc_line(line: &str) -> Option<Self> {
+        let label = line.trim().strip_prefix(STABILITY_DOC_PREFIX)?.trim();
+        let (level, since) = match label.strip_prefix("since ") {
+            Some(since) => ("", since),
+            None => label.split_once(" since ").unwrap_or((label, "")),
+        };
+        let value = |text: &str| (!text.trim().is_empty()).then(|| text.trim().to_string());
+        let stability = Self { level: value(level), since: value(since) };
+        stability.label().is_some().then_some(stability)
+    }
+
+    /// Doc lines or attribute put in front of the item, following `options.stability_marker`
+    fn preamble(&self, options: &ExportOptions) -> String {
+        let Some(label) = self.label() else {
+            return String::new();
+        };
+        let doc = format!("/// {}{}\n", STABILITY_DOC_PREFIX, label);
+        match options.stability_marker {
+            StabilityMarker::Omit => String::new(),
+            StabilityMarker::Doc => doc,
+            StabilityMarker::Attribute if self.is_deprecated() => match &self.since {
+                Some(since) => format!("#[deprecated(since = {:?})]\n", since),
+                None => "#[deprecated]\n".to_string(),
+            },
+            StabilityMarker::Attribute => match &options.stability_attribute {
+                Some(template) => {
+                    let attribute = fill_placeholders(
+                        template,
+                        &[
+                            ("stability", self.level.clone().unwrap_or_else(|| "stable".to_string())),
+                            ("since", self.since.clone().unwrap_or_default()),
+                        ],
+                    );
+                    format!("{}\n", attribute.trim_end())
+                }
+                // Only std may use `#[stable]`, so without a project attribute the doc line stays
+                None => doc,
+            },
+        }
+    }
+}
+
+/// How the stability marker appears in generated code
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StabilityMarker {
+    /// A `/// Stability: ...` doc line
+    #[default]
+    Doc,
+    /// `#[deprecated(since = "...")]` for deprecated aliases, otherwise the project's
+    /// `stabilityAttribute`, falling back to the doc line
+    Attribute,
+    Omit,
+}
+
+/// Options controlling the generated `.rs` output
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    /// Emit `pub const` items for const generic defaults used in the tree
+    pub emit_consts: bool,
+    pub mode: ExportMode,
+    /// `pub` unless the meta says otherwise, as before visibility was configurable
+    pub visibility: Visibility,
+    /// Only used by struct exports; plain aliases can't carry attributes
+    pub serde: SerdeOptions,
+    /// `From` impls between a single-field struct export and its field type, both ways
+    pub conversions: bool,
+    /// Project template for alias items, from `alias_editor.json`. Not stored in the meta.
+    #[serde(skip)]
+    pub template: Option<String>,
+    pub stability_marker: StabilityMarker,
+    /// Project attribute for stability markers, from `alias_editor.json`. Not stored in the meta.
+    #[serde(skip)]
+    pub stability_attribute: Option<String>,
+}
+
+impl ExportOptions {
+    /// Read export options stored in the asset meta, falling back to defaults
+    pub fn from_meta(meta: &serde_json::Value) -> Self {
+        meta.get(EXPORT_META_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Use the project's alias template and stability attribute, if it has them
+    pub fn with_template(mut self, config: &project::ProjectTypeConfig) -> Self {
+        self.template = config.alias_template.clone();
+        self.stability_attribute = config.stability_attribute.clone();
+        self
+    }
+
+    /// Store the export options in the asset meta so they persist with the file
+    pub fn write_to_meta(&self, meta: &mut serde_json::Value) {
+        if let (Some(map), Ok(value)) = (meta.as_object_mut(), serde_json::to_value(self)) {
+            map.insert(EXPORT_META_KEY.to_string(), value);
+        }
+    }
+}
+
+/// Path of the generated `.rs` file for an alias asset file
+pub fn generated_rs_path(alias_path: &Path) -> PathBuf {
+    alias_path.with_extension("rs")
+}
+
+/// Shared output module for an alias, if its meta sets one. Relative module paths are
+/// resolved against the project root, or the alias file's directory outside a project.
+pub fn output_module_path(meta: &serde_json::Value, alias_path: &Path) -> Option<PathBuf> {
+    let module = meta.get(OUTPUT_MODULE_META_KEY)?.as_str()?;
+    let module = Path::new(module);
+    if module.is_absolute() {
+        return Some(module.to_path_buf());
+    }
+
+    let base = project::find_project_root(alias_path)
+        .or_else(|| alias_path.parent().map(Path::to_path_buf))?;
+    Some(base.join(module))
+}
+
+/// Path the generated code for an alias is written to
+pub fn output_path(meta: &serde_json::Value, alias_path: &Path) -> PathBuf {
+    output_module_path(meta, alias_path).unwrap_or_else(|| generated_rs_path(alias_path))
+}
+
+/// Generic type params declared in the asset meta, in declaration order
+pub fn declared_generics(meta: &serde_json::Value) -> Vec<String> {
+    meta.get(GENERICS_META_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Store the declared generic type params in the asset meta, dropping the key when empty
+pub fn set_declared_generics(meta: &mut serde_json::Value, params: &[String]) {
+    if let Some(map) = meta.as_object_mut() {
+        if params.is_empty() {
+            map.remove(GENERICS_META_KEY);
+        } else {
+            map.insert(GENERICS_META_KEY.to_string(), serde_json::json!(params));
+        }
+    }
+}
+
+/// Trait bounds per generic param from the asset meta, declared params first in
+/// declaration order. Params without bounds are left out.
+pub fn generic_bounds(meta: &serde_json::Value) -> Vec<(String, Vec<String>)> {
+    let mut bounds: BTreeMap<String, Vec<String>> = meta
+        .get(BOUNDS_META_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default();
+    let mut ordered: Vec<(String, Vec<String>)> = declared_generics(meta)
+        .into_iter()
+        .filter_map(|param| bounds.remove_entry(&param))
+        .collect();
+    ordered.extend(bounds);
+    ordered.retain(|(_, param_bounds)| !param_bounds.is_empty());
+    ordered
+}
+
+/// Move the bounds of a generic param to its new name
+pub fn rename_generic_bounds(meta: &mut serde_json::Value, from: &str, to: &str) {
+    let Some(bounds) = meta.get_mut(BOUNDS_META_KEY).and_then(serde_json::Value::as_object_mut) else {
+        return;
+    };
+    if let Some(value) = bounds.remove(from) {
+        bounds.insert(to.to_string(), value);
+    }
+}
+
+/// The `cfg` predicate from the meta; `None` when unset or blank
+pub fn cfg_predicate(meta: &serde_json::Value) -> Option<String> {
+    meta.get(CFG_META_KEY)
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|predicate| !predicate.is_empty())
+        .map(str::to_string)
+}
+
+/// Store the `cfg` predicate in the meta, dropping the key when unset
+pub fn set_cfg_predicate(meta: &mut serde_json::Value, predicate: Option<&str>) {
+    if let Some(map) = meta.as_object_mut() {
+        match predicate.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(predicate) => map.insert(CFG_META_KEY.to_string(), serde_json::json!(predicate)),
+            None => map.remove(CFG_META_KEY),
+        };
+    }
+}
+
+/// `#[cfg(...)]` line for the alias, or an empty string without a predicate
+fn cfg_attribute(meta: &serde_json::Value) -> String {
+    cfg_predicate(meta)
+        .map(|predicate| format!("#[cfg({})]\n", predicate))
+        .unwrap_or_default()
+}
+
+/// Check a `cfg` predicate and print it back with the usual spacing, e.g.
+/// `all(unix, target_arch = "x86_64")`. Options are single identifiers with an optional
+/// string value, combined with `all`, `any` and `not`.
+pub fn format_cfg_predicate(predicate: &str) -> Result<String, String> {
+    let meta = syn::parse_str::<syn::Meta>(predicate.trim())
+        .map_err(|e| format!("`{}` is not a cfg predicate: {}", predicate.trim(), e))?;
+    cfg_meta_to_string(&meta)
+}
+
+fn cfg_meta_to_string(meta: &syn::Meta) -> Result<String, String> {
+    let option = |path: &syn::Path| {
+        path.get_ident()
+            .map(ToString::to_string)
+            .ok_or_else(|| format!("`{}` is not a cfg option", path.to_token_stream().to_string().replace(' ', "")))
+    };
+    match meta {
+        syn::Meta::Path(path) => option(path),
+        syn::Meta::NameValue(pair) => match &pair.value {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(value), .. }) => {
+                Ok(format!("{} = {:?}", option(&pair.path)?, value.value()))
+            }
+            _ => Err(format!("the value of `{}` must be a string", option(&pair.path)?)),
+        },
+        syn::Meta::List(list) => {
+            let operator = option(&list.path)?;
+            let args = list
+                .parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+                .map_err(|e| format!("in `{}(...)`: {}", operator, e))?
+                .iter()
+                .map(cfg_meta_to_string)
+                .collect::<Result<Vec<_>, _>>()?;
+            match operator.as_str() {
+                "not" if args.len() != 1 => Err("`not(...)` takes exactly one predicate".to_string()),
+                "all" | "any" | "not" => Ok(format!("{}({})", operator, args.join(", "))),
+                _ => Err(format!("`{}(...)` is not a cfg operator; use all, any or not", operator)),
+            }
+        }
+    }
+}
+
+/// Drop the bounds of a generic param that is no longer declared
+pub fn remove_generic_bounds(meta: &mut serde_json::Value, param: &str) {
+    if let Some(bounds) = meta.get_mut(BOUNDS_META_KEY).and_then(serde_json::Value::as_object_mut) {
+        bounds.remove(param);
+    }
+}
+
+/// `where` clause for the generic bounds, starting on a new line; empty without bounds
+fn where_clause(meta: &serde_json::Value) -> String {
+    let predicates: Vec<String> = generic_bounds(meta)
+        .iter()
+        .map(|(param, bounds)| format!("    {}: {},\n", param, bounds.join(" + ")))
+        .collect();
+    if predicates.is_empty() {
+        String::new()
+    } else {
+        format!("\nwhere\n{}", predicates.concat())
+    }
+}
+
+/// Whether the alias is declared inside a trait or impl, where `Self` is valid
+pub fn has_self_context(meta: &serde_json::Value) -> bool {
+    matches!(
+        meta.get(CONTEXT_META_KEY).and_then(serde_json::Value::as_str),
+        Some("trait" | "impl")
+    )
+}
+
+/// Render a type AST as Rust source
+pub fn ast_to_rust_string(ast: &TypeAstNode) -> String {
+    match ast {
+        TypeAstNode::Primitive { name } => name.clone(),
+        TypeAstNode::Path { path } => type_ast::const_arg_expr(ast).map_or_else(|| path.clone(), str::to_string),
+        TypeAstNode::AliasRef { alias } => alias.clone(),
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => {
+            if let Some((_, bindings)) = ASSOCIATED_TYPE_CONSTRUCTORS.iter().find(|(n, _)| *n == name.as_str()) {
+                let args = bindings
+                    .iter()
+                    .zip(params)
+                    .map(|(binding, param)| format!("{} = {}", binding, ast_to_rust_string(param)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return format!("{}<{}>", name, args);
+            }
+            if let Some((output, inputs)) = params.split_last().filter(|_| FN_TRAIT_CONSTRUCTORS.contains(&name.as_str())) {
+                let inputs = inputs.iter().map(ast_to_rust_string).collect::<Vec<_>>().join(", ");
+                return format!("{}({}) -> {}", name, inputs, ast_to_rust_string(output));
+            }
+
+            let args = lifetimes
+                .iter()
+                .map(|l| format!("'{}", l.trim_start_matches('\'')))
+                .chain(params.iter().map(ast_to_rust_string))
+                .chain(const_generics.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{}<{}>", name, args)
+        }
+        TypeAstNode::Tuple { elements } => {
+            let elements_str = elements
+                .iter()
+                .map(ast_to_rust_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            // A one-element tuple needs its trailing comma, `(T)` is just `T`
+            if elements.len() == 1 {
+                format!("({},)", elements_str)
+            } else {
+                format!("({})", elements_str)
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            let params_str = params
+                .iter()
+                .map(ast_to_rust_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("fn({}) -> {}", params_str, ast_to_rust_string(return_type))
+        }
+        TypeAstNode::None => "_".to_string(),
+    }
+}
+
+/// Collect the const generic identifiers used anywhere in the tree, in order of first use
+pub fn collect_const_generics(ast: &TypeAstNode, out: &mut Vec<String>) {
+    match ast {
+        TypeAstNode::Constructor { params, const_generics, .. } => {
+            for param in params {
+                collect_const_generics(param, out);
+            }
+            for name in const_generics {
+                if is_const_ident(name) && !out.contains(name) {
+                    out.push(name.clone());
+                }
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            for element in elements {
+                collect_const_generics(element, out);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            for param in params {
+                collect_const_generics(param, out);
+            }
+            collect_const_generics(return_type, out);
+        }
+        TypeAstNode::Path { .. } => {
+            if let Some(expr) = type_ast::const_arg_expr(ast).filter(|expr| is_const_ident(expr)) {
+                if !out.iter().any(|name| name == expr) {
+                    out.push(expr.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Const generic arguments can be literals (`32`) or named constants (`BUF`);
+/// only named ones need a materialized item.
+fn is_const_ident(value: &str) -> bool {
+    value
+        .chars()
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_')
+        .unwrap_or(false)
+}
+
+/// Default values for const generics stored in the asset meta
+pub fn const_defaults(meta: &serde_json::Value) -> Vec<(String, String)> {
+    meta.get(CONST_DEFAULTS_META_KEY)
+        .and_then(|value| value.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(name, value)| match value {
+                    serde_json::Value::String(s) => Some((name.clone(), s.clone())),
+                    serde_json::Value::Number(n) => Some((name.clone(), n.to_string())),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check a template uses only known placeholders and has `{name}` and `{type}`
+pub fn validate_template(template: &str) -> Result<(), String> {
+    let used = template_placeholders(template);
+    if let Some(unknown) = used.iter().find(|p| !TEMPLATE_PLACEHOLDERS.contains(p)) {
+        return Err(format!("unknown placeholder {{{}}}", unknown));
+    }
+    let missing: Vec<String> = REQUIRED_TEMPLATE_PLACEHOLDERS
+        .iter()
+        .filter(|p| !used.contains(p))
+        .map(|p| format!("{{{}}}", p))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("missing {}", missing.join(" and ")))
+    }
+}
+
+/// A run of literal text or a `{name}` placeholder in a template
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into text and placeholders. `{{` and `}}` stand for literal braces,
+/// as in `format!`; a brace without a partner is kept as text.
+fn template_parts(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        parts.push(TemplatePart::Text(&rest[..start]));
+        let brace = &rest[start..start + 1];
+        let after = &rest[start + 1..];
+        if after.starts_with(brace) {
+            parts.push(TemplatePart::Text(brace));
+            rest = &after[1..];
+        } else if let Some(len) = after.find('}').filter(|_| brace == "{") {
+            parts.push(TemplatePart::Placeholder(&after[..len]));
+            rest = &after[len + 1..];
+        } else {
+            parts.push(TemplatePart::Text(brace));
+            rest = after;
+        }
+    }
+    parts.push(TemplatePart::Text(rest));
+    parts
+}
+
+/// Names inside `{...}` in a template, in order
+fn template_placeholders(template: &str) -> Vec<&str> {
+    template_parts(template)
+        .into_iter()
+        .filter_map(|part| match part {
+            TemplatePart::Placeholder(name) => Some(name),
+            TemplatePart::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Substitute `values` in one pass, so braces inside a value are never read as
+/// placeholders. Unknown placeholders are left as written.
+fn fill_placeholders(template: &str, values: &[(&str, String)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    for part in template_parts(template) {
+        match part {
+            TemplatePart::Text(text) => filled.push_str(text),
+            TemplatePart::Placeholder(name) => match values.iter().find(|(placeholder, _)| *placeholder == name) {
+                Some((_, value)) => filled.push_str(value),
+                None => {
+                    filled.push('{');
+                    filled.push_str(name);
+                    filled.push('}');
+                }
+            },
+        }
+    }
+    filled
+}
+
+/// Fill an alias template. An invalid template falls back to the built-in one.
+fn render_template(template: Option<&str>, values: &[(&str, String)]) -> String {
+    let template = template
+        .filter(|t| validate_template(t).is_ok())
+        .unwrap_or(DEFAULT_ALIAS_TEMPLATE);
+    let mut code = fill_placeholders(template, values);
+    if !code.ends_with('\n') {
+        code.push('\n');
+    }
+    code
+}
+
+/// Generate `const` items for the const generics used in the tree that have a default
+fn generate_const_items(asset: &AliasAsset, visibility: &Visibility) -> String {
+    let mut used = Vec::new();
+    collect_const_generics(&asset.ast, &mut used);
+    let defaults = const_defaults(&asset.meta);
+
+    used.iter()
+        .filter_map(|name| {
+            defaults
+                .iter()
+                .find(|(default_name, _)| default_name == name)
+                .map(|(_, value)| format!("{}const {}: usize = {};\n", visibility.prefix(), name, value))
+        })
+        .collect()
+}
+
+/// Generate the contents of the `.rs` file for an alias asset
+pub fn generate_alias_code(asset: &AliasAsset, options: &ExportOptions) -> String {
+    format!(
+        "// Auto-generated Rust type alias\n{}{}",
+        use_declarations(std::slice::from_ref(asset)),
+        generate_alias_item(asset, options)
+    )
+}
+
+/// `use` lines for the `STD_IMPORTS` the aliases need, with a blank line after them
+fn use_declarations(assets: &[AliasAsset]) -> String {
+    let mut paths = Vec::new();
+    for asset in assets {
+        crate::import::visit_nodes(&asset.ast, &mut |node| {
+            if let TypeAstNode::Constructor { name, .. } = node {
+                if let Some((_, path)) = STD_IMPORTS.iter().find(|(n, _)| n == name) {
+                    if !paths.contains(path) {
+                        paths.push(*path);
+                    }
+                }
+            }
+        });
+    }
+    if paths.is_empty() {
+        return String::new();
+    }
+    paths.sort();
+    let mut code: String = paths.iter().map(|path| format!("use {};\n", path)).collect();
+    code.push('\n');
+    code
+}
+
+/// Generate the items for a single alias, without a file header
+fn generate_alias_item(asset: &AliasAsset, options: &ExportOptions) -> String {
+    let mut code = String::new();
+
+    if options.emit_consts {
+        let consts = generate_const_items(asset, &options.visibility);
+        if !consts.is_empty() {
+            code.push_str(&consts);
+            code.push('\n');
+        }
+    }
+
+    let mut docs = asset.description.as_deref().map(doc_comment).unwrap_or_default();
+    let mut attrs = String::new();
+    let stability = Stability::from_meta(&asset.meta).preamble(options);
+    if stability.starts_with("///") {
+        if !docs.is_empty() {
+            docs.push_str("///\n");
+        }
+        docs.push_str(&stability);
+    } else {
+        attrs.push_str(&stability);
+    }
+    attrs.push_str(&cfg_attribute(&asset.meta));
+
+    match options.mode {
+        ExportMode::Alias => {
+            let template = options
+                .template
+                .as_deref()
+                .filter(|t| validate_template(t).is_ok())
+                .unwrap_or(DEFAULT_ALIAS_TEMPLATE);
+            if !template_placeholders(template).contains(&"attrs") {
+                code.push_str(&attrs);
+            }
+            code.push_str(&render_template(
+                Some(template),
+                &[
+                    ("docs", docs),
+                    ("attrs", attrs),
+                    ("visibility", options.visibility.prefix()),
+                    ("name", format!("{}{}", asset.display_name, generic_params(asset))),
+                    ("type", ast_to_rust_string(&asset.ast)),
+                ],
+            ));
+        }
+        // Struct exports keep the built-in layout; the template describes a `type` item
+        ExportMode::Struct => {
+            code.push_str(&docs);
+            code.push_str(&attrs);
+            code.push_str(&generate_struct_item(asset, options));
+        }
+    }
+    code
+}
+
+/// Render a description as `///` lines, one per line of text.
+/// The importer reads these back into `description`.
+fn doc_comment(description: &str) -> String {
+    let description = description.trim();
+    if description.is_empty() {
+        return String::new();
+    }
+    description
+        .lines()
+        .map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() {
+                "///\n".to_string()
+            } else {
+                format!("/// {}\n", line)
+            }
+        })
+        .collect()
+}
+
+/// `pub struct Name(pub A, pub B);` with optional serde attributes. The fields stay `pub`;
+/// the struct's own visibility already limits who can reach them. Generic bounds become
+/// a `where` clause; serde's derives add the `Serialize`/`Deserialize` bounds themselves.
+fn generate_struct_item(asset: &AliasAsset, options: &ExportOptions) -> String {
+    let serde = &options.serde;
+    let fields: Vec<String> = struct_fields(&asset.ast)
+        .into_iter()
+        .map(|field| format!("pub {}", ast_to_rust_string(field)))
+        .collect();
+
+    let mut attributes = String::new();
+    if serde.derive {
+        attributes.push_str("#[derive(serde::Serialize, serde::Deserialize)]\n");
+
+        let mut args = Vec::new();
+        if serde.transparent && fields.len() == 1 {
+            args.push("transparent".to_string());
+        }
+        if let Some(rename) = serde.rename.as_deref().filter(|r| !r.trim().is_empty()) {
+            args.push(format!("rename = {:?}", rename.trim()));
+        }
+        if !args.is_empty() {
+            attributes.push_str(&format!("#[serde({})]\n", args.join(", ")));
+        }
+    }
+
+    let where_clause = where_clause(&asset.meta);
+    let mut code = format!(
+        "{}{}struct {}{}({}){};\n",
+        attributes,
+        options.visibility.prefix(),
+        asset.display_name,
+        generic_params(asset),
+        fields.join(", "),
+        where_clause.trim_end_matches(",\n")
+    );
+    if options.conversions {
+        if let [field] = struct_fields(&asset.ast).as_slice() {
+            code.push_str(&conversion_impls(asset, field, &where_clause));
+        }
+    }
+    code
+}
+
+/// Fields of a struct export: the elements of a tuple root, or the root itself
+pub fn struct_fields(ast: &TypeAstNode) -> Vec<&TypeAstNode> {
+    match ast {
+        TypeAstNode::Tuple { elements } if !elements.is_empty() => elements.iter().collect(),
+        other => vec![other],
+    }
+}
+
+/// `impl From<Inner> for Newtype` and the reverse. The reverse is left out when the field
+/// is a bare generic param, which the orphan rules do not allow implementing for.
+fn conversion_impls(asset: &AliasAsset, field: &TypeAstNode, where_clause: &str) -> String {
+    let generics = generic_params(asset);
+    let newtype = format!("{}{}", asset.display_name, generics);
+    let inner = ast_to_rust_string(field);
+    let open = if where_clause.is_empty() { " {\n" } else { "{\n" };
+    // The impls only exist where the struct does
+    let cfg = cfg_attribute(&asset.meta);
+
+    let mut code = format!(
+        "\n{}impl{} From<{}> for {}{}{}    fn from(value: {}) -> Self {{\n        Self(value)\n    }}\n}}\n",
+        cfg, generics, inner, newtype, where_clause, open, inner
+    );
+    if !declared_generics(&asset.meta).contains(&inner) {
+        code.push_str(&format!(
+            "\n{}impl{} From<{}> for {}{}{}    fn from(value: {}) -> Self {{\n        value.0\n    }}\n}}\n",
+            cfg, generics, newtype, inner, where_clause, open, newtype
+        ));
+    }
+    code
+}
+
+/// Generic params the alias must declare (`<'a, T>`): lifetimes used in the tree,
+/// then the declared type params. An empty string when there are none.
+fn generic_params(asset: &AliasAsset) -> String {
+    let mut lifetimes = Vec::new();
+    collect_lifetimes(&asset.ast, &mut lifetimes);
+    let params: Vec<_> = lifetimes
+        .iter()
+        .map(|l| format!("'{}", l))
+        .chain(declared_generics(&asset.meta))
+        .collect();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// Collect named lifetimes used in the tree, excluding `'static` and `'_`
+fn collect_lifetimes(ast: &TypeAstNode, out: &mut Vec<String>) {
+    match ast {
+        TypeAstNode::Constructor { params, lifetimes, .. } => {
+            for lifetime in lifetimes {
+                let name = lifetime.trim_start_matches('\'');
+                if name != "static" && name != "_" && !out.iter().any(|l| l == name) {
+                    out.push(name.to_string());
+                }
+            }
+            for param in params {
+                collect_lifetimes(param, out);
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            for element in elements {
+                collect_lifetimes(element, out);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            for param in params {
+                collect_lifetimes(param, out);
+            }
+            collect_lifetimes(return_type, out);
+        }
+        _ => {}
+    }
+}
+
+/// Order aliases so that each one appears after the aliases it references.
+/// Aliases caught in a reference cycle keep their original relative order at the end.
+pub fn order_by_dependencies(assets: &[AliasAsset]) -> Vec<&AliasAsset> {
+    let dependencies: Vec<Vec<usize>> = assets
+        .iter()
+        .map(|asset| {
+            let mut refs = Vec::new();
+            collect_alias_refs(&asset.ast, &mut refs);
+            assets
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| refs.contains(&other.display_name))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+
+    let mut ordered = Vec::with_capacity(assets.len());
+    let mut emitted = vec![false; assets.len()];
+
+    loop {
+        let next = (0..assets.len()).find(|&i| {
+            !emitted[i] && dependencies[i].iter().all(|&dep| emitted[dep] || dep == i)
+        });
+        match next {
+            Some(i) => {
+                emitted[i] = true;
+                ordered.push(&assets[i]);
+            }
+            None => break,
+        }
+    }
+
+    ordered.extend(assets.iter().enumerate().filter(|(i, _)| !emitted[*i]).map(|(_, a)| a));
+    ordered
+}
+
+/// Collect the names of all aliases referenced in the tree
+pub fn collect_alias_refs(ast: &TypeAstNode, out: &mut Vec<String>) {
+    match ast {
+        TypeAstNode::AliasRef { alias } => {
+            if !out.contains(alias) {
+                out.push(alias.clone());
+            }
+        }
+        TypeAstNode::Constructor { params, .. } => {
+            for param in params {
+                collect_alias_refs(param, out);
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            for element in elements {
+                collect_alias_refs(element, out);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            for param in params {
+                collect_alias_refs(param, out);
+            }
+            collect_alias_refs(return_type, out);
+        }
+        _ => {}
+    }
+}
+
+/// Whether a `Path` leaf names a type outside the alias, rather than one of its generic
+/// params or `Self`
+pub fn is_external_path(path: &str, generics: &[String]) -> bool {
+    let is_self = path == SELF_TYPE || path.strip_prefix(SELF_TYPE).is_some_and(|rest| rest.starts_with("::"));
+    !is_self && !generics.iter().any(|param| param == path)
+}
+
+/// The aliases and external paths an alias depends on, each in order of first use
+pub fn dependencies(asset: &AliasAsset) -> (Vec<String>, Vec<String>) {
+    let mut aliases = Vec::new();
+    collect_alias_refs(&asset.ast, &mut aliases);
+
+    let generics = declared_generics(&asset.meta);
+    let mut paths = Vec::new();
+    collect_paths(&asset.ast, &mut paths);
+    paths.retain(|path| is_external_path(path, &generics));
+    (aliases, paths)
+}
+
+/// Collect the distinct `Path` leaves in the tree
+fn collect_paths(ast: &TypeAstNode, out: &mut Vec<String>) {
+    match ast {
+        TypeAstNode::Path { path } => {
+            if type_ast::const_arg_expr(ast).is_none() && !out.contains(path) {
+                out.push(path.clone());
+            }
+        }
+        TypeAstNode::Constructor { params, .. } => {
+            for param in params {
+                collect_paths(param, out);
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            for element in elements {
+                collect_paths(element, out);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            for param in params {
+                collect_paths(param, out);
+            }
+            collect_paths(return_type, out);
+        }
+        _ => {}
+    }
+}
+
+/// Generate a module file containing several aliases, dependencies first
+pub fn generate_module_code(assets: &[AliasAsset], config: &project::ProjectTypeConfig) -> String {
+    let items = order_by_dependencies(assets)
+        .into_iter()
+        .map(|asset| generate_alias_item(asset, &ExportOptions::from_meta(&asset.meta).with_template(config)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("// Auto-generated Rust type aliases\n{}{}", use_declarations(assets), items)
+}
+
+/// Write the generated code for an alias. Aliases that share an output module are
+/// combined with every other alias in the project targeting the same file.
+pub fn write_generated_code(alias_path: &Path, asset: &AliasAsset, options: &ExportOptions) -> Result<PathBuf, String> {
+    let (path, code) = generated_file(alias_path, asset, options);
+    write_generated_file(&path, &code)?;
+    Ok(path)
+}
+
+/// Write code computed by `generated_file`, creating the module directory if needed
+pub fn write_generated_file(path: &Path, code: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    std::fs::write(path, code).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// The path and full contents `write_generated_code` would write, without touching disk
+pub fn generated_file(alias_path: &Path, asset: &AliasAsset, options: &ExportOptions) -> (PathBuf, String) {
+    let Some(module_path) = output_module_path(&asset.meta, alias_path) else {
+        return (generated_rs_path(alias_path), generate_alias_code(asset, options));
+    };
+
+    let root = project::find_project_root(alias_path)
+        .or_else(|| alias_path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+
+    // Group every alias by its target module, using the in-memory asset for this file
+    let mut modules: BTreeMap<PathBuf, Vec<AliasAsset>> = BTreeMap::new();
+    for path in project::find_alias_files(&root) {
+        if path == alias_path {
+            continue;
+        }
+        if let Ok(other) = project::load_alias_asset(&path) {
+            if let Some(other_module) = output_module_path(&other.meta, &path) {
+                modules.entry(other_module).or_default().push(other);
+            }
+        }
+    }
+    let mut assets = modules.remove(&module_path).unwrap_or_default();
+    assets.push(asset.clone());
+
+    let code = generate_module_code(&assets, &project::ProjectTypeConfig::load(&root));
+    (module_path, code)
+}
+
+/// Remove the generated code of an alias that is being deleted. A shared output module
+/// is rewritten without it, or removed when no other alias targets it.
+pub fn remove_generated_code(alias_path: &Path, asset: &AliasAsset) -> Result<(), String> {
+    let Some(module_path) = output_module_path(&asset.meta, alias_path) else {
+        let path = generated_rs_path(alias_path);
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {:?}: {}", path, e)),
+            _ => Ok(()),
+        };
+    };
+
+    let root = project::find_project_root(alias_path)
+        .or_else(|| alias_path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let others: Vec<AliasAsset> = project::find_alias_files(&root)
+        .into_iter()
+        .filter(|path| path != alias_path)
+        .filter_map(|path| {
+            let other = project::load_alias_asset(&path).ok()?;
+            (output_module_path(&other.meta, &path).as_ref() == Some(&module_path)).then_some(other)
+        })
+        .collect();
+
+    if others.is_empty() {
+        std::fs::remove_file(&module_path).map_err(|e| format!("Failed to remove {:?}: {}", module_path, e))
+    } else {
+        let code = generate_module_code(&others, &project::ProjectTypeConfig::load(&root));
+        write_generated_file(&module_path, &code)
+    }
+}
+
+/// Why a headless `.alias` → `.rs` conversion failed
+#[derive(Debug)]
+pub enum ConvertError {
+    /// The file could not be read or the output could not be written
+    Io { path: PathBuf, message: String },
+    /// The file is not a valid alias asset
+    Parse { path: PathBuf, message: String },
+    /// The file describes another type kind (struct, enum, trait)
+    NotAnAlias { path: PathBuf },
+    /// The type tree still has empty slots
+    Incomplete { path: PathBuf },
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            ConvertError::Parse { path, message } => write!(f, "{}: failed to parse: {}", path.display(), message),
+            ConvertError::NotAnAlias { path } => write!(f, "{}: not a type alias asset", path.display()),
+            ConvertError::Incomplete { path } => write!(f, "{}: type has empty slots", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Check that no slot in the tree was left empty
+pub fn is_complete(ast: &TypeAstNode) -> bool {
+    match ast {
+        TypeAstNode::None => false,
+        TypeAstNode::Constructor { params, .. } => params.iter().all(is_complete),
+        TypeAstNode::Tuple { elements } => elements.iter().all(is_complete),
+        TypeAstNode::FnPointer { params, return_type } => {
+            params.iter().all(is_complete) && is_complete(return_type)
+        }
+        _ => true,
+    }
+}
+
+/// Load an alias asset file and generate its Rust source without any UI. An alias with
+/// an output module yields the whole shared module, as the editor would write it.
+pub fn convert_alias_file(path: &Path) -> Result<String, ConvertError> {
+    let asset = load_convertible(path)?;
+    let options = ExportOptions::from_meta(&asset.meta).with_template(&project::ProjectTypeConfig::for_file(path));
+    Ok(generated_file(path, &asset, &options).1)
+}
+
+/// Load an alias asset file, rejecting anything `generated_file` can't turn into Rust
+fn load_convertible(path: &Path) -> Result<AliasAsset, ConvertError> {
+    let json = std::fs::read_to_string(path).map_err(|e| ConvertError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let asset = crate::schema::parse_alias_asset(&json).map(|parsed| parsed.asset).map_err(|message| ConvertError::Parse {
+        path: path.to_path_buf(),
+        message,
+    })?;
+
+    if !matches!(asset.type_kind, ui_types_common::TypeKind::Alias) {
+        return Err(ConvertError::NotAnAlias { path: path.to_path_buf() });
+    }
+    if !is_complete(&asset.ast) {
+        return Err(ConvertError::Incomplete { path: path.to_path_buf() });
+    }
+    Ok(asset)
+}
+
+/// Outcome of `regenerate_project`, by generated file
+#[derive(Clone, Debug, Default)]
+pub struct RegenerateReport {
+    /// Generated files that were written
+    pub written: Vec<PathBuf>,
+    /// Generated files already up to date, left untouched
+    pub unchanged: Vec<PathBuf>,
+    /// Alias files that were not regenerated, with why
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl RegenerateReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Rewrite the generated code of every alias in a project with the current generator and
+/// template. Files with validation errors are skipped and reported; output that would not
+/// change is not rewritten, so running this twice writes nothing the second time.
+pub fn regenerate_project(root: &Path) -> RegenerateReport {
+    let config = project::ProjectTypeConfig::load(root);
+    let mut report = RegenerateReport::default();
+
+    for (path, diagnostics) in crate::validation::validate_project(root) {
+        if let Some(error) = diagnostics.iter().find(|d| d.is_error()) {
+            report.failed.push((path, error.to_string()));
+            continue;
+        }
+        let asset = match project::load_alias_asset(&path) {
+            Ok(asset) => asset,
+            Err(e) => {
+                report.failed.push((path, e));
+                continue;
+            }
+        };
+        if !matches!(asset.type_kind, ui_types_common::TypeKind::Alias) {
+            continue;
+        }
+
+        let options = ExportOptions::from_meta(&asset.meta).with_template(&config);
+        let (output, code) = generated_file(&path, &asset, &options);
+        // Aliases sharing an output module produce the same file; handle it once
+        if report.written.contains(&output) || report.unchanged.contains(&output) {
+            continue;
+        }
+        if std::fs::read_to_string(&output).is_ok_and(|existing| existing == code) {
+            report.unchanged.push(output);
+            continue;
+        }
+        match write_generated_file(&output, &code) {
+            Ok(()) => report.written.push(output),
+            Err(e) => report.failed.push((path, e)),
+        }
+    }
+    report
+}
+
+/// Convert every alias asset under `dir`, writing each generated file where the editor
+/// would: next to its source, or into its shared output module. Returns the outcome per
+/// source file.
+pub fn convert_directory(dir: &Path) -> Vec<(PathBuf, Result<PathBuf, ConvertError>)> {
+    let mut written: Vec<PathBuf> = Vec::new();
+    project::find_alias_files(dir)
+        .into_iter()
+        .map(|path| {
+            let result = load_convertible(&path).and_then(|asset| {
+                let options = ExportOptions::from_meta(&asset.meta).with_template(&project::ProjectTypeConfig::for_file(&path));
+                let (output, code) = generated_file(&path, &asset, &options);
+                // Aliases sharing an output module produce the same file; write it once
+                if written.contains(&output) {
+                    return Ok(output);
+                }
+                write_generated_file(&output, &code)
+                    .map_err(|message| ConvertError::Io { path: output.clone(), message })?;
+                written.push(output.clone());
+                Ok(output)
+            });
+            (path, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const ENTITY_ID_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../EntityId/alias.json");
+
+    /// A fresh project directory under the system temp dir
+    fn scratch_project() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("alias_convert_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("Pulsar.toml"), "").unwrap();
+        root
+    }
+
+    /// The EntityId fixture with its empty error slot filled with `String`
+    fn complete_entity_id() -> Value {
+        let mut json: Value = serde_json::from_str(&std::fs::read_to_string(ENTITY_ID_FIXTURE).unwrap()).unwrap();
+        json["ast"]["params"][0]["params"][1] = json!({ "nodeKind": "Primitive", "name": "String" });
+        json
+    }
+
+    fn write_alias(root: &Path, dir: &str, json: &Value) -> PathBuf {
+        let path = root.join(dir).join(project::ALIAS_FILE_NAME);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string_pretty(json).unwrap()).unwrap();
+        path
+    }
+
+    /// `Score = u32`, deprecated since 2.0 and only built on unix
+    fn deprecated_unix_score() -> AliasAsset {
+        let mut asset: AliasAsset = serde_json::from_value(json!({
+            "schemaVersion": 2,
+            "typeKind": "alias",
+            "name": "score",
+            "displayName": "Score",
+            "description": "Points so far",
+            "ast": { "nodeKind": "Primitive", "name": "u32" },
+            "meta": {}
+        }))
+        .unwrap();
+        Stability { level: Some("deprecated".to_string()), since: Some("2.0".to_string()) }.write_to_meta(&mut asset.meta);
+        set_cfg_predicate(&mut asset.meta, Some("unix"));
+        asset
+    }
+
+    #[test]
+    fn attributes_survive_a_template_without_attrs_or_docs() {
+        let options = ExportOptions {
+            stability_marker: StabilityMarker::Attribute,
+            template: Some("// custom\n{visibility}type {name} = {type};".to_string()),
+            ..ExportOptions::default()
+        };
+        let code = generate_alias_code(&deprecated_unix_score(), &options);
+        assert!(code.contains("#[deprecated(since = \"2.0\")]\n#[cfg(unix)]\n// custom\npub type Score = u32;"), "{}", code);
+        assert!(!code.contains("Points so far"));
+    }
+
+    #[test]
+    fn template_values_are_not_read_as_placeholders() {
+        let code = render_template(
+            Some("// {{generated}}\n{docs}type {name} = {type};"),
+            &[
+                ("docs", "/// Same as {type}\n".to_string()),
+                ("name", "Grid".to_string()),
+                ("type", "[u8; {N}]".to_string()),
+            ],
+        );
+        assert_eq!(code, "// {generated}\n/// Same as {type}\ntype Grid = [u8; {N}];\n");
+    }
+
+    #[test]
+    fn escaped_braces_are_not_placeholders() {
+        assert_eq!(template_placeholders("{{attrs}} {name} = {type} }}"), vec!["name", "type"]);
+        assert!(validate_template("{{name}} {type}").is_err());
+    }
+
+    #[test]
+    fn default_template_puts_attributes_after_docs() {
+        let options = ExportOptions { stability_marker: StabilityMarker::Attribute, ..ExportOptions::default() };
+        let code = generate_alias_code(&deprecated_unix_score(), &options);
+        assert!(
+            code.ends_with("/// Points so far\n#[deprecated(since = \"2.0\")]\n#[cfg(unix)]\npub type Score = u32;\n"),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn entity_id_fixture_has_an_empty_slot() {
+        let result = convert_alias_file(Path::new(ENTITY_ID_FIXTURE));
+        assert!(matches!(result, Err(ConvertError::Incomplete { .. })));
+    }
+
+    #[test]
+    fn converts_a_complete_entity_id() {
+        let root = scratch_project();
+        let path = write_alias(&root, "EntityId", &complete_entity_id());
+
+        let code = convert_alias_file(&path).unwrap();
+        assert!(code.starts_with("// Auto-generated Rust type alias\n"));
+        assert!(code.contains("/// Unique identifier for game entities\n"));
+        assert!(code.contains("type EntityId = Option<Result<u64, String>>;"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn converts_a_directory_next_to_each_source() {
+        let root = scratch_project();
+        let complete = write_alias(&root, "EntityId", &complete_entity_id());
+        let incomplete = root.join("Broken").join(project::ALIAS_FILE_NAME);
+        std::fs::create_dir_all(incomplete.parent().unwrap()).unwrap();
+        std::fs::copy(ENTITY_ID_FIXTURE, &incomplete).unwrap();
+
+        let results = convert_directory(&root);
+        assert_eq!(results.len(), 2);
+        for (source, result) in results {
+            if source == complete {
+                let output = result.unwrap();
+                assert_eq!(output, generated_rs_path(&complete));
+                let code = std::fs::read_to_string(output).unwrap();
+                assert!(code.contains("type EntityId = Option<Result<u64, String>>;"));
+            } else {
+                assert_eq!(source, incomplete);
+                assert!(matches!(result, Err(ConvertError::Incomplete { .. })));
+                assert!(!generated_rs_path(&incomplete).exists());
+            }
+        }
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn converts_a_shared_output_module_once() {
+        let root = scratch_project();
+        let mut entity_id = complete_entity_id();
+        entity_id["meta"] = json!({ OUTPUT_MODULE_META_KEY: "types.rs" });
+        write_alias(&root, "EntityId", &entity_id);
+        let mut score = entity_id.clone();
+        score["name"] = json!("score");
+        score["displayName"] = json!("Score");
+        score["description"] = Value::Null;
+        score["ast"] = json!({ "nodeKind": "Primitive", "name": "u32" });
+        write_alias(&root, "Score", &score);
+
+        let module = root.join("types.rs");
+        let results = convert_directory(&root);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| result.as_ref().is_ok_and(|output| *output == module)));
+        assert!(!root.join("EntityId").join("alias.rs").exists());
+
+        let code = std::fs::read_to_string(&module).unwrap();
+        assert!(code.starts_with("// Auto-generated Rust type aliases\n"));
+        assert_eq!(code.matches("type EntityId = Option<Result<u64, String>>;").count(), 1);
+        assert_eq!(code.matches("type Score = u32;").count(), 1);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn async_fn_combo_imports_pin_and_future() {
+        let mut asset = deprecated_unix_score();
+        asset.ast = crate::import::type_from_rust("fn(u32) -> Pin<Box<dyn Future<Output = bool>>>").unwrap();
+        let code = generate_alias_code(&asset, &ExportOptions::default());
+        assert!(code.contains("use std::future::Future;\nuse std::pin::Pin;\n"));
+        assert!(syn::parse_file(&code).is_ok(), "{}", code);
+    }
+}
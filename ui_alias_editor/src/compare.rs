@@ -0,0 +1,304 @@
+// Side-by-side structural diff of two alias files, for reviewing changes
+
+use gpui::*;
+use ui::{h_flex, v_flex, ActiveTheme, StyledExt, dock::{Panel, PanelEvent}};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::schema;
+use crate::type_ast;
+use crate::type_block::{BlockId, BlockTheme, TypeBlock, TypeBlockView};
+
+/// How a block differs from the block in the same position on the other side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only in the right tree
+    Added,
+    /// Only in the left tree
+    Removed,
+    /// In both trees, with a different type
+    Changed,
+}
+
+impl DiffKind {
+    fn color(&self, cx: &App) -> Hsla {
+        match self {
+            DiffKind::Added => cx.theme().success,
+            DiffKind::Removed => cx.theme().danger,
+            DiffKind::Changed => cx.theme().warning,
+        }
+    }
+}
+
+/// Differing blocks of two trees, keyed by block on each side
+#[derive(Clone, Debug, Default)]
+pub struct TreeDiff {
+    pub left: HashMap<BlockId, DiffKind>,
+    pub right: HashMap<BlockId, DiffKind>,
+}
+
+impl TreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+
+    /// Number of differences of one kind; a changed pair counts once
+    pub fn count(&self, kind: DiffKind) -> usize {
+        let side = if kind == DiffKind::Added { &self.right } else { &self.left };
+        side.values().filter(|k| **k == kind).count()
+    }
+}
+
+/// Align two trees by slot position and mark the subtrees that differ. Blocks of the
+/// same shape are descended into so only the innermost differences are marked.
+pub fn diff_trees(left: &TypeBlock, right: &TypeBlock) -> TreeDiff {
+    let mut diff = TreeDiff::default();
+    diff_blocks(left, right, &mut diff);
+    diff
+}
+
+fn diff_blocks(left: &TypeBlock, right: &TypeBlock, diff: &mut TreeDiff) {
+    if let (Some(a), Some(b)) = (left.to_ast(), right.to_ast()) {
+        if type_ast::structural_eq(&a, &b) {
+            return;
+        }
+    }
+    if !same_shape(left, right) {
+        diff.left.insert(left.id().clone(), DiffKind::Changed);
+        diff.right.insert(right.id().clone(), DiffKind::Changed);
+        return;
+    }
+
+    for index in 0..left.slot_count().max(right.slot_count()) {
+        match (left.get_slot(index), right.get_slot(index)) {
+            (Some(a), Some(b)) => diff_blocks(a, b, diff),
+            (Some(a), None) => {
+                diff.left.insert(a.id().clone(), DiffKind::Removed);
+            }
+            (None, Some(b)) => {
+                diff.right.insert(b.id().clone(), DiffKind::Added);
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Whether two blocks line up slot for slot. Leaves only line up when they are equal.
+fn same_shape(left: &TypeBlock, right: &TypeBlock) -> bool {
+    match (left, right) {
+        (
+            TypeBlock::Constructor { name: name_a, lifetimes: lifetimes_a, const_generics: consts_a, .. },
+            TypeBlock::Constructor { name: name_b, lifetimes: lifetimes_b, const_generics: consts_b, .. },
+        ) => name_a == name_b && lifetimes_a.len() == lifetimes_b.len() && consts_a == consts_b,
+        (TypeBlock::Tuple { .. }, TypeBlock::Tuple { .. }) => true,
+        // The return type is the last slot, so differing arities would misalign it
+        (TypeBlock::FnPointer { .. }, TypeBlock::FnPointer { .. }) => left.slot_count() == right.slot_count(),
+        _ => {
+            std::mem::discriminant(left) == std::mem::discriminant(right)
+                && left.display_name() == right.display_name()
+        }
+    }
+}
+
+/// One side of a comparison
+struct CompareSide {
+    path: PathBuf,
+    display_name: String,
+    root: Result<TypeBlock, String>,
+}
+
+impl CompareSide {
+    fn load(path: PathBuf) -> Self {
+        let asset = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| schema::parse_alias_asset(&json))
+            .map(|parsed| parsed.asset);
+        match asset {
+            Ok(asset) => Self {
+                path,
+                display_name: asset.display_name.clone(),
+                root: Ok(TypeBlock::from_ast(&asset.ast)),
+            },
+            Err(e) => Self {
+                path,
+                display_name: String::new(),
+                root: Err(e),
+            },
+        }
+    }
+}
+
+/// Read-only panel showing two alias files side by side with their differences outlined
+pub struct AliasCompareView {
+    left: CompareSide,
+    right: CompareSide,
+    diff: TreeDiff,
+    focus_handle: FocusHandle,
+}
+
+impl AliasCompareView {
+    pub fn new(left: PathBuf, right: PathBuf, cx: &mut Context<Self>) -> Self {
+        let left = CompareSide::load(left);
+        let right = CompareSide::load(right);
+        let diff = match (&left.root, &right.root) {
+            (Ok(a), Ok(b)) => diff_trees(a, b),
+            _ => TreeDiff::default(),
+        };
+        Self {
+            left,
+            right,
+            diff,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn summary(&self) -> String {
+        if self.left.root.is_err() || self.right.root.is_err() {
+            return "Cannot compare".to_string();
+        }
+        if self.diff.is_empty() {
+            return "No structural differences".to_string();
+        }
+        format!(
+            "{} added · {} removed · {} changed",
+            self.diff.count(DiffKind::Added),
+            self.diff.count(DiffKind::Removed),
+            self.diff.count(DiffKind::Changed)
+        )
+    }
+
+    fn render_side(
+        &self,
+        id: &'static str,
+        side: &CompareSide,
+        marks: &HashMap<BlockId, DiffKind>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let highlights: HashMap<BlockId, Hsla> = marks
+            .iter()
+            .map(|(block_id, kind)| (block_id.clone(), kind.color(cx)))
+            .collect();
+        let file_name = side
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        v_flex()
+            .flex_1()
+            .h_full()
+            .gap_3()
+            .p_4()
+            .child(
+                v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_bold()
+                            .text_color(cx.theme().foreground)
+                            .child(side.display_name.clone())
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(file_name)
+                    )
+            )
+            .child(match &side.root {
+                Ok(root) => div()
+                    .child(TypeBlockView::new(root.clone(), id)
+                        .highlights(Arc::new(highlights))
+                        .theme(BlockTheme::from_app(cx)))
+                    .into_any_element(),
+                Err(e) => div()
+                    .text_sm()
+                    .text_color(cx.theme().danger)
+                    .child(format!("Failed to load: {}", e))
+                    .into_any_element(),
+            })
+    }
+}
+
+impl Render for AliasCompareView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let legend = [DiffKind::Added, DiffKind::Removed, DiffKind::Changed];
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .px_4()
+                    .py_3()
+                    .gap_4()
+                    .items_center()
+                    .bg(cx.theme().secondary)
+                    .border_b_2()
+                    .border_color(cx.theme().border)
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_bold()
+                            .text_color(cx.theme().foreground)
+                            .child(self.summary())
+                    )
+                    .child(div().flex_1())
+                    .children(legend.iter().map(|kind| {
+                        h_flex()
+                            .gap_1()
+                            .items_center()
+                            .child(div().size(px(10.0)).rounded(px(2.0)).bg(kind.color(cx)))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(format!("{:?}", kind))
+                            )
+                    }))
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .items_start()
+                    .child(self.render_side("compare-left", &self.left, &self.diff.left, cx))
+                    .child(div().w(px(2.0)).h_full().bg(cx.theme().border))
+                    .child(self.render_side("compare-right", &self.right, &self.diff.right, cx))
+            )
+    }
+}
+
+impl Focusable for AliasCompareView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for AliasCompareView {}
+
+impl Panel for AliasCompareView {
+    fn panel_name(&self) -> &'static str {
+        "Alias Comparison"
+    }
+
+    fn title(&self, _window: &Window, _cx: &App) -> gpui::AnyElement {
+        let name = |side: &CompareSide| {
+            if side.display_name.is_empty() {
+                side.path.display().to_string()
+            } else {
+                side.display_name.clone()
+            }
+        };
+        format!("⇄ {} ↔ {}", name(&self.left), name(&self.right)).into_any_element()
+    }
+
+    fn dump(&self, _cx: &App) -> ui::dock::PanelState {
+        ui::dock::PanelState {
+            panel_name: self.panel_name().to_string(),
+            ..Default::default()
+        }
+    }
+}
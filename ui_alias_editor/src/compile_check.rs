@@ -0,0 +1,134 @@
+// Optional `cargo check` of generated alias code in a scratch crate.
+// Heavier than the `syn` parse gate, but catches unresolved paths and trait-bound errors.
+
+use crate::validation::EditorDiagnostic;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Manifest for the scratch crate the generated code is checked in, before the
+/// project's dependencies are appended
+const SCRATCH_MANIFEST: &str = r#"[package]
+name = "pulsar_alias_check"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[lib]
+path = "src/lib.rs"
+"#;
+
+/// Held while a check writes and builds the scratch crate, so editors checking at the
+/// same time don't overwrite each other's source
+static SCRATCH_LOCK: Mutex<()> = Mutex::new(());
+
+/// Directory of the scratch crate, reused between checks so `cargo` can cache its work
+pub fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join("pulsar_alias_check")
+}
+
+/// Cache key for the result of checking `code` against `manifest`
+pub fn code_hash(manifest: &str, code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    manifest.hash(&mut hasher);
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Scratch crate manifest with the `[dependencies]` of the project's `Cargo.toml`, so
+/// paths into the project's crates resolve. Relative `path` dependencies are made
+/// absolute; `workspace = true` entries can't resolve outside the workspace and are
+/// left out, so paths into those crates are reported as unresolved.
+pub fn scratch_manifest(project_root: Option<&Path>) -> String {
+    let mut manifest = SCRATCH_MANIFEST.to_string();
+    let Some(root) = project_root else {
+        return manifest;
+    };
+    let Ok(project_manifest) = std::fs::read_to_string(root.join("Cargo.toml")) else {
+        return manifest;
+    };
+    manifest.push_str("\n[dependencies]\n");
+    let mut in_dependencies = false;
+    for line in project_manifest.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_dependencies = trimmed == "[dependencies]";
+            continue;
+        }
+        if !in_dependencies || trimmed.is_empty() || trimmed.contains("workspace = true") {
+            continue;
+        }
+        manifest.push_str(&absolute_dependency_path(trimmed, root));
+        manifest.push('\n');
+    }
+    manifest
+}
+
+/// Rewrite a relative `path = "..."` in a dependency line to be under `root`
+fn absolute_dependency_path(line: &str, root: &Path) -> String {
+    const KEY: &str = "path = \"";
+    let Some(start) = line.find(KEY).map(|i| i + KEY.len()) else {
+        return line.to_string();
+    };
+    let Some(len) = line[start..].find('"') else {
+        return line.to_string();
+    };
+    let path = Path::new(&line[start..start + len]);
+    if path.is_absolute() {
+        return line.to_string();
+    }
+    let absolute = root.join(path).to_string_lossy().replace('\\', "/");
+    format!("{}{}{}", &line[..start], absolute, &line[start + len..])
+}
+
+/// Write `code` into the scratch crate with `manifest` (see `scratch_manifest`) and
+/// run `cargo check` on it.
+///
+/// Blocking; run it on a background thread. Returns the compiler's errors and
+/// warnings, or `Err` when cargo could not be run or failed without reporting any,
+/// e.g. when a dependency doesn't resolve. Checks run one at a time.
+pub fn cargo_check(manifest: &str, code: &str) -> Result<Vec<EditorDiagnostic>, String> {
+    // A panicked check leaves nothing worth protecting, so a poisoned lock is reused
+    let _guard = SCRATCH_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let dir = scratch_dir();
+    let src = dir.join("src");
+    std::fs::create_dir_all(&src).map_err(|e| format!("Failed to create scratch crate: {}", e))?;
+    std::fs::write(dir.join("Cargo.toml"), manifest)
+        .map_err(|e| format!("Failed to write scratch manifest: {}", e))?;
+    std::fs::write(src.join("lib.rs"), format!("#![allow(dead_code, unused)]\n{}", code))
+        .map_err(|e| format!("Failed to write scratch source: {}", e))?;
+
+    let output = Command::new("cargo")
+        .args(["check", "--quiet", "--message-format=short"])
+        .current_dir(&dir)
+        .output()
+        .map_err(|e| format!("Failed to run cargo: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let diagnostics = parse_short_messages(&stderr);
+    if !output.status.success() && diagnostics.is_empty() {
+        let reason = stderr.lines().find(|line| line.starts_with("error")).unwrap_or(stderr.trim());
+        return Err(format!("cargo check failed: {}", reason));
+    }
+    Ok(diagnostics)
+}
+
+/// Parse `--message-format=short` output (`src/lib.rs:3:20: error[E0412]: ...`),
+/// skipping cargo's own summary lines
+fn parse_short_messages(stderr: &str) -> Vec<EditorDiagnostic> {
+    stderr
+        .lines()
+        .filter(|line| line.starts_with("src/"))
+        .filter_map(|line| {
+            if line.contains(": error") {
+                Some(EditorDiagnostic::error(line))
+            } else if line.contains(": warning") {
+                Some(EditorDiagnostic::warning(line))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
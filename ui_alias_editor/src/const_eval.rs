@@ -0,0 +1,104 @@
+// Integer-only evaluator for const generic expressions like `4 * 1024`,
+// used to preview their value. Generated code keeps the expression form.
+
+/// Integer suffixes accepted on literals (`1024usize`)
+const INT_SUFFIXES: &[&str] = &[
+    "usize", "isize", "u128", "i128", "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8",
+];
+
+/// Evaluate an integer expression with `+ - * /` and parentheses.
+/// Returns `None` for anything non-constant (names, calls) or on overflow and division by zero.
+pub fn eval_const_expr(expr: &str) -> Option<i128> {
+    let mut parser = Parser { input: expr.as_bytes(), pos: 0 };
+    let value = parser.expr()?;
+    parser.skip_whitespace();
+    (parser.pos == parser.input.len()).then_some(value)
+}
+
+/// Text shown next to a const expression, e.g. `= 4096`. `None` when the
+/// expression is not constant or is already a plain literal.
+pub fn preview(expr: &str) -> Option<String> {
+    let value = eval_const_expr(expr)?;
+    let is_literal = expr.trim().chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    (!is_literal).then(|| format!("= {}", value))
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.input.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Option<i128> {
+        let mut value = self.term()?;
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == b'+' { value.checked_add(rhs)? } else { value.checked_sub(rhs)? };
+        }
+        Some(value)
+    }
+
+    /// term := factor (('*' | '/') factor)*
+    fn term(&mut self) -> Option<i128> {
+        let mut value = self.factor()?;
+        while let Some(op @ (b'*' | b'/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            value = if op == b'*' { value.checked_mul(rhs)? } else { value.checked_div(rhs)? };
+        }
+        Some(value)
+    }
+
+    /// factor := '-' factor | '(' expr ')' | integer
+    fn factor(&mut self) -> Option<i128> {
+        match self.peek()? {
+            b'-' => {
+                self.pos += 1;
+                self.factor()?.checked_neg()
+            }
+            b'(' => {
+                self.pos += 1;
+                let value = self.expr()?;
+                if self.peek()? != b')' {
+                    return None;
+                }
+                self.pos += 1;
+                Some(value)
+            }
+            b'0'..=b'9' => self.integer(),
+            _ => None,
+        }
+    }
+
+    fn integer(&mut self) -> Option<i128> {
+        let start = self.pos;
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+        {
+            self.pos += 1;
+        }
+
+        let token = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+        let digits = INT_SUFFIXES
+            .iter()
+            .find_map(|suffix| token.strip_suffix(suffix))
+            .unwrap_or(token)
+            .replace('_', "");
+        digits.parse().ok()
+    }
+}
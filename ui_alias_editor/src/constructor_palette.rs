@@ -3,7 +3,7 @@ use ui::{h_flex, v_flex, divider::Divider, ActiveTheme, StyledExt, button::{Butt
 use pulsar_std::{get_all_type_constructors, get_type_constructors_by_category, TypeConstructorMetadata};
 use ui_types_common::PRIMITIVES;
 use std::collections::HashMap;
-use crate::type_block::TypeBlock;
+use crate::type_block::{primitives_for_literal, BlockColor, TypeBlock};
 
 /// Event emitted when a type is selected from the palette
 #[derive(Clone, Debug)]
@@ -127,11 +127,16 @@ impl ConstructorPalette {
             .collect()
     }
 
-    /// Filter primitives based on search query
+    /// Filter primitives based on search query. A numeric literal such as `255` lists the
+    /// primitives that can hold it instead, best fit first.
     fn filter_primitives(&self) -> Vec<&'static str> {
         if self.search_query.is_empty() {
             return PRIMITIVES.to_vec();
         }
+        let fitting = primitives_for_literal(&self.search_query);
+        if !fitting.is_empty() {
+            return fitting;
+        }
 
         let query_lower = self.search_query.to_lowercase();
         PRIMITIVES
@@ -291,6 +296,18 @@ impl ConstructorPalette {
                             .child(format!("Primitives ({})", primitives.len()))
                     )
             )
+            .when_some(
+                primitives.first().filter(|_| !primitives_for_literal(&self.search_query).is_empty()),
+                |this, best| {
+                    this.child(
+                        div()
+                            .px_3()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{} fits in {}; wider types follow", self.search_query.trim(), best))
+                    )
+                },
+            )
             .when(!is_collapsed, |this| {
                 this.child(
                     // Primitive blocks in a grid
@@ -308,7 +325,7 @@ impl ConstructorPalette {
     }
 
     fn render_primitive_block(&self, name: &str, cx: &App) -> impl IntoElement {
-        let color = hsla(0.55, 0.7, 0.5, 1.0); // Blue for primitives
+        let color = BlockColor::Primitive.for_theme(cx.theme().mode.is_dark());
         
         div()
             .px_3()
@@ -379,7 +396,7 @@ impl ConstructorPalette {
     }
 
     fn render_constructor_block(&self, constructor: &TypeConstructorMetadata, cx: &App) -> impl IntoElement {
-        let color = hsla(0.08, 0.8, 0.6, 1.0); // Orange for constructors
+        let color = BlockColor::Constructor.for_theme(cx.theme().mode.is_dark());
         
         v_flex()
             .w_full()
@@ -464,7 +481,7 @@ impl ConstructorPalette {
     }
 
     fn render_palette_block(&self, constructor: &TypeConstructorMetadata, cx: &mut App) -> impl IntoElement {
-        let color = hsla(0.08, 0.8, 0.6, 1.0); // Orange for constructors
+        let color = BlockColor::Constructor.for_theme(cx.theme().mode.is_dark());
 
         v_flex()
             .w_full()
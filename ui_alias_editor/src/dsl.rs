@@ -0,0 +1,363 @@
+// Terse text form of a type tree for hand editing, e.g. `HashMap(String, Vec(Option(i32)))`
+//
+//   type := `_`                          empty slot
+//         | `@` Name                     reference to another alias
+//         | `fn(` types `)` [`->` type]  fn pointer; without an arrow it returns `()`
+//         | `()`                         unit
+//         | `(` type `,` types `)`       tuple; a single element keeps its trailing comma
+//         | name `(` args `)`            constructor, e.g. `Vec(u8)` or `dyn Fn(i32, bool)`
+//         | name                         primitive or generic param; a path when it has `::`
+//         | `"` text `"`                 path written as is, e.g. `"Self::Item"`
+//   arg  := type | `'a` lifetime | `{expr}` const value | `const {expr}` const generic
+
+use ui_types_common::TypeAstNode;
+use crate::type_ast;
+
+/// Characters that end a bare word
+const DELIMITERS: &[char] = &['(', ')', ',', '{', '}', '"'];
+
+/// Print a tree in the DSL. Empty slots (`TypeAstNode::None`) print as `_`.
+pub fn to_dsl(ast: &TypeAstNode) -> String {
+    match ast {
+        TypeAstNode::Primitive { name } => name.clone(),
+        TypeAstNode::Path { .. } if type_ast::const_arg_expr(ast).is_some() => {
+            format!("{{{}}}", type_ast::const_arg_expr(ast).unwrap_or_default())
+        }
+        TypeAstNode::Path { path } if is_bare_path(path) => path.clone(),
+        TypeAstNode::Path { path } => format!("{:?}", path),
+        TypeAstNode::AliasRef { alias } => format!("@{}", alias),
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => {
+            let args = lifetimes
+                .iter()
+                .map(|l| format!("'{}", l.trim_start_matches('\'')))
+                .chain(params.iter().map(to_dsl))
+                .chain(const_generics.iter().map(|expr| format!("const {{{}}}", expr)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{}({})", name, args)
+        }
+        TypeAstNode::Tuple { elements } if elements.len() == 1 => format!("({},)", to_dsl(&elements[0])),
+        TypeAstNode::Tuple { elements } => format!("({})", join(elements)),
+        TypeAstNode::FnPointer { params, return_type } => match return_type.as_ref() {
+            TypeAstNode::Primitive { name } if name == "()" => format!("fn({})", join(params)),
+            output => format!("fn({}) -> {}", join(params), to_dsl(output)),
+        },
+        TypeAstNode::None => "_".to_string(),
+    }
+}
+
+fn join(nodes: &[TypeAstNode]) -> String {
+    nodes.iter().map(to_dsl).collect::<Vec<_>>().join(", ")
+}
+
+/// Paths that read back as paths without quotes: `::`-separated words
+fn is_bare_path(path: &str) -> bool {
+    path.contains("::") && !path.contains(|c: char| c.is_whitespace() || DELIMITERS.contains(&c))
+}
+
+/// Read a tree written in the DSL. Type names are not checked; see `import::check_imported_ast`.
+pub fn from_dsl(text: &str) -> Result<TypeAstNode, String> {
+    let mut parser = Parser { text, pos: 0 };
+    let ast = parser.parse_type()?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("unexpected text after the type"));
+    }
+    Ok(ast)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+/// A constructor argument before it is sorted into lifetimes, params and const generics
+enum Arg {
+    Lifetime(String),
+    Type(TypeAstNode),
+    ConstGeneric(String),
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at column {}", message, self.text[..self.pos].chars().count() + 1)
+    }
+
+    /// Consume `token` if it comes next
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", token)))
+        }
+    }
+
+    /// The next bare word, without consuming it
+    fn peek_word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || DELIMITERS.contains(&c))
+            .unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    fn word(&mut self) -> Result<&'a str, String> {
+        let word = self.peek_word();
+        if word.is_empty() {
+            return Err(self.error("expected a type"));
+        }
+        self.pos += word.len();
+        Ok(word)
+    }
+
+    /// Text up to the matching `}`, after the opening `{`
+    fn braced(&mut self) -> Result<String, String> {
+        self.expect("{")?;
+        let mut depth = 1;
+        for (offset, c) in self.rest().char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                let expr = self.rest()[..offset].trim().to_string();
+                self.pos += offset + 1;
+                return Ok(expr);
+            }
+        }
+        Err(self.error("unclosed `{`"))
+    }
+
+    /// `"..."` with `\"` and `\\` escapes
+    fn quoted(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        Err(self.error("unclosed `\"`"))
+    }
+
+    /// Comma-separated items up to `)`, which is consumed
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T, String>) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        if self.eat(")") {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(")") {
+                return Ok(items);
+            }
+            self.expect(",")?;
+            // Trailing comma
+            if self.eat(")") {
+                return Ok(items);
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<TypeAstNode, String> {
+        self.skip_whitespace();
+        if self.rest().starts_with('"') {
+            return Ok(TypeAstNode::Path { path: self.quoted()? });
+        }
+        if self.eat("(") {
+            if self.eat(")") {
+                return Ok(TypeAstNode::Primitive { name: "()".to_string() });
+            }
+            let first = self.parse_type()?;
+            if self.eat(")") {
+                // Parentheses around a single type only group it
+                return Ok(first);
+            }
+            self.expect(",")?;
+            let mut elements = vec![first];
+            elements.extend(self.list(Self::parse_type)?);
+            return Ok(TypeAstNode::Tuple { elements });
+        }
+
+        let mut name = self.word()?.to_string();
+        if name == "dyn" {
+            name = format!("dyn {}", self.word()?);
+        }
+        if name == "_" {
+            return Ok(TypeAstNode::None);
+        }
+        if let Some(alias) = name.strip_prefix('@') {
+            if alias.is_empty() {
+                return Err(self.error("expected an alias name after `@`"));
+            }
+            return Ok(TypeAstNode::AliasRef { alias: alias.to_string() });
+        }
+        if !self.eat("(") {
+            return Ok(if name.contains("::") {
+                TypeAstNode::Path { path: name }
+            } else {
+                TypeAstNode::Primitive { name }
+            });
+        }
+
+        if name == "fn" {
+            let params = self.list(Self::parse_type)?;
+            let return_type = if self.eat("->") {
+                self.parse_type()?
+            } else {
+                TypeAstNode::Primitive { name: "()".to_string() }
+            };
+            return Ok(TypeAstNode::FnPointer { params, return_type: Box::new(return_type) });
+        }
+
+        let mut lifetimes = Vec::new();
+        let mut params = Vec::new();
+        let mut const_generics = Vec::new();
+        for arg in self.list(Self::parse_arg)? {
+            match arg {
+                Arg::Lifetime(lifetime) => lifetimes.push(lifetime),
+                Arg::Type(param) => params.push(param),
+                Arg::ConstGeneric(expr) => const_generics.push(expr),
+            }
+        }
+        Ok(TypeAstNode::Constructor { name, params, lifetimes, const_generics })
+    }
+
+    fn parse_arg(&mut self) -> Result<Arg, String> {
+        self.skip_whitespace();
+        if self.rest().starts_with('{') {
+            return Ok(Arg::Type(type_ast::const_arg(self.braced()?)));
+        }
+        let word = self.peek_word();
+        if let Some(lifetime) = word.strip_prefix('\'') {
+            self.pos += word.len();
+            return Ok(Arg::Lifetime(lifetime.to_string()));
+        }
+        if word == "const" {
+            self.pos += word.len();
+            return Ok(Arg::ConstGeneric(self.braced()?));
+        }
+        self.parse_type().map(Arg::Type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn primitive(name: &str) -> TypeAstNode {
+        TypeAstNode::Primitive { name: name.to_string() }
+    }
+
+    /// `text` reads back to a tree that prints as `text` again
+    fn assert_round_trip(text: &str) -> TypeAstNode {
+        let ast = from_dsl(text).unwrap_or_else(|e| panic!("{}: {}", text, e));
+        assert_eq!(to_dsl(&ast), text);
+        let reparsed = from_dsl(&to_dsl(&ast)).unwrap();
+        assert!(type_ast::structural_eq(&ast, &reparsed), "{} changed shape", text);
+        ast
+    }
+
+    #[test]
+    fn nested_constructors_round_trip() {
+        assert_round_trip("HashMap(String, Vec(Option(i32)))");
+        assert_round_trip("@Other");
+        assert_round_trip("()");
+    }
+
+    #[test]
+    fn empty_slots_print_as_underscores() {
+        let ast = assert_round_trip("Result(_, Vec(_))");
+        let TypeAstNode::Constructor { params, .. } = &ast else {
+            panic!("expected a constructor");
+        };
+        assert!(matches!(params[0], TypeAstNode::None));
+        assert_eq!(to_dsl(&TypeAstNode::None), "_");
+    }
+
+    #[test]
+    fn one_element_tuples_keep_their_comma() {
+        let ast = assert_round_trip("(u8,)");
+        assert!(type_ast::structural_eq(&ast, &TypeAstNode::Tuple { elements: vec![primitive("u8")] }));
+        assert_round_trip("Vec((u8,))");
+        // Parentheses around one type only group it
+        assert!(type_ast::structural_eq(&from_dsl("(u8)").unwrap(), &primitive("u8")));
+    }
+
+    #[test]
+    fn paths_are_quoted_unless_they_read_back_bare() {
+        assert_round_trip("Self::Item");
+        let ast = assert_round_trip(r#""<T as Iterator>::Item""#);
+        assert!(matches!(&ast, TypeAstNode::Path { path } if path == "<T as Iterator>::Item"));
+        // A path without `::` would read back as a primitive
+        let plain = TypeAstNode::Path { path: "T".to_string() };
+        assert_eq!(to_dsl(&plain), r#""T""#);
+        assert!(type_ast::structural_eq(&from_dsl(&to_dsl(&plain)).unwrap(), &plain));
+    }
+
+    #[test]
+    fn lifetimes_and_const_values_round_trip() {
+        let ast = assert_round_trip("Cow('a, str)");
+        assert!(matches!(&ast, TypeAstNode::Constructor { lifetimes, .. } if lifetimes == &["a".to_string()]));
+
+        let ast = assert_round_trip("GenericArray(u8, {16})");
+        let TypeAstNode::Constructor { params, .. } = &ast else {
+            panic!("expected a constructor");
+        };
+        assert_eq!(type_ast::const_arg_expr(&params[1]), Some("16"));
+
+        let ast = assert_round_trip("ArrayVec(u8, const {N * 2})");
+        assert!(matches!(&ast, TypeAstNode::Constructor { const_generics, .. } if const_generics == &["N * 2".to_string()]));
+    }
+
+    #[test]
+    fn fn_pointers_and_closure_traits_round_trip() {
+        assert_round_trip("fn() -> T");
+        assert_round_trip("fn(u8, bool) -> Vec(u8)");
+        // A unit return is left out
+        let ast = assert_round_trip("fn(u8)");
+        assert!(matches!(&ast, TypeAstNode::FnPointer { return_type, .. } if type_ast::structural_eq(return_type, &primitive("()"))));
+        assert_eq!(to_dsl(&from_dsl("fn(u8) -> ()").unwrap()), "fn(u8)");
+
+        let ast = assert_round_trip("Box(dyn Fn(i32, bool))");
+        assert!(matches!(&ast, TypeAstNode::Constructor { params, .. }
+            if matches!(&params[0], TypeAstNode::Constructor { name, .. } if name == "dyn Fn")));
+    }
+
+    #[test]
+    fn malformed_text_is_rejected() {
+        for text in ["Vec(u8", "(u8, bool", "@", "\"unclosed", "Vec(u8) extra", "GenericArray(u8, {16)"] {
+            assert!(from_dsl(text).is_err(), "{} parsed", text);
+        }
+    }
+}
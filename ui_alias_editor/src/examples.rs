@@ -0,0 +1,38 @@
+// Starter content offered on the empty canvas
+
+use crate::import;
+use crate::type_block::TypeBlock;
+use crate::type_palette::TypeItem;
+
+/// Example aliases that can be loaded into an empty canvas: (label, Rust type)
+pub const EXAMPLE_ALIASES: &[(&str, &str)] = &[
+    ("Lookup table", "HashMap<String, Vec<u32>>"),
+    ("Shared state", "Arc<Mutex<Vec<String>>>"),
+    ("Fallible parse", "Result<Option<i64>, String>"),
+    ("Event callback", "fn(u32, String) -> bool"),
+    ("Grid", "Vec<Vec<(f32, f32)>>"),
+];
+
+/// Block tree for an example alias
+pub fn example_block(index: usize) -> Option<TypeBlock> {
+    let (_, source) = EXAMPLE_ALIASES.get(index)?;
+    import::type_from_rust(source).ok().map(|ast| TypeBlock::from_ast(&ast))
+}
+
+/// Templates are the picker's combos: structures with the interesting slots left empty
+pub fn templates() -> Vec<TypeItem> {
+    TypeItem::combos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_example_imports() {
+        for (index, (label, source)) in EXAMPLE_ALIASES.iter().enumerate() {
+            assert!(import::type_from_rust(source).is_ok(), "{} ({}) does not import", label, source);
+            assert!(example_block(index).is_some(), "{}", label);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// Plain-English descriptions of type trees, for onboarding
+
+use ui_types_common::TypeAstNode;
+use crate::type_ast;
+
+/// Phrasing for constructors as (name, singular, plural). Placeholders refer to params:
+/// `{0}` singular, `{0a}` singular with an article, `{0p}` plural.
+const CONSTRUCTOR_PHRASES: &[(&str, &str, &str)] = &[
+    ("Vec", "list of {0p}", "lists of {0p}"),
+    ("VecDeque", "double-ended queue of {0p}", "double-ended queues of {0p}"),
+    ("LinkedList", "linked list of {0p}", "linked lists of {0p}"),
+    ("HashSet", "set of {0p}", "sets of {0p}"),
+    ("BTreeSet", "sorted set of {0p}", "sorted sets of {0p}"),
+    ("HashMap", "map from {0p} to {1p}", "maps from {0p} to {1p}"),
+    ("BTreeMap", "sorted map from {0p} to {1p}", "sorted maps from {0p} to {1p}"),
+    ("IndexMap", "insertion-ordered map from {0p} to {1p}", "insertion-ordered maps from {0p} to {1p}"),
+    ("Option", "optional {0}", "optional {0p}"),
+    ("Result", "result that is either {0a} or an error holding {1a}", "results that are either {0a} or an error holding {1a}"),
+    ("Box", "boxed {0}", "boxed {0p}"),
+    ("Rc", "shared {0}", "shared {0p}"),
+    ("Arc", "thread-safe shared {0}", "thread-safe shared {0p}"),
+    ("Weak", "weak reference to {0a}", "weak references to {0p}"),
+    ("Mutex", "mutex-protected {0}", "mutex-protected {0p}"),
+    ("RwLock", "read-write-locked {0}", "read-write-locked {0p}"),
+    ("Cell", "mutable cell holding {0a}", "mutable cells holding {0p}"),
+    ("RefCell", "borrow-checked cell holding {0a}", "borrow-checked cells holding {0p}"),
+    ("Cow", "clone-on-write {0}", "clone-on-write {0p}"),
+    ("Pin", "pinned {0}", "pinned {0p}"),
+    ("PhantomData", "marker for {0p}", "markers for {0p}"),
+    ("dyn Future", "future that resolves to {0a}", "futures that resolve to {0a}"),
+    ("dyn Iterator", "iterator over {0p}", "iterators over {0p}"),
+];
+
+/// Describe a whole alias, e.g. "`Scores` is a map from strings to 32-bit integers."
+pub fn explain_alias(name: &str, ast: &TypeAstNode) -> String {
+    if name.is_empty() {
+        format!("{}.", capitalize(&with_article(&phrase(ast, false))))
+    } else {
+        format!("`{}` is {}.", name, with_article(&phrase(ast, false)))
+    }
+}
+
+/// Noun phrase for a type, without a leading article
+fn phrase(ast: &TypeAstNode, plural: bool) -> String {
+    match ast {
+        TypeAstNode::Primitive { name } => primitive_phrase(name, plural),
+        TypeAstNode::Path { .. } if type_ast::const_arg_expr(ast).is_some() => {
+            format!("const `{}`", type_ast::const_arg_expr(ast).unwrap_or_default())
+        }
+        TypeAstNode::Path { path } => named_phrase(path, plural),
+        TypeAstNode::AliasRef { alias } => named_phrase(alias, plural),
+        TypeAstNode::Constructor { name, params, .. } => {
+            match CONSTRUCTOR_PHRASES.iter().find(|(n, _, _)| *n == name.as_str()) {
+                Some((_, singular, plural_form)) => {
+                    fill_template(if plural { plural_form } else { singular }, params)
+                }
+                None => {
+                    let params = params
+                        .iter()
+                        .map(|param| with_article(&phrase(param, false)))
+                        .collect::<Vec<_>>();
+                    format!("`{}`{} of {}", name, if plural { " values" } else { "" }, join_list(&params))
+                }
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            let parts = elements
+                .iter()
+                .map(|element| with_article(&phrase(element, false)))
+                .collect::<Vec<_>>();
+            let noun = match elements.len() {
+                1 => "one-element tuple",
+                2 => "pair",
+                _ => "tuple",
+            };
+            format!("{}{} of {}", noun, if plural { "s" } else { "" }, join_list(&parts))
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            let takes = if params.is_empty() {
+                "no arguments".to_string()
+            } else {
+                join_list(
+                    &params
+                        .iter()
+                        .map(|param| with_article(&phrase(param, false)))
+                        .collect::<Vec<_>>(),
+                )
+            };
+            let returns = match return_type.as_ref() {
+                TypeAstNode::Primitive { name } if name == "()" => "nothing".to_string(),
+                other => with_article(&phrase(other, false)),
+            };
+            format!(
+                "function{} taking {} and returning {}",
+                if plural { "s" } else { "" },
+                takes,
+                returns
+            )
+        }
+        TypeAstNode::None => if plural { "unknown types" } else { "unknown type" }.to_string(),
+    }
+}
+
+fn primitive_phrase(name: &str, plural: bool) -> String {
+    let singular = match name {
+        "bool" => "boolean".to_string(),
+        "char" => "character".to_string(),
+        "String" | "str" => "string".to_string(),
+        "()" => return "nothing".to_string(),
+        "Self" if plural => return "values of the implementing type".to_string(),
+        "Self" => return "the implementing type".to_string(),
+        "isize" => "pointer-sized integer".to_string(),
+        "usize" => "pointer-sized unsigned integer".to_string(),
+        "f32" | "f64" => format!("{}-bit float", &name[1..]),
+        _ if name.starts_with('i') && name[1..].parse::<u32>().is_ok() => {
+            format!("{}-bit integer", &name[1..])
+        }
+        _ if name.starts_with('u') && name[1..].parse::<u32>().is_ok() => {
+            format!("unsigned {}-bit integer", &name[1..])
+        }
+        _ => return named_phrase(name, plural),
+    };
+    if plural { format!("{}s", singular) } else { singular }
+}
+
+/// Phrase for a type we have no wording for: "`Foo`" or "`Foo` values"
+fn named_phrase(name: &str, plural: bool) -> String {
+    if plural {
+        format!("`{}` values", name)
+    } else {
+        format!("`{}`", name)
+    }
+}
+
+/// Replace `{0}`, `{0a}` and `{0p}` style placeholders with param phrases
+fn fill_template(template: &str, params: &[TypeAstNode]) -> String {
+    let mut text = template.to_string();
+    for (i, param) in params.iter().enumerate() {
+        text = text
+            .replace(&format!("{{{}a}}", i), &with_article(&phrase(param, false)))
+            .replace(&format!("{{{}p}}", i), &phrase(param, true))
+            .replace(&format!("{{{}}}", i), &phrase(param, false));
+    }
+    text
+}
+
+fn with_article(phrase: &str) -> String {
+    if phrase == "nothing" {
+        return phrase.to_string();
+    }
+    let first = phrase.chars().find(|c| c.is_alphanumeric()).unwrap_or('x');
+    // "an 8-bit", "an 11-..." read with a vowel sound
+    let article = if "aeiouAEIOU8".contains(first) || phrase.starts_with("11") || phrase.starts_with("18") {
+        "an"
+    } else {
+        "a"
+    };
+    format!("{} {}", article, phrase)
+}
+
+/// "a", "a and b", "a, b and c"
+fn join_list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,57 @@
+// Storage backend for alias files, so hosts can keep assets somewhere other than the local disk
+
+use std::io;
+use std::path::Path;
+
+/// Reads and writes the files an editor loads and saves. Hosts with virtual or remote
+/// asset storage supply their own; `LocalFileProvider` is the plain filesystem.
+pub trait FileProvider: Send + Sync {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Write `contents` to `path`, creating missing parent directories
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()>;
+
+    /// Delete the file at `path`
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    /// Whether a file exists at `path`
+    fn exists(&self, path: &Path) -> bool {
+        self.read_to_string(path).is_ok()
+    }
+
+    /// Whether the backend refuses writes to `path`; such files open read-only
+    fn is_read_only(&self, _path: &Path) -> bool {
+        false
+    }
+}
+
+/// The local filesystem
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalFileProvider;
+
+impl FileProvider for LocalFileProvider {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_read_only(&self, path: &Path) -> bool {
+        std::fs::metadata(path)
+            .map(|metadata| metadata.permissions().readonly())
+            .unwrap_or(false)
+    }
+}
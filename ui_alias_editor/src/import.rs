@@ -0,0 +1,494 @@
+// Conversion from Rust type syntax into `TypeAstNode`, and bulk import of `type` items from `.rs` files
+
+use ui_types_common::{AliasAsset, TypeAstNode, TypeKind, PRIMITIVES};
+use quote::ToTokens;
+use std::path::Path;
+use crate::type_block::const_param_positions;
+use crate::type_ast;
+use crate::codegen::{self, ExportOptions, Stability, Visibility, ASSOCIATED_TYPE_CONSTRUCTORS, FN_TRAIT_CONSTRUCTORS};
+use crate::project::ProjectTypeConfig;
+
+/// Parse a Rust type like `HashMap<String, Vec<u8>>` into an AST
+pub fn type_from_rust(source: &str) -> Result<TypeAstNode, String> {
+    let ty: syn::Type = syn::parse_str(source).map_err(|e| format!("Invalid Rust type: {}", e))?;
+    ast_from_syn(&ty)
+}
+
+/// Read a `TypeAstNode` serialized as JSON, e.g. by external tooling. Every primitive must
+/// be a known primitive or one of `generics`, every constructor a std or project
+/// constructor, and every path valid Rust. Primitive names are normalized first if the
+/// project opts in; the renames made are returned alongside the tree.
+pub fn ast_from_json(
+    json: &str,
+    config: &ProjectTypeConfig,
+    generics: &[String],
+) -> Result<(TypeAstNode, Vec<(String, String)>), String> {
+    let ast: TypeAstNode = serde_json::from_str(json).map_err(|e| format!("Not a type AST: {}", e))?;
+    check_imported_ast(&ast, config, generics)
+}
+
+/// Check a tree from outside the editor the way `ast_from_json` does
+pub fn check_imported_ast(
+    ast: &TypeAstNode,
+    config: &ProjectTypeConfig,
+    generics: &[String],
+) -> Result<(TypeAstNode, Vec<(String, String)>), String> {
+    let (ast, renamed) = config.normalize_primitives(ast);
+
+    let constructors = pulsar_std::get_all_type_constructors();
+    let mut unknown = Vec::new();
+    visit_nodes(&ast, &mut |node| {
+        let known = match node {
+            TypeAstNode::Primitive { name } => {
+                PRIMITIVES.contains(&name.as_str()) || name == type_ast::SELF_TYPE || generics.contains(name)
+            }
+            TypeAstNode::Constructor { name, .. } => {
+                constructors.iter().any(|ctor| ctor.name == name.as_str())
+                    || config.constructors.iter().any(|ctor| ctor.name == *name)
+            }
+            TypeAstNode::Path { path } => {
+                type_ast::const_arg_expr(node).is_some() || syn::parse_str::<syn::Path>(path).is_ok()
+            }
+            _ => true,
+        };
+        if !known {
+            unknown.push(quoted_name(node));
+        }
+    });
+    if unknown.is_empty() {
+        Ok((ast, renamed))
+    } else {
+        Err(format!("Unknown types: {}", unknown.join(", ")))
+    }
+}
+
+fn quoted_name(node: &TypeAstNode) -> String {
+    match node {
+        TypeAstNode::Primitive { name } | TypeAstNode::Constructor { name, .. } => format!("`{}`", name),
+        TypeAstNode::Path { path } => format!("`{}`", path),
+        _ => String::new(),
+    }
+}
+
+/// Visit every node in the tree, parents first
+pub(crate) fn visit_nodes(node: &TypeAstNode, visit: &mut impl FnMut(&TypeAstNode)) {
+    visit(node);
+    match node {
+        TypeAstNode::Constructor { params, .. } => params.iter().for_each(|p| visit_nodes(p, visit)),
+        TypeAstNode::Tuple { elements } => elements.iter().for_each(|e| visit_nodes(e, visit)),
+        TypeAstNode::FnPointer { params, return_type } => {
+            params.iter().for_each(|p| visit_nodes(p, visit));
+            visit_nodes(return_type, visit);
+        }
+        _ => {}
+    }
+}
+
+/// Convert a parsed `syn` type into an AST
+pub fn ast_from_syn(ty: &syn::Type) -> Result<TypeAstNode, String> {
+    match ty {
+        // `(T)` is only grouping; `(T,)` parses as a one-element `Type::Tuple` below
+        syn::Type::Paren(paren) => ast_from_syn(&paren.elem),
+        syn::Type::Group(group) => ast_from_syn(&group.elem),
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(TypeAstNode::Primitive {
+            name: "()".to_string(),
+        }),
+        syn::Type::Tuple(tuple) => Ok(TypeAstNode::Tuple {
+            elements: tuple.elems.iter().map(ast_from_syn).collect::<Result<_, _>>()?,
+        }),
+        syn::Type::BareFn(bare_fn) => {
+            let params = bare_fn
+                .inputs
+                .iter()
+                .map(|arg| ast_from_syn(&arg.ty))
+                .collect::<Result<_, _>>()?;
+            let return_type = match &bare_fn.output {
+                syn::ReturnType::Default => TypeAstNode::Primitive { name: "()".to_string() },
+                syn::ReturnType::Type(_, ty) => ast_from_syn(ty)?,
+            };
+            Ok(TypeAstNode::FnPointer {
+                params,
+                return_type: Box::new(return_type),
+            })
+        }
+        syn::Type::Path(type_path) if type_path.qself.is_none() => ast_from_path(&type_path.path, ""),
+        syn::Type::TraitObject(object) => {
+            // Only single-trait objects map onto the `dyn Trait<..>` constructor form
+            let mut traits = object.bounds.iter().filter_map(|bound| match bound {
+                syn::TypeParamBound::Trait(bound) => Some(&bound.path),
+                _ => None,
+            });
+            match (traits.next(), traits.next()) {
+                (Some(path), None) => ast_from_path(path, "dyn "),
+                _ => Err("Trait objects with several traits are not supported".to_string()),
+            }
+        }
+        other => Err(format!("Unsupported type `{}`", other.to_token_stream())),
+    }
+}
+
+fn ast_from_path(path: &syn::Path, prefix: &str) -> Result<TypeAstNode, String> {
+    let Some(last) = path.segments.last() else {
+        return Err("Empty type path".to_string());
+    };
+
+    let full_path = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    let name = format!("{}{}", prefix, full_path);
+
+    // `dyn Fn(A, B) -> R` keeps the argument types followed by the return type
+    if let syn::PathArguments::Parenthesized(args) = &last.arguments {
+        if !FN_TRAIT_CONSTRUCTORS.contains(&name.as_str()) {
+            return Err(format!("Unsupported arguments on `{}`", full_path));
+        }
+        let mut params = args.inputs.iter().map(ast_from_syn).collect::<Result<Vec<_>, _>>()?;
+        params.push(match &args.output {
+            syn::ReturnType::Default => TypeAstNode::Primitive { name: "()".to_string() },
+            syn::ReturnType::Type(_, ty) => ast_from_syn(ty)?,
+        });
+        return Ok(TypeAstNode::Constructor {
+            name,
+            params,
+            lifetimes: Vec::new(),
+            const_generics: Vec::new(),
+        });
+    }
+
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        if !last.arguments.is_none() {
+            return Err(format!("Unsupported arguments on `{}`", full_path));
+        }
+        // `Self` is a keyword like the primitives, so it gets a primitive block too
+        let keyword = PRIMITIVES.contains(&full_path.as_str()) || full_path == type_ast::SELF_TYPE;
+        return Ok(if path.segments.len() == 1 && keyword {
+            TypeAstNode::Primitive { name: full_path }
+        } else {
+            TypeAstNode::Path { path: name }
+        });
+    };
+
+    // `N` parses as a type, so known const positions are converted after the fact
+    let known_consts = const_param_positions(&name);
+    // Associated type bindings are stored positionally, so only the known ones in their
+    // declared order can be kept
+    let bindings = ASSOCIATED_TYPE_CONSTRUCTORS
+        .iter()
+        .find(|(n, _)| *n == name.as_str())
+        .map_or(&[][..], |(_, bindings)| *bindings);
+    let mut params = Vec::new();
+    let mut lifetimes = Vec::new();
+    for arg in &args.args {
+        match arg {
+            syn::GenericArgument::Type(ty) if known_consts.contains(&params.len()) => {
+                params.push(type_ast::const_arg(ty.to_token_stream().to_string()))
+            }
+            syn::GenericArgument::Type(ty) => params.push(ast_from_syn(ty)?),
+            syn::GenericArgument::Lifetime(lifetime) => lifetimes.push(lifetime.ident.to_string()),
+            syn::GenericArgument::Const(expr) => {
+                params.push(type_ast::const_arg(expr.to_token_stream().to_string()))
+            }
+            syn::GenericArgument::AssocType(assoc) if bindings.get(params.len()) == Some(&assoc.ident.to_string().as_str()) => {
+                params.push(ast_from_syn(&assoc.ty)?)
+            }
+            syn::GenericArgument::AssocType(assoc) => {
+                return Err(format!("Unsupported associated type binding `{}` on `{}`", assoc.to_token_stream(), full_path))
+            }
+            other => return Err(format!("Unsupported generic argument `{}`", other.to_token_stream())),
+        }
+    }
+
+    // Const values after the last type stay trailing const generics unless the
+    // constructor declares a const param there
+    let mut const_generics = Vec::new();
+    while let Some(expr) = params.last().and_then(type_ast::const_arg_expr).map(str::to_string) {
+        if known_consts.contains(&(params.len() - 1)) {
+            break;
+        }
+        params.pop();
+        const_generics.insert(0, expr);
+    }
+
+    Ok(TypeAstNode::Constructor {
+        name,
+        params,
+        lifetimes,
+        const_generics,
+    })
+}
+
+/// Key in `AliasAsset.meta` listing the slot paths of blocks that came from a Rust
+/// import and have not been edited since. Review aid only; codegen ignores it.
+pub const IMPORTED_META_KEY: &str = "imported";
+
+/// Slot paths of the blocks marked as imported; the root is the empty path
+pub fn imported_paths(meta: &serde_json::Value) -> Vec<Vec<usize>> {
+    meta.get(IMPORTED_META_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Store the imported slot paths, dropping the key once nothing is left marked
+pub fn set_imported_paths(meta: &mut serde_json::Value, paths: &[Vec<usize>]) {
+    let Some(map) = meta.as_object_mut() else {
+        return;
+    };
+    if paths.is_empty() {
+        map.remove(IMPORTED_META_KEY);
+    } else {
+        map.insert(IMPORTED_META_KEY.to_string(), serde_json::json!(paths));
+    }
+}
+
+/// A `type` alias item read from a Rust source file
+#[derive(Clone, Debug)]
+pub struct ImportedAlias {
+    pub name: String,
+    /// Doc comment on the item, if any
+    pub description: Option<String>,
+    pub ast: TypeAstNode,
+    pub visibility: Visibility,
+    /// From a generated `Stability:` doc line or a `#[deprecated]` attribute
+    pub stability: Stability,
+    /// Predicate of the item's `#[cfg(...)]` attributes, several joined with `all(...)`
+    pub cfg: Option<String>,
+}
+
+impl ImportedAlias {
+    /// Build an alias asset for this item, ready to be written to disk
+    pub fn to_asset(&self, name: String) -> AliasAsset {
+        let mut meta = serde_json::Value::Object(serde_json::Map::new());
+        if self.visibility != Visibility::Public {
+            let options = ExportOptions { visibility: self.visibility.clone(), ..Default::default() };
+            options.write_to_meta(&mut meta);
+        }
+        self.stability.write_to_meta(&mut meta);
+        codegen::set_cfg_predicate(&mut meta, self.cfg.as_deref());
+        set_imported_paths(&mut meta, &type_ast::node_paths(&self.ast));
+        AliasAsset {
+            schema_version: crate::schema::CURRENT_SCHEMA_VERSION,
+            type_kind: TypeKind::Alias,
+            name,
+            display_name: self.name.clone(),
+            description: self.description.clone(),
+            ast: self.ast.clone(),
+            meta,
+        }
+    }
+}
+
+/// Aliases found in a Rust file, plus a note for every item that was skipped
+#[derive(Clone, Debug, Default)]
+pub struct RustFileImport {
+    pub aliases: Vec<ImportedAlias>,
+    pub skipped: Vec<String>,
+}
+
+/// Read a `.rs` file and extract its top-level `type` aliases
+pub fn aliases_from_rust_file(path: &Path) -> Result<RustFileImport, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    aliases_from_rust_source(&source)
+}
+
+/// Extract the top-level `type` aliases from Rust source. Generic aliases, aliases
+/// over unsupported types and all other items are listed in `skipped`.
+pub fn aliases_from_rust_source(source: &str) -> Result<RustFileImport, String> {
+    let file = syn::parse_file(source).map_err(|e| format!("Invalid Rust file: {}", e))?;
+    let mut result = RustFileImport::default();
+
+    for item in &file.items {
+        let syn::Item::Type(item_type) = item else {
+            result.skipped.push(describe_item(item));
+            continue;
+        };
+
+        let name = item_type.ident.to_string();
+        if !item_type.generics.params.is_empty() {
+            result.skipped.push(format!("type {} (generic aliases are not supported)", name));
+            continue;
+        }
+        match ast_from_syn(&item_type.ty) {
+            Ok(ast) => {
+                let (description, stability) = doc_comment(&item_type.attrs);
+                result.aliases.push(ImportedAlias {
+                    name,
+                    description,
+                    ast,
+                    visibility: visibility_from_syn(&item_type.vis),
+                    stability,
+                    cfg: cfg_predicate(&item_type.attrs),
+                })
+            }
+            Err(e) => result.skipped.push(format!("type {} ({})", name, e)),
+        }
+    }
+
+    Ok(result)
+}
+
+fn visibility_from_syn(vis: &syn::Visibility) -> Visibility {
+    match vis {
+        syn::Visibility::Public(_) => Visibility::Public,
+        syn::Visibility::Inherited => Visibility::Private,
+        syn::Visibility::Restricted(restricted) => {
+            let path = restricted.path.to_token_stream().to_string().replace(' ', "");
+            match (restricted.in_token.is_some(), path.as_str()) {
+                (false, "crate") => Visibility::Crate,
+                (false, "super") => Visibility::Super,
+                // `pub(self)` is private
+                (false, _) => Visibility::Private,
+                (true, _) => Visibility::In(path),
+            }
+        }
+    }
+}
+
+/// Join `///` lines (`#[doc = "..."]` attributes) into one description, taking out the
+/// stability marker the generator writes. `#[deprecated]` also sets the stability.
+fn doc_comment(attrs: &[syn::Attribute]) -> (Option<String>, Stability) {
+    let mut stability = Stability::default();
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let value = match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }),
+                ..
+            }) => Some(text.value()),
+            _ => None,
+        };
+        if attr.path().is_ident("deprecated") {
+            stability.level = Some("deprecated".to_string());
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("since") {
+                    let since: syn::LitStr = meta.value()?.parse()?;
+                    stability.since = Some(since.value());
+                }
+                Ok(())
+            });
+        } else if attr.path().is_ident("doc") {
+            let Some(line) = value else {
+                continue;
+            };
+            match Stability::from_doc_line(&line) {
+                Some(marker) => stability = marker,
+                None => lines.push(line.strip_prefix(' ').unwrap_or(&line).to_string()),
+            }
+        }
+    }
+    let text = lines.join("\n").trim().to_string();
+    ((!text.is_empty()).then_some(text), stability)
+}
+
+/// The predicate of the `#[cfg(...)]` attributes, joined with `all(...)` when there are several
+fn cfg_predicate(attrs: &[syn::Attribute]) -> Option<String> {
+    let predicates: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .map(|list| {
+            let tokens = list.tokens.to_string();
+            codegen::format_cfg_predicate(&tokens).unwrap_or(tokens)
+        })
+        .collect();
+    match predicates.as_slice() {
+        [] => None,
+        [only] => Some(only.clone()),
+        _ => Some(format!("all({})", predicates.join(", "))),
+    }
+}
+
+/// Short label for a skipped item, e.g. `struct Foo (not a type alias)`
+fn describe_item(item: &syn::Item) -> String {
+    let label = match item {
+        syn::Item::Struct(item) => format!("struct {}", item.ident),
+        syn::Item::Enum(item) => format!("enum {}", item.ident),
+        syn::Item::Union(item) => format!("union {}", item.ident),
+        syn::Item::Fn(item) => format!("fn {}", item.sig.ident),
+        syn::Item::Const(item) => format!("const {}", item.ident),
+        syn::Item::Static(item) => format!("static {}", item.ident),
+        syn::Item::Trait(item) => format!("trait {}", item.ident),
+        syn::Item::Mod(item) => format!("mod {}", item.ident),
+        syn::Item::Use(_) => return "use declaration (not a type alias)".to_string(),
+        syn::Item::Impl(_) => return "impl block (not a type alias)".to_string(),
+        _ => return "item (not a type alias)".to_string(),
+    };
+    format!("{} (not a type alias)", label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn primitive(name: &str) -> TypeAstNode {
+        TypeAstNode::Primitive { name: name.to_string() }
+    }
+
+    #[test]
+    fn parenthesized_type_is_just_the_type() {
+        let ast = type_from_rust("(u8)").unwrap();
+        assert!(type_ast::structural_eq(&ast, &primitive("u8")));
+        assert_eq!(codegen::ast_to_rust_string(&ast), "u8");
+    }
+
+    #[test]
+    fn trailing_comma_makes_a_one_element_tuple() {
+        let ast = type_from_rust("(u8,)").unwrap();
+        assert!(type_ast::structural_eq(&ast, &TypeAstNode::Tuple { elements: vec![primitive("u8")] }));
+        assert_eq!(codegen::ast_to_rust_string(&ast), "(u8,)");
+    }
+
+    #[test]
+    fn tricky_parens_round_trip() {
+        let cases = [
+            ("((u8))", "u8"),
+            ("((u8,),)", "((u8,),)"),
+            ("Vec<(u8,)>", "Vec<(u8,)>"),
+            ("Option<(u8)>", "Option<u8>"),
+            ("(u8, bool)", "(u8, bool)"),
+            ("()", "()"),
+        ];
+        for (source, expected) in cases {
+            let ast = type_from_rust(source).unwrap();
+            let generated = codegen::ast_to_rust_string(&ast);
+            assert_eq!(generated, expected, "{}", source);
+            let reparsed = type_from_rust(&generated).unwrap();
+            assert!(type_ast::structural_eq(&ast, &reparsed), "{} changed through {}", source, generated);
+        }
+    }
+
+    #[test]
+    fn const_values_round_trip() {
+        for source in ["GenericArray<u8, 16>", "ArrayVec<u8, N>", "SmallVec<u8, 4>"] {
+            let ast = type_from_rust(source).unwrap();
+            assert_eq!(codegen::ast_to_rust_string(&ast), source);
+        }
+        let TypeAstNode::Constructor { params, .. } = type_from_rust("GenericArray<u8, 16>").unwrap() else {
+            panic!("expected a constructor");
+        };
+        assert_eq!(type_ast::const_arg_expr(&params[1]), Some("16"));
+    }
+
+    #[test]
+    fn associated_type_bindings_keep_their_name() {
+        for source in ["Box<dyn Future<Output = u8>>", "Box<dyn Iterator<Item = String>>"] {
+            let ast = type_from_rust(source).unwrap();
+            assert_eq!(codegen::ast_to_rust_string(&ast), source);
+        }
+        assert!(type_from_rust("Box<dyn Iterator<Output = u8>>").is_err());
+        assert!(type_from_rust("Box<dyn Deref<Target = u8>>").is_err());
+    }
+
+    #[test]
+    fn newtype_aliases_import_as_one_element_tuples() {
+        let import = aliases_from_rust_source("pub type Meters = (f32,);\npub type Plain = (f32);\n").unwrap();
+        assert!(import.skipped.is_empty());
+        let [meters, plain] = &import.aliases[..] else {
+            panic!("expected two aliases, got {}", import.aliases.len());
+        };
+        assert!(matches!(&meters.ast, TypeAstNode::Tuple { elements } if elements.len() == 1));
+        assert!(type_ast::structural_eq(&plain.ast, &primitive("f32")));
+    }
+}
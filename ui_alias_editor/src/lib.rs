@@ -6,10 +6,33 @@ pub mod constructor_palette;
 pub mod block_canvas;
 pub mod visual_editor;
 pub mod type_palette;
+pub mod codegen;
+pub mod compare;
+pub mod compile_check;
+pub mod const_eval;
+pub mod dsl;
+pub mod examples;
+pub mod explain;
+pub mod file_provider;
+pub mod project;
+pub mod recent;
+pub mod repair;
+pub mod type_ast;
+pub mod validation;
+pub mod import;
+pub mod placement;
+pub mod schema;
+pub mod settings;
+pub mod share;
+pub mod svg_export;
 
 // Export the visual editor as the main AliasEditor
-pub use visual_editor::{VisualAliasEditor as AliasEditor, ShowTypePickerRequest};
-pub use type_block::{TypeBlock, BlockId};
+pub use visual_editor::{VisualAliasEditor as AliasEditor, ShowTypePickerRequest, OpenAliasRequest, CompareAliasRequest, ShowRecentFilesRequest, ValidityChanged, AliasFileEvent, SaveAllRequest, SaveAllReport, save_all, init, is_locked};
+pub use type_block::{TypeBlock, BlockId, DraggedBlock};
 pub use constructor_palette::{ConstructorPalette, TypeSelected};
-pub use block_canvas::{BlockCanvas, DragState, DropTarget};
+pub use compare::AliasCompareView;
+pub use file_provider::{FileProvider, LocalFileProvider};
+pub use block_canvas::{BlockCanvas, CanvasHandlers, DragState, DropTarget, EmptyStateChoice};
 pub use type_palette::{TypeLibraryPalette, TypeItem};
+pub use recent::{RecentAliasPalette, set_recent_files_path};
+pub use settings::{EditorSetting, EditorSettings};
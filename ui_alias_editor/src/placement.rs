@@ -0,0 +1,126 @@
+// Lints for placements that are always wrong, keyed by parent constructor and child kind
+
+use crate::type_block::{primitive_kind, BlockId, TypeBlock};
+
+/// Constructors whose param may be unsized because it sits behind a pointer (or is never stored)
+const INDIRECTION_CONSTRUCTORS: &[&str] = &["Box", "Rc", "Arc", "Weak", "PhantomData"];
+
+/// Which parents a rule applies to
+#[derive(Clone, Copy, Debug)]
+pub enum ParentMatch {
+    /// Constructors with one of these names
+    Named(&'static [&'static str]),
+    /// Every parent except these constructors, including tuples, fn pointers and the root
+    Except(&'static [&'static str]),
+}
+
+/// Broad shape of a child block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChildKind {
+    FnPointer,
+    TraitObject,
+    /// `f32` or `f64`
+    Float,
+}
+
+impl ChildKind {
+    pub fn matches(&self, block: &TypeBlock) -> bool {
+        match self {
+            ChildKind::FnPointer => matches!(block, TypeBlock::FnPointer { .. }),
+            ChildKind::TraitObject => block.is_trait_object(),
+            ChildKind::Float => matches!(block, TypeBlock::Primitive { name, .. } if primitive_kind(name) == Some("float")),
+        }
+    }
+}
+
+/// A known-bad placement of a kind of child under a parent
+#[derive(Debug)]
+pub struct PlacementRule {
+    pub parent: ParentMatch,
+    /// Param index the rule applies to; every slot when unset
+    pub slot: Option<usize>,
+    pub child: ChildKind,
+    /// Short text for the block badge
+    pub badge: &'static str,
+    /// Full explanation for the diagnostics banner
+    pub reason: &'static str,
+}
+
+impl PlacementRule {
+    fn applies(&self, parent: Option<&TypeBlock>, slot: usize, child: &TypeBlock) -> bool {
+        let parent_name = match parent {
+            Some(TypeBlock::Constructor { name, .. }) => Some(name.as_str()),
+            _ => None,
+        };
+        let parent_matches = match self.parent {
+            ParentMatch::Named(names) => parent_name.is_some_and(|name| names.contains(&name)),
+            ParentMatch::Except(names) => !parent_name.is_some_and(|name| names.contains(&name)),
+        };
+        parent_matches && self.slot.is_none_or(|s| s == slot) && self.child.matches(child)
+    }
+}
+
+pub const PLACEMENT_RULES: &[PlacementRule] = &[
+    PlacementRule {
+        parent: ParentMatch::Except(INDIRECTION_CONSTRUCTORS),
+        slot: None,
+        child: ChildKind::TraitObject,
+        badge: "unsized - wrap in Box",
+        reason: "trait objects are unsized and must sit behind a pointer such as Box, Rc or Arc",
+    },
+    PlacementRule {
+        parent: ParentMatch::Named(&["HashMap", "HashSet", "IndexMap", "IndexSet"]),
+        slot: Some(0),
+        child: ChildKind::Float,
+        badge: "floats can't be hashed",
+        reason: "f32 and f64 implement neither Eq nor Hash, so they cannot be hashed keys",
+    },
+    PlacementRule {
+        parent: ParentMatch::Named(&["BTreeMap", "BTreeSet", "BinaryHeap"]),
+        slot: Some(0),
+        child: ChildKind::Float,
+        badge: "floats aren't Ord",
+        reason: "f32 and f64 do not implement Ord, which ordered collections require of their keys",
+    },
+    PlacementRule {
+        parent: ParentMatch::Named(&["HashMap", "HashSet", "BTreeMap", "BTreeSet", "IndexMap", "IndexSet"]),
+        slot: Some(0),
+        child: ChildKind::FnPointer,
+        badge: "fn pointer key",
+        reason: "fn pointers compare by address, and the same function is not guaranteed a single address, so lookups can miss",
+    },
+];
+
+/// A rule broken by a block in the tree
+#[derive(Clone, Debug)]
+pub struct PlacementFinding {
+    pub block: BlockId,
+    /// The parent's name, or `None` at the root
+    pub parent: Option<String>,
+    pub child: String,
+    pub rule: &'static PlacementRule,
+}
+
+/// Check every block against `PLACEMENT_RULES`. A block breaking several rules is
+/// reported for the first only.
+pub fn check_placements(root: &TypeBlock) -> Vec<PlacementFinding> {
+    let mut findings = Vec::new();
+    check_block(None, 0, root, &mut findings);
+    findings
+}
+
+fn check_block(parent: Option<&TypeBlock>, slot: usize, block: &TypeBlock, out: &mut Vec<PlacementFinding>) {
+    if let Some(rule) = PLACEMENT_RULES.iter().find(|rule| rule.applies(parent, slot, block)) {
+        out.push(PlacementFinding {
+            block: block.id().clone(),
+            parent: parent.map(TypeBlock::display_name),
+            child: block.display_name(),
+            rule,
+        });
+    }
+    for index in 0..block.slot_count() {
+        if let Some(child) = block.get_slot(index) {
+            check_block(Some(block), index, child, out);
+        }
+    }
+}
@@ -0,0 +1,391 @@
+// Project-level helpers for locating and loading alias assets
+
+use serde::Deserialize;
+use ui_types_common::{AliasAsset, TypeAstNode};
+use crate::schema::JsonStyle;
+use crate::type_ast;
+use crate::type_block::primitive_kind;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File marking the root of a Pulsar project
+const PROJECT_MANIFEST: &str = "Pulsar.toml";
+
+/// Project-level alias editor configuration, next to `Pulsar.toml`
+const PROJECT_CONFIG_FILE: &str = "alias_editor.json";
+
+/// File name alias assets are stored under (`<TypeName>/alias.json`)
+pub const ALIAS_FILE_NAME: &str = "alias.json";
+
+/// Directories never scanned for alias assets
+const SKIPPED_DIRS: &[&str] = &["target", ".git", "node_modules"];
+
+/// Std constructors whose params only take some primitives: (constructor, slot, primitives)
+const BUILTIN_SLOT_CONSTRAINTS: &[(&str, usize, &[&str])] = &[
+    ("NonZero", 0, &["integer"]),
+    ("Wrapping", 0, &["integer"]),
+    ("Saturating", 0, &["integer"]),
+];
+
+/// Foreign primitive names and their Rust equivalents, used by `normalizePrimitives`
+/// unless the project sets its own `primitiveAliases`
+pub const DEFAULT_PRIMITIVE_ALIASES: &[(&str, &str)] = &[
+    ("int", "i32"),
+    ("integer", "i64"),
+    ("long", "i64"),
+    ("short", "i16"),
+    ("byte", "u8"),
+    ("float", "f64"),
+    ("double", "f64"),
+    ("boolean", "bool"),
+    ("string", "String"),
+];
+
+/// Walk up from `path` to the directory containing `Pulsar.toml`
+pub fn find_project_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(PROJECT_MANIFEST).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Check whether a path looks like an alias asset file
+pub fn is_alias_file(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()) == Some(ALIAS_FILE_NAME)
+        || path.extension().and_then(|e| e.to_str()) == Some("alias")
+}
+
+/// Recursively collect every alias asset file under `root`, sorted for stable output
+pub fn find_alias_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let skipped = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| SKIPPED_DIRS.contains(&n))
+                    .unwrap_or(false);
+                if !skipped {
+                    pending.push(path);
+                }
+            } else if is_alias_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Read and parse an alias asset file
+pub fn load_alias_asset(path: &Path) -> Result<AliasAsset, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    crate::schema::parse_alias_asset(&json)
+        .map(|parsed| parsed.asset)
+        .map_err(|e| format!("Failed to parse: {}", e))
+}
+
+/// An alias file that references another alias
+#[derive(Clone, Debug)]
+pub struct AliasUsage {
+    pub path: PathBuf,
+    pub display_name: String,
+    /// The referencing alias's full type, showing where the reference sits
+    pub rust_type: String,
+}
+
+/// Every alias file under `root` whose tree references `alias`, in path order.
+/// Uses the same reference walk as cycle detection.
+pub fn find_usages(root: &Path, alias: &str) -> Vec<AliasUsage> {
+    find_alias_files(root)
+        .into_iter()
+        .filter_map(|path| {
+            let asset = load_alias_asset(&path).ok()?;
+            let mut refs = Vec::new();
+            crate::codegen::collect_alias_refs(&asset.ast, &mut refs);
+            if asset.display_name == alias || !refs.iter().any(|r| r == alias) {
+                return None;
+            }
+            Some(AliasUsage {
+                path,
+                display_name: asset.display_name,
+                rust_type: crate::codegen::ast_to_rust_string(&asset.ast),
+            })
+        })
+        .collect()
+}
+
+/// Why `inline_alias_into_usages` stopped, and the files it had already rewritten
+#[derive(Clone, Debug)]
+pub struct InlineFailure {
+    pub rewritten: Vec<PathBuf>,
+    pub message: String,
+}
+
+/// Replace references to `alias` with `definition` in every alias file under `root`,
+/// rewriting each changed file and its generated code. Returns the files changed.
+pub fn inline_alias_into_usages(root: &Path, alias: &str, definition: &TypeAstNode) -> Result<Vec<PathBuf>, InlineFailure> {
+    let config = ProjectTypeConfig::load(root);
+    let mut changed = Vec::new();
+    for usage in find_usages(root, alias) {
+        match inline_into_file(&usage.path, alias, definition, &config) {
+            Ok(()) => changed.push(usage.path),
+            Err(message) => return Err(InlineFailure { rewritten: changed, message }),
+        }
+    }
+    Ok(changed)
+}
+
+fn inline_into_file(path: &Path, alias: &str, definition: &TypeAstNode, config: &ProjectTypeConfig) -> Result<(), String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut parsed = crate::schema::parse_alias_asset(&json)?;
+    parsed.asset.ast = crate::type_ast::inline_alias(&parsed.asset.ast, alias, definition);
+
+    let json = crate::schema::alias_asset_to_json(&parsed.asset, &parsed.extra_fields, config.json_style)?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    let options = crate::codegen::ExportOptions::from_meta(&parsed.asset.meta).with_template(config);
+    let (output, code) = crate::codegen::generated_file(path, &parsed.asset, &options);
+    crate::codegen::write_generated_file(&output, &code)
+}
+
+/// Index of the aliases in a project, mapping type names to their asset files
+#[derive(Clone, Debug, Default)]
+pub struct AliasIndex {
+    entries: HashMap<String, PathBuf>,
+}
+
+impl AliasIndex {
+    /// Scan a project for alias assets
+    pub fn build(root: &Path) -> Self {
+        let entries = find_alias_files(root)
+            .into_iter()
+            .filter_map(|path| {
+                let asset = load_alias_asset(&path).ok()?;
+                Some((asset.display_name, path))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Build the index for the project containing `path`, if any
+    pub fn for_file(path: &Path) -> Option<Self> {
+        find_project_root(path).map(|root| Self::build(&root))
+    }
+
+    /// Asset file defining the alias with this type name
+    pub fn resolve(&self, name: &str) -> Option<&Path> {
+        self.entries.get(name).map(PathBuf::as_path)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// All alias type names in the project
+    pub fn names(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    /// Load every indexed alias, skipping files that no longer parse
+    pub fn load_assets(&self) -> Vec<AliasAsset> {
+        self.entries
+            .values()
+            .filter_map(|path| load_alias_asset(path).ok())
+            .collect()
+    }
+}
+
+/// Project configuration for the alias editor, read from `alias_editor.json`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectTypeConfig {
+    /// When set, only these primitive and constructor names may be used
+    pub allowed_types: Option<Vec<String>>,
+    /// Primitive and constructor names that may not be used
+    pub denied_types: Vec<String>,
+    /// How alias files are written: `"pretty"` (default) or `"compact"`
+    pub json_style: JsonStyle,
+    /// Project generic types offered in the type picker alongside the std constructors
+    pub constructors: Vec<CustomConstructor>,
+    /// Warn when constructors nest deeper than this; no limit when unset
+    pub max_nesting_depth: Option<usize>,
+    /// Picker icon per category, by icon name (`{"Collections": "Folder"}`)
+    pub category_icons: HashMap<String, String>,
+    /// Layout of generated alias items, e.g. to add header comments or attributes.
+    /// Placeholders: `{name}`, `{type}`, `{docs}`, `{attrs}`, `{visibility}`, with `{{` and `}}` for literal
+    /// braces; see `codegen::DEFAULT_ALIAS_TEMPLATE`
+    pub alias_template: Option<String>,
+    /// Attribute emitted for stability markers in `attribute` mode, e.g.
+    /// `#[stability::{stability}(since = "{since}")]`. Deprecated aliases always get `#[deprecated]`.
+    pub stability_attribute: Option<String>,
+    /// Rewrite foreign primitive names like `int` to Rust ones on load and import
+    pub normalize_primitives: bool,
+    /// Name table for `normalize_primitives`; `DEFAULT_PRIMITIVE_ALIASES` when empty
+    pub primitive_aliases: HashMap<String, String>,
+}
+
+/// A generic type defined by the project, declared in `alias_editor.json`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomConstructor {
+    /// Name or path as written in code (`Handle`, `crate::grid::Grid`)
+    pub name: String,
+    pub params_count: usize,
+    /// Picker category, `Project` when not given
+    #[serde(default = "default_constructor_category")]
+    pub category: String,
+    #[serde(default)]
+    pub description: String,
+    /// Param positions that take a const value instead of a type (`[1]` for `Grid<T, N>`)
+    #[serde(default)]
+    pub const_params: Vec<usize>,
+    /// Lifetime params before the type params (`1` for `View<'a, T>`)
+    #[serde(default)]
+    pub lifetimes: usize,
+    /// What each constrained param accepts, keyed by position
+    #[serde(default)]
+    pub slot_constraints: HashMap<usize, SlotConstraint>,
+    /// Crate the type comes from, for grouping the picker by crate; `project` when not given
+    #[serde(default = "default_constructor_crate", rename = "crate")]
+    pub crate_name: String,
+}
+
+/// What a constructor param accepts. A constraint with both lists empty accepts anything;
+/// otherwise only the listed primitives and constructors are accepted. Alias references
+/// are always accepted since what they resolve to is not known here.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlotConstraint {
+    /// Primitive names, or kinds: `"signed"`, `"unsigned"`, `"integer"`, `"float"`
+    pub primitives: Vec<String>,
+    /// Constructor and path names
+    pub constructors: Vec<String>,
+}
+
+impl SlotConstraint {
+    fn is_unconstrained(&self) -> bool {
+        self.primitives.is_empty() && self.constructors.is_empty()
+    }
+
+    pub fn allows_primitive(&self, name: &str) -> bool {
+        if self.is_unconstrained() {
+            return true;
+        }
+        let kind = primitive_kind(name);
+        self.primitives.iter().any(|allowed| {
+            allowed == name
+                || kind == Some(allowed.as_str())
+                || (allowed == "integer" && matches!(kind, Some("signed" | "unsigned")))
+        })
+    }
+
+    pub fn allows_constructor(&self, name: &str) -> bool {
+        self.is_unconstrained() || self.constructors.iter().any(|allowed| allowed == name)
+    }
+
+    /// Check the type in a slot against the constraint
+    pub fn allows(&self, ast: &TypeAstNode) -> bool {
+        match ast {
+            TypeAstNode::Primitive { name } => self.allows_primitive(name),
+            TypeAstNode::Constructor { name, .. } => self.allows_constructor(name),
+            TypeAstNode::Path { .. } if type_ast::const_arg_expr(ast).is_some() => true,
+            TypeAstNode::Path { path } => self.allows_constructor(path),
+            TypeAstNode::Tuple { .. } | TypeAstNode::FnPointer { .. } => self.is_unconstrained(),
+            TypeAstNode::AliasRef { .. } | TypeAstNode::None => true,
+        }
+    }
+
+    /// What the slot accepts, for messages: "integer, f64 or Duration"
+    pub fn describe(&self) -> String {
+        let names: Vec<&str> = self.primitives.iter().chain(&self.constructors).map(String::as_str).collect();
+        match names.split_last() {
+            None => "anything".to_string(),
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        }
+    }
+}
+
+fn default_constructor_category() -> String {
+    "Project".to_string()
+}
+
+fn default_constructor_crate() -> String {
+    "project".to_string()
+}
+
+impl ProjectTypeConfig {
+    /// Load the config from a project root, using defaults if it is missing or invalid
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(PROJECT_CONFIG_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load the config for the project containing `path`
+    pub fn for_file(path: &Path) -> Self {
+        find_project_root(path)
+            .map(|root| Self::load(&root))
+            .unwrap_or_default()
+    }
+
+    /// Apply the primitive name table if normalization is on. Returns the rewritten tree
+    /// and each distinct `(from, to)` rename made.
+    pub fn normalize_primitives(&self, ast: &TypeAstNode) -> (TypeAstNode, Vec<(String, String)>) {
+        if !self.normalize_primitives {
+            return (ast.clone(), Vec::new());
+        }
+        let mut renamed = Vec::new();
+        let ast = type_ast::rename_primitives(ast, &self.primitive_alias_table(), &mut renamed);
+        (ast, renamed)
+    }
+
+    /// The primitive name table, whether or not normalization is on
+    pub fn primitive_alias_table(&self) -> HashMap<String, String> {
+        if self.primitive_aliases.is_empty() {
+            DEFAULT_PRIMITIVE_ALIASES
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect()
+        } else {
+            self.primitive_aliases.clone()
+        }
+    }
+
+    /// Constraint on param `slot` of `constructor`, from the project config or the std table
+    pub fn slot_constraint(&self, constructor: &str, slot: usize) -> Option<SlotConstraint> {
+        let custom = self
+            .constructors
+            .iter()
+            .find(|custom| custom.name == constructor)
+            .and_then(|custom| custom.slot_constraints.get(&slot).cloned());
+        custom.or_else(|| {
+            BUILTIN_SLOT_CONSTRAINTS
+                .iter()
+                .find(|(name, index, _)| *name == constructor && *index == slot)
+                .map(|(_, _, primitives)| SlotConstraint {
+                    primitives: primitives.iter().map(|p| p.to_string()).collect(),
+                    constructors: Vec::new(),
+                })
+        })
+    }
+
+    /// Check a primitive or constructor name against the allow and deny lists
+    pub fn is_type_allowed(&self, name: &str) -> bool {
+        if self.denied_types.iter().any(|denied| denied == name) {
+            return false;
+        }
+        match &self.allowed_types {
+            Some(allowed) => allowed.iter().any(|a| a == name),
+            None => true,
+        }
+    }
+}
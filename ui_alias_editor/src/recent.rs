@@ -0,0 +1,147 @@
+// Most recently opened alias files, for the quick switcher
+
+use gpui::{App, Global};
+use ui::IconName;
+use ui_common::command_palette::{PaletteDelegate, PaletteItem};
+use std::path::{Path, PathBuf};
+use crate::project;
+
+/// How many files the switcher remembers
+const RECENT_LIMIT: usize = 20;
+
+/// Recently opened alias files shared by every editor, held as a gpui global
+#[derive(Default)]
+struct RecentFiles {
+    /// Most recent first
+    files: Vec<PathBuf>,
+    /// Where the list is persisted; session-only until the host sets it
+    path: Option<PathBuf>,
+}
+
+impl Global for RecentFiles {}
+
+/// Persist the recent files list at `path`, e.g. in the host's settings directory.
+/// A list already stored there is merged behind the files opened this session.
+pub fn set_recent_files_path(path: PathBuf, cx: &mut App) {
+    let stored: Vec<PathBuf> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let recent = cx.default_global::<RecentFiles>();
+    for file in stored {
+        if !recent.files.contains(&file) && recent.files.len() < RECENT_LIMIT {
+            recent.files.push(file);
+        }
+    }
+    recent.path = Some(path);
+}
+
+/// Move `path` to the front of the recent files and persist the list
+pub fn record_opened(path: &Path, cx: &mut App) {
+    let recent = cx.default_global::<RecentFiles>();
+    recent.files.retain(|file| file != path);
+    recent.files.insert(0, path.to_path_buf());
+    recent.files.truncate(RECENT_LIMIT);
+
+    if let (Some(target), Ok(json)) = (&recent.path, serde_json::to_string_pretty(&recent.files)) {
+        // Losing the list is harmless, so write failures are ignored
+        let _ = std::fs::write(target, json);
+    }
+}
+
+/// Recent files that still exist, most recent first
+pub fn recent_files(cx: &App) -> Vec<PathBuf> {
+    cx.try_global::<RecentFiles>()
+        .map(|recent| recent.files.iter().filter(|file| file.is_file()).cloned().collect())
+        .unwrap_or_default()
+}
+
+#[derive(Clone)]
+pub struct RecentFileItem {
+    pub path: PathBuf,
+    name: String,
+    location: String,
+}
+
+impl RecentFileItem {
+    fn new(path: PathBuf) -> Self {
+        let name = project::load_alias_asset(&path)
+            .map(|asset| asset.display_name)
+            .unwrap_or_else(|_| path.display().to_string());
+        let location = project::find_project_root(&path)
+            .and_then(|root| path.strip_prefix(&root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| path.clone())
+            .display()
+            .to_string();
+        Self { path, name, location }
+    }
+}
+
+impl PaletteItem for RecentFileItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.location
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::File
+    }
+
+    fn keywords(&self) -> Vec<&str> {
+        vec![self.location.as_str()]
+    }
+
+    fn documentation(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Quick switcher over the recent alias files. Confirming stores the file for
+/// `take_selected_path`; pass it to the editor's `open_recent`.
+pub struct RecentAliasPalette {
+    items: Vec<RecentFileItem>,
+    selected_path: Option<PathBuf>,
+}
+
+impl RecentAliasPalette {
+    /// List the recent files, leaving out `current`, the file already being edited
+    pub fn new(current: Option<&Path>, cx: &App) -> Self {
+        let items = recent_files(cx)
+            .into_iter()
+            .filter(|file| Some(file.as_path()) != current)
+            .map(RecentFileItem::new)
+            .collect();
+        Self { items, selected_path: None }
+    }
+
+    pub fn take_selected_path(&mut self) -> Option<PathBuf> {
+        self.selected_path.take()
+    }
+}
+
+impl PaletteDelegate for RecentAliasPalette {
+    type Item = RecentFileItem;
+
+    fn placeholder(&self) -> &str {
+        "Open recent alias..."
+    }
+
+    fn categories(&self) -> Vec<(String, Vec<Self::Item>)> {
+        vec![("Recent".to_string(), self.items.clone())]
+    }
+
+    fn confirm(&mut self, item: &Self::Item) {
+        self.selected_path = Some(item.path.clone());
+    }
+
+    fn categories_collapsed_by_default(&self) -> bool {
+        false
+    }
+
+    fn supports_docs(&self) -> bool {
+        false
+    }
+}
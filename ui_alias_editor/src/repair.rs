@@ -0,0 +1,171 @@
+// Fixes offered for problems found when an alias is opened
+
+use ui_types_common::TypeAstNode;
+use crate::codegen;
+use crate::import;
+use crate::project::ProjectTypeConfig;
+use crate::type_ast;
+use crate::type_block::TypeBlock;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A fix for one kind of problem in an alias
+#[derive(Clone, Debug, PartialEq)]
+pub enum Repair {
+    /// `constructor` is used with `found` params but takes `expected`. Missing params
+    /// become empty slots and extra params are dropped.
+    Arity { constructor: String, found: usize, expected: usize },
+    /// Foreign primitive names to rename, e.g. `int` → `i32`
+    NormalizePrimitives(Vec<(String, String)>),
+    /// Declared generic params the tree never uses
+    UnusedGenerics(Vec<String>),
+}
+
+impl Repair {
+    /// Whether the repair keeps everything the user wrote. Dropping extra params loses
+    /// types, and a foreign primitive name may really be a project type called `int`, so
+    /// those are left for the user to confirm.
+    pub fn is_safe(&self) -> bool {
+        match self {
+            Repair::Arity { found, expected, .. } => found <= expected,
+            Repair::NormalizePrimitives(_) => false,
+            Repair::UnusedGenerics(_) => true,
+        }
+    }
+
+    /// Apply the repair to a tree and its meta. Empty slots in `ast` are `TypeAstNode::None`,
+    /// see `TypeBlock::to_ast_with_holes`.
+    pub fn apply(&self, ast: &TypeAstNode, meta: &mut serde_json::Value) -> TypeAstNode {
+        match self {
+            Repair::Arity { constructor, found, expected } => fix_arity(ast, constructor, *found, *expected),
+            Repair::NormalizePrimitives(renames) => {
+                let table: HashMap<String, String> = renames.iter().cloned().collect();
+                type_ast::rename_primitives(ast, &table, &mut Vec::new())
+            }
+            Repair::UnusedGenerics(unused) => {
+                let generics: Vec<String> = codegen::declared_generics(meta)
+                    .into_iter()
+                    .filter(|param| !unused.contains(param))
+                    .collect();
+                codegen::set_declared_generics(meta, &generics);
+                for param in unused {
+                    codegen::remove_generic_bounds(meta, param);
+                }
+                ast.clone()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Arity { constructor, found, expected } if found < expected => write!(
+                f,
+                "`{}` has {} param(s) but takes {} - add empty slots for the rest",
+                constructor, found, expected
+            ),
+            Self::Arity { constructor, found, expected } => write!(
+                f,
+                "`{}` has {} params but takes {} - drop the extra params",
+                constructor, found, expected
+            ),
+            Self::NormalizePrimitives(renames) => write!(
+                f,
+                "Rename {}",
+                renames
+                    .iter()
+                    .map(|(from, to)| format!("`{}` → `{}`", from, to))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::UnusedGenerics(unused) => write!(
+                f,
+                "Remove unused generic params {}",
+                unused.iter().map(|param| format!("`{}`", param)).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+/// Find every problem in `ast` that has a repair. Empty slots may be `TypeAstNode::None`.
+pub fn find_repairs(ast: &TypeAstNode, meta: &serde_json::Value, config: &ProjectTypeConfig) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let generics = codegen::declared_generics(meta);
+
+    let mut param_counts: HashMap<String, usize> = pulsar_std::get_all_type_constructors()
+        .iter()
+        .map(|ctor| (ctor.name.to_string(), ctor.params_count))
+        .collect();
+    param_counts.extend(config.constructors.iter().map(|ctor| (ctor.name.clone(), ctor.params_count)));
+    import::visit_nodes(ast, &mut |node| {
+        let TypeAstNode::Constructor { name, params, .. } = node else {
+            return;
+        };
+        if codegen::FN_TRAIT_CONSTRUCTORS.contains(&name.as_str()) {
+            // Any number of arguments, the output comes last
+            return;
+        }
+        let Some(&max) = param_counts.get(name) else {
+            return;
+        };
+        let min = TypeBlock::required_param_count(name).unwrap_or(max).min(max);
+        let found = params.len();
+        let expected = found.clamp(min, max);
+        let repair = Repair::Arity { constructor: name.clone(), found, expected };
+        if found != expected && !repairs.contains(&repair) {
+            repairs.push(repair);
+        }
+    });
+
+    let mut table = config.primitive_alias_table();
+    table.retain(|from, _| !generics.contains(from));
+    let mut renamed = Vec::new();
+    type_ast::rename_primitives(ast, &table, &mut renamed);
+    if !renamed.is_empty() {
+        repairs.push(Repair::NormalizePrimitives(renamed));
+    }
+
+    let mut names = Vec::new();
+    type_ast::collect_type_names(ast, &mut names);
+    let unused: Vec<String> = generics
+        .into_iter()
+        .filter(|param| {
+            // `T::Output` is stored as a path and still uses `T`
+            let prefix = format!("{}::", param);
+            !names.iter().any(|name| name == param || name.starts_with(&prefix))
+        })
+        .collect();
+    if !unused.is_empty() {
+        repairs.push(Repair::UnusedGenerics(unused));
+    }
+
+    repairs
+}
+
+/// Resize the params of every `constructor` that has `found` of them to `expected`
+fn fix_arity(node: &TypeAstNode, constructor: &str, found: usize, expected: usize) -> TypeAstNode {
+    let fix = |children: &[TypeAstNode]| -> Vec<TypeAstNode> {
+        children.iter().map(|child| fix_arity(child, constructor, found, expected)).collect()
+    };
+    match node {
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => {
+            let mut params = fix(params);
+            if name == constructor && params.len() == found {
+                params.resize(expected, TypeAstNode::None);
+            }
+            TypeAstNode::Constructor {
+                name: name.clone(),
+                params,
+                lifetimes: lifetimes.clone(),
+                const_generics: const_generics.clone(),
+            }
+        }
+        TypeAstNode::Tuple { elements } => TypeAstNode::Tuple { elements: fix(elements) },
+        TypeAstNode::FnPointer { params, return_type } => TypeAstNode::FnPointer {
+            params: fix(params),
+            return_type: Box::new(fix_arity(return_type, constructor, found, expected)),
+        },
+        _ => node.clone(),
+    }
+}
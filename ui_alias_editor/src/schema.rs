@@ -0,0 +1,172 @@
+// Alias asset schema versions and migrations
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use ui_types_common::AliasAsset;
+
+/// Migrations indexed by source version: `MIGRATIONS[0]` upgrades version 1 to 2
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v1_to_v2];
+
+/// Schema version written by this editor
+pub const CURRENT_SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// How alias assets are formatted on disk. Keys are sorted either way, so
+/// saving an unchanged asset rewrites the file byte for byte.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JsonStyle {
+    /// Indented, one field per line
+    #[default]
+    Pretty,
+    /// Minified on a single line
+    Compact,
+}
+
+/// Version 2 always stores `meta` as an object with explicit export options
+fn migrate_v1_to_v2(asset: &mut Value) {
+    let Some(object) = asset.as_object_mut() else {
+        return;
+    };
+
+    let meta = object
+        .entry("meta")
+        .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    if !meta.is_object() {
+        *meta = Value::Object(serde_json::Map::new());
+    }
+    if let Some(meta) = meta.as_object_mut() {
+        meta.entry("export")
+            .or_insert_with(|| serde_json::json!({ "emitConsts": false }));
+    }
+}
+
+/// An alias asset as read from disk
+pub struct ParsedAsset {
+    pub asset: AliasAsset,
+    /// A migration was applied, so the file on disk is outdated
+    pub migrated: bool,
+    /// Top-level fields `AliasAsset` doesn't know, written back on save so
+    /// newer editors and external tools don't lose data
+    pub extra_fields: Map<String, Value>,
+}
+
+/// Parse an alias asset, upgrading older schema versions
+pub fn parse_alias_asset(json: &str) -> Result<ParsedAsset, String> {
+    let mut value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    let version = value
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .unwrap_or(1) as u32;
+
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "File is from a newer version of the editor (schema {}, this editor supports up to {})",
+            version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    let migrated = version < CURRENT_SCHEMA_VERSION;
+    for migration in &MIGRATIONS[(version.max(1) - 1) as usize..] {
+        migration(&mut value);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("schemaVersion".to_string(), Value::from(CURRENT_SCHEMA_VERSION));
+    }
+
+    let asset: AliasAsset = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+
+    // Whatever doesn't survive a serialize of the parsed asset is unknown to us
+    let known = serde_json::to_value(&asset).map_err(|e| e.to_string())?;
+    let extra_fields = match (value, known) {
+        (Value::Object(fields), Value::Object(known)) => fields
+            .into_iter()
+            .filter(|(key, _)| !known.contains_key(key))
+            .collect(),
+        _ => Map::new(),
+    };
+
+    Ok(ParsedAsset { asset, migrated, extra_fields })
+}
+
+/// Serialize an asset for saving, putting back fields captured on load.
+/// Known fields always win over extras with the same name.
+pub fn alias_asset_to_json(
+    asset: &AliasAsset,
+    extra_fields: &Map<String, Value>,
+    style: JsonStyle,
+) -> Result<String, String> {
+    let mut value = serde_json::to_value(asset).map_err(|e| format!("Failed to serialize: {}", e))?;
+    if let Value::Object(fields) = &mut value {
+        for (key, extra) in extra_fields {
+            fields.entry(key.clone()).or_insert_with(|| extra.clone());
+        }
+    }
+    let value = sort_keys(value);
+    let json = match style {
+        JsonStyle::Pretty => serde_json::to_string_pretty(&value),
+        JsonStyle::Compact => serde_json::to_string(&value),
+    };
+    json.map(|json| json + "\n")
+        .map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Rebuild every object with its keys in sorted order. `Map` is only sorted by
+/// default; with serde_json's `preserve_order` feature it keeps insertion order.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields.into_iter().collect();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(fields.into_iter().map(|(key, value)| (key, sort_keys(value))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET_WITH_EXTRAS: &str = r#"{
+        "schemaVersion": 2,
+        "typeKind": "alias",
+        "name": "entity_id",
+        "displayName": "EntityId",
+        "ast": { "nodeKind": "Primitive", "name": "u64" },
+        "meta": { "export": { "emitConsts": false } },
+        "toolVersion": "3.1",
+        "lint": { "ignore": ["unused"] }
+    }"#;
+
+    #[test]
+    fn unknown_fields_survive_a_save() {
+        let parsed = parse_alias_asset(ASSET_WITH_EXTRAS).unwrap();
+        assert!(!parsed.migrated);
+        assert_eq!(parsed.extra_fields.len(), 2);
+
+        let json = alias_asset_to_json(&parsed.asset, &parsed.extra_fields, JsonStyle::Pretty).unwrap();
+        let saved: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(saved["toolVersion"], "3.1");
+        assert_eq!(saved["lint"], serde_json::json!({ "ignore": ["unused"] }));
+        assert_eq!(saved["displayName"], "EntityId");
+
+        // A second load and save changes nothing
+        let reparsed = parse_alias_asset(&json).unwrap();
+        assert_eq!(reparsed.extra_fields, parsed.extra_fields);
+        assert_eq!(alias_asset_to_json(&reparsed.asset, &reparsed.extra_fields, JsonStyle::Pretty).unwrap(), json);
+    }
+
+    #[test]
+    fn known_fields_win_over_extras() {
+        let parsed = parse_alias_asset(ASSET_WITH_EXTRAS).unwrap();
+        let mut extras = parsed.extra_fields.clone();
+        extras.insert("displayName".to_string(), Value::from("Stale"));
+
+        let json = alias_asset_to_json(&parsed.asset, &extras, JsonStyle::Compact).unwrap();
+        let saved: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(saved["displayName"], "EntityId");
+        assert_eq!(saved["toolVersion"], "3.1");
+    }
+}
@@ -0,0 +1,176 @@
+// Editor toggles a file can override, stored in `AliasAsset.meta` and falling back to the global defaults
+
+use gpui::{App, Global};
+use serde_json::Value;
+use crate::type_block::LayoutDirection;
+
+/// Key in `AliasAsset.meta` holding the file's overrides, e.g. `{"showSizes": true}`
+pub const SETTINGS_META_KEY: &str = "editorSettings";
+
+/// A toggle with a global default that a file may override
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditorSetting {
+    ShowSizes,
+    HighContrast,
+    OpenPickerOnClick,
+    ConfirmGeneratedCode,
+    DedupeOnSave,
+    ValidateOnLoad,
+}
+
+impl EditorSetting {
+    pub const ALL: [EditorSetting; 6] = [
+        EditorSetting::ShowSizes,
+        EditorSetting::HighContrast,
+        EditorSetting::OpenPickerOnClick,
+        EditorSetting::ConfirmGeneratedCode,
+        EditorSetting::DedupeOnSave,
+        EditorSetting::ValidateOnLoad,
+    ];
+
+    /// Field name inside the meta object
+    pub fn key(self) -> &'static str {
+        match self {
+            EditorSetting::ShowSizes => "showSizes",
+            EditorSetting::HighContrast => "highContrast",
+            EditorSetting::OpenPickerOnClick => "openPickerOnClick",
+            EditorSetting::ConfirmGeneratedCode => "confirmGeneratedCode",
+            EditorSetting::DedupeOnSave => "dedupeOnSave",
+            EditorSetting::ValidateOnLoad => "validateOnLoad",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EditorSetting::ShowSizes => "Show sizes",
+            EditorSetting::HighContrast => "High contrast",
+            EditorSetting::OpenPickerOnClick => "Picker on click",
+            EditorSetting::ConfirmGeneratedCode => "Review code on every save",
+            EditorSetting::DedupeOnSave => "Offer to extract repeats on save",
+            EditorSetting::ValidateOnLoad => "Validate on open",
+        }
+    }
+}
+
+/// Session-wide settings shared by every alias editor, held as a gpui global. Change
+/// them with `update_global`; files fall back to these for each `EditorSetting`.
+#[derive(Clone, Debug)]
+pub struct EditorSettings {
+    /// Primitive size annotations on the canvas
+    pub show_sizes: bool,
+    /// Accessibility high-contrast rendering, e.g. from the host's accessibility settings
+    pub high_contrast: bool,
+    /// Open the type picker when an empty slot is clicked. When off, clicking only selects
+    /// the slot so it can be filled by pasting or repeating the last type.
+    pub open_picker_on_click: bool,
+    /// Review the generated `.rs` before every save, not only when the file is first created
+    pub confirm_generated_code: bool,
+    /// Offer to extract subtrees repeated within the alias into a helper alias when saving
+    pub dedupe_on_save: bool,
+    /// Validate an alias as soon as it is opened and offer repairs for what can be fixed
+    pub validate_on_load: bool,
+    /// Canvas layout for files that don't pin a direction. A view preference, so
+    /// changing it never touches the file.
+    pub layout_direction: LayoutDirection,
+    /// Pre-fill common slots when placing constructors, e.g. `String` keys for maps
+    pub smart_defaults: bool,
+    /// Group the picker's constructors by the crate they come from
+    pub group_by_crate: bool,
+}
+
+/// Settings until the host or the user changes them
+static DEFAULT_SETTINGS: EditorSettings = EditorSettings {
+    show_sizes: false,
+    high_contrast: false,
+    open_picker_on_click: true,
+    confirm_generated_code: false,
+    dedupe_on_save: true,
+    validate_on_load: true,
+    layout_direction: LayoutDirection::TopDown,
+    smart_defaults: true,
+    group_by_crate: false,
+};
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        DEFAULT_SETTINGS.clone()
+    }
+}
+
+impl Global for EditorSettings {}
+
+impl EditorSettings {
+    /// The current settings, or the defaults if nothing has changed them yet
+    pub fn global(cx: &App) -> &Self {
+        cx.try_global::<Self>().unwrap_or(&DEFAULT_SETTINGS)
+    }
+
+    /// Change the settings for every editor. Open editors pick the change up on their next render.
+    pub fn update_global(cx: &mut App, update: impl FnOnce(&mut Self)) {
+        update(cx.default_global::<Self>());
+    }
+
+    /// The session-wide value of a toggle, used by files that don't override it
+    pub fn get(&self, setting: EditorSetting) -> bool {
+        match setting {
+            EditorSetting::ShowSizes => self.show_sizes,
+            EditorSetting::HighContrast => self.high_contrast,
+            EditorSetting::OpenPickerOnClick => self.open_picker_on_click,
+            EditorSetting::ConfirmGeneratedCode => self.confirm_generated_code,
+            EditorSetting::DedupeOnSave => self.dedupe_on_save,
+            EditorSetting::ValidateOnLoad => self.validate_on_load,
+        }
+    }
+
+    pub fn set(&mut self, setting: EditorSetting, enabled: bool) {
+        let field = match setting {
+            EditorSetting::ShowSizes => &mut self.show_sizes,
+            EditorSetting::HighContrast => &mut self.high_contrast,
+            EditorSetting::OpenPickerOnClick => &mut self.open_picker_on_click,
+            EditorSetting::ConfirmGeneratedCode => &mut self.confirm_generated_code,
+            EditorSetting::DedupeOnSave => &mut self.dedupe_on_save,
+            EditorSetting::ValidateOnLoad => &mut self.validate_on_load,
+        };
+        *field = enabled;
+    }
+}
+
+/// The file's value for `setting`, or `None` when it follows the global default
+pub fn file_override(meta: &Value, setting: EditorSetting) -> Option<bool> {
+    meta.get(SETTINGS_META_KEY)?.get(setting.key())?.as_bool()
+}
+
+/// Store or, with `None`, clear the file's value for `setting`. The settings object
+/// is dropped once it is empty so files without overrides keep a clean meta.
+pub fn set_file_override(meta: &mut Value, setting: EditorSetting, value: Option<bool>) {
+    let Some(map) = meta.as_object_mut() else {
+        return;
+    };
+    let settings = map
+        .entry(SETTINGS_META_KEY)
+        .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    if !settings.is_object() {
+        *settings = Value::Object(serde_json::Map::new());
+    }
+    if let Some(settings) = settings.as_object_mut() {
+        match value {
+            Some(value) => {
+                settings.insert(setting.key().to_string(), Value::Bool(value));
+            }
+            None => {
+                settings.remove(setting.key());
+            }
+        }
+        if settings.is_empty() {
+            map.remove(SETTINGS_META_KEY);
+        }
+    }
+}
+
+/// How many settings the file overrides
+pub fn override_count(meta: &Value) -> usize {
+    EditorSetting::ALL
+        .iter()
+        .filter(|setting| file_override(meta, **setting).is_some())
+        .count()
+}
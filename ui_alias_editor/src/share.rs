@@ -0,0 +1,117 @@
+// Short text encoding of a type tree, for pasting into chat
+
+use ui_types_common::TypeAstNode;
+
+/// Marks a share string so pasted text is recognised
+pub const SHARE_PREFIX: &str = "pulsar-type:";
+
+/// URL-safe base64 alphabet, so share strings survive links and chat clients
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// `pulsar-type:` followed by the minified AST JSON in unpadded URL-safe base64
+pub fn encode_share_string(ast: &TypeAstNode) -> String {
+    let json = serde_json::to_string(ast).unwrap_or_default();
+    format!("{}{}", SHARE_PREFIX, encode_base64(json.as_bytes()))
+}
+
+/// Read a string made by `encode_share_string`. Surrounding whitespace is ignored and the
+/// prefix may be left off. Type names are not checked; see `import::check_imported_ast`.
+pub fn decode_share_string(text: &str) -> Result<TypeAstNode, String> {
+    let text = text.trim();
+    let encoded = text.strip_prefix(SHARE_PREFIX).unwrap_or(text);
+    if encoded.is_empty() {
+        return Err("Share string is empty".to_string());
+    }
+    let bytes = decode_base64(encoded).ok_or("Share string is damaged or incomplete")?;
+    let json = String::from_utf8(bytes).map_err(|_| "Share string is damaged or incomplete".to_string())?;
+    serde_json::from_str(&json).map_err(|e| format!("Share string does not hold a type: {}", e))
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+        // n bytes carry n + 1 significant sextets
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let sextets = text
+        .bytes()
+        .map(|c| ALPHABET.iter().position(|a| *a == c).map(|i| i as u32))
+        .collect::<Option<Vec<_>>>()?;
+    let mut out = Vec::with_capacity(sextets.len() * 3 / 4);
+    for chunk in sextets.chunks(4) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, s)| acc | s << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            out.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import;
+    use crate::type_ast;
+
+    #[test]
+    fn base64_matches_the_reference_vectors() {
+        // RFC 4648 test vectors, unpadded
+        let vectors = [("", ""), ("f", "Zg"), ("fo", "Zm8"), ("foo", "Zm9v"), ("foob", "Zm9vYg"), ("fooba", "Zm9vYmE"), ("foobar", "Zm9vYmFy")];
+        for (plain, encoded) in vectors {
+            assert_eq!(encode_base64(plain.as_bytes()), encoded);
+            assert_eq!(decode_base64(encoded).as_deref(), Some(plain.as_bytes()));
+        }
+        // The URL-safe alphabet in place of `+` and `/`
+        assert_eq!(encode_base64(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_base64("-_8"), Some(vec![0xfb, 0xff]));
+    }
+
+    #[test]
+    fn every_byte_value_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..bytes.len() {
+            assert_eq!(decode_base64(&encode_base64(&bytes[..len])).as_deref(), Some(&bytes[..len]));
+        }
+    }
+
+    #[test]
+    fn share_strings_round_trip() {
+        let ast = import::type_from_rust("HashMap<String, Vec<(u8, Option<f32>)>>").unwrap();
+        let shared = encode_share_string(&ast);
+        assert!(shared.starts_with(SHARE_PREFIX));
+        assert!(type_ast::structural_eq(&decode_share_string(&shared).unwrap(), &ast));
+        // Whitespace from chat clients and a missing prefix are tolerated
+        let pasted = format!("  {}\n", &shared[SHARE_PREFIX.len()..]);
+        assert!(type_ast::structural_eq(&decode_share_string(&pasted).unwrap(), &ast));
+    }
+
+    #[test]
+    fn damaged_share_strings_are_rejected() {
+        let shared = encode_share_string(&import::type_from_rust("Vec<u8>").unwrap());
+        let damaged = [
+            String::new(),
+            SHARE_PREFIX.to_string(),
+            // Standard base64 characters are not in the alphabet
+            format!("{}+/", shared),
+            // One sextet short of a byte
+            format!("{}A", &shared[..shared.len() - shared.len() % 4]),
+            // Cut off part way through the JSON
+            shared[..shared.len() - 4].to_string(),
+            // Valid base64 that is not JSON
+            format!("{}{}", SHARE_PREFIX, encode_base64(b"not json")),
+        ];
+        for text in damaged {
+            assert!(decode_share_string(&text).is_err(), "{:?} decoded", text);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// SVG snapshot of the block tree, for slides and docs
+
+use gpui::{Bounds, Hsla, Pixels, Rgba};
+use ui::Colorize;
+use std::fmt::Write;
+use crate::type_block::{BlockLayout, TypeBlock};
+
+/// Space left around the tree
+const MARGIN: f32 = 16.0;
+
+/// Render the tree as SVG using the bounds recorded during the last layout pass, so the
+/// snapshot mirrors the canvas arrangement. Returns `None` if the tree has not been laid out.
+pub fn tree_to_svg(root: &TypeBlock, layout: &BlockLayout) -> Option<String> {
+    let root_bounds = *layout.blocks.get(root.id())?;
+    let origin = (f32::from(root_bounds.origin.x) - MARGIN, f32::from(root_bounds.origin.y) - MARGIN);
+    let width = f32::from(root_bounds.size.width) + MARGIN * 2.0;
+    let height = f32::from(root_bounds.size.height) + MARGIN * 2.0;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="sans-serif">"#,
+        w = width,
+        h = height
+    );
+    write_block(&mut svg, root, layout, origin);
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Emit a block, then its connectors, children and empty slots so children draw on top
+fn write_block(svg: &mut String, block: &TypeBlock, layout: &BlockLayout, origin: (f32, f32)) {
+    let Some(bounds) = layout.blocks.get(block.id()) else {
+        return;
+    };
+    let (x, y, w, h) = rect(bounds, origin);
+    let color = block.color().to_hsla();
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="6" fill="{}" stroke="{}" stroke-width="1.5"/>"#,
+        x, y, w, h, hex(color), hex(color.lighten(0.1))
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" font-size="13" font-weight="{}" fill="white">{}</text>"#,
+        x + 10.0,
+        y + 18.0,
+        if block.is_container() { "bold" } else { "normal" },
+        escape(&block.display_name())
+    );
+
+    for index in 0..block.slot_count() {
+        match block.get_slot(index) {
+            Some(child) => {
+                if let Some(child_bounds) = layout.blocks.get(child.id()) {
+                    write_connector(svg, (x, y), rect(child_bounds, origin), color);
+                }
+                write_block(svg, child, layout, origin);
+            }
+            None => {
+                let Some(slot_bounds) = layout.slots.get(&(block.id().clone(), index)) else {
+                    continue;
+                };
+                let (sx, sy, sw, sh) = rect(slot_bounds, origin);
+                write_connector(svg, (x, y), (sx, sy, sw, sh), color);
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="6" fill="none" stroke="#888888" stroke-dasharray="4 3"/>"##,
+                    sx, sy, sw, sh
+                );
+                let label = block.slot_hint(index).unwrap_or_else(|| "empty".to_string());
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{:.1}" y="{:.1}" font-size="11" font-style="italic" fill="#888888" text-anchor="middle">{}</text>"##,
+                    sx + sw / 2.0,
+                    sy + sh / 2.0 + 4.0,
+                    escape(&label)
+                );
+            }
+        }
+    }
+}
+
+/// Elbow line from under the parent's label to the left edge of a slot's content
+fn write_connector(svg: &mut String, parent: (f32, f32), child: (f32, f32, f32, f32), color: Hsla) {
+    let (px, py) = (parent.0 + 6.0, parent.1 + 24.0);
+    let (cx, cy) = (child.0, child.1 + 14.0);
+    if cx <= px || cy <= py {
+        // Side-by-side layouts put the child right of the label; no room for an elbow
+        return;
+    }
+    let _ = writeln!(
+        svg,
+        r#"<path d="M {:.1} {:.1} V {:.1} H {:.1}" fill="none" stroke="{}" stroke-width="1.5" stroke-opacity="0.6"/>"#,
+        px, py, cy, cx, hex(color)
+    );
+}
+
+fn rect(bounds: &Bounds<Pixels>, origin: (f32, f32)) -> (f32, f32, f32, f32) {
+    (
+        f32::from(bounds.origin.x) - origin.0,
+        f32::from(bounds.origin.y) - origin.1,
+        f32::from(bounds.size.width),
+        f32::from(bounds.size.height),
+    )
+}
+
+fn hex(color: Hsla) -> String {
+    let rgba = Rgba::from(color);
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(rgba.r), channel(rgba.g), channel(rgba.b))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,425 @@
+// Helpers for comparing and walking `TypeAstNode` trees
+
+use ui_types_common::{TypeAstNode, PRIMITIVES};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Wrapper giving a `TypeAstNode` structural `Eq` and `Hash`, so subtrees can be used
+/// as map keys for dedup detection and caching
+#[derive(Clone, Debug)]
+pub struct AstKey(pub TypeAstNode);
+
+impl PartialEq for AstKey {
+    fn eq(&self, other: &Self) -> bool {
+        structural_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for AstKey {}
+
+impl Hash for AstKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        structural_hash(&self.0, state);
+    }
+}
+
+/// Compare two trees by structure. Lifetime names are ignored since renaming
+/// `'a` to `'b` does not change the shape of the type.
+pub fn structural_eq(a: &TypeAstNode, b: &TypeAstNode) -> bool {
+    match (a, b) {
+        (TypeAstNode::Primitive { name: a }, TypeAstNode::Primitive { name: b }) => a == b,
+        (TypeAstNode::Path { path: a }, TypeAstNode::Path { path: b }) => a == b,
+        (TypeAstNode::AliasRef { alias: a }, TypeAstNode::AliasRef { alias: b }) => a == b,
+        (
+            TypeAstNode::Constructor { name: name_a, params: params_a, lifetimes: lifetimes_a, const_generics: consts_a },
+            TypeAstNode::Constructor { name: name_b, params: params_b, lifetimes: lifetimes_b, const_generics: consts_b },
+        ) => {
+            name_a == name_b
+                && lifetimes_a.len() == lifetimes_b.len()
+                && consts_a == consts_b
+                && all_structural_eq(params_a, params_b)
+        }
+        (TypeAstNode::Tuple { elements: a }, TypeAstNode::Tuple { elements: b }) => all_structural_eq(a, b),
+        (
+            TypeAstNode::FnPointer { params: params_a, return_type: ret_a },
+            TypeAstNode::FnPointer { params: params_b, return_type: ret_b },
+        ) => all_structural_eq(params_a, params_b) && structural_eq(ret_a, ret_b),
+        (TypeAstNode::None, TypeAstNode::None) => true,
+        _ => false,
+    }
+}
+
+fn all_structural_eq(a: &[TypeAstNode], b: &[TypeAstNode]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| structural_eq(a, b))
+}
+
+/// Hash a tree consistently with `structural_eq`
+pub fn structural_hash<H: Hasher>(node: &TypeAstNode, state: &mut H) {
+    match node {
+        TypeAstNode::Primitive { name } => {
+            0u8.hash(state);
+            name.hash(state);
+        }
+        TypeAstNode::Path { path } => {
+            1u8.hash(state);
+            path.hash(state);
+        }
+        TypeAstNode::AliasRef { alias } => {
+            2u8.hash(state);
+            alias.hash(state);
+        }
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => {
+            3u8.hash(state);
+            name.hash(state);
+            lifetimes.len().hash(state);
+            const_generics.hash(state);
+            params.len().hash(state);
+            for param in params {
+                structural_hash(param, state);
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            4u8.hash(state);
+            elements.len().hash(state);
+            for element in elements {
+                structural_hash(element, state);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            5u8.hash(state);
+            params.len().hash(state);
+            for param in params {
+                structural_hash(param, state);
+            }
+            structural_hash(return_type, state);
+        }
+        TypeAstNode::None => 6u8.hash(state),
+    }
+}
+
+/// Structural hash of a whole tree, for cache keys
+pub fn tree_hash(node: &TypeAstNode) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    structural_hash(node, &mut hasher);
+    hasher.finish()
+}
+
+/// Collect every primitive, constructor and path name used in the tree
+pub fn collect_type_names(node: &TypeAstNode, out: &mut Vec<String>) {
+    let mut push = |name: &String| {
+        if !out.contains(name) {
+            out.push(name.clone());
+        }
+    };
+    match node {
+        TypeAstNode::Primitive { name } => push(name),
+        TypeAstNode::Path { .. } if const_arg_expr(node).is_some() => {}
+        TypeAstNode::Path { path } => push(path),
+        TypeAstNode::Constructor { name, params, .. } => {
+            push(name);
+            for param in params {
+                collect_type_names(param, out);
+            }
+        }
+        TypeAstNode::Tuple { elements } => {
+            for element in elements {
+                collect_type_names(element, out);
+            }
+        }
+        TypeAstNode::FnPointer { params, return_type } => {
+            for param in params {
+                collect_type_names(param, out);
+            }
+            collect_type_names(return_type, out);
+        }
+        TypeAstNode::AliasRef { .. } | TypeAstNode::None => {}
+    }
+}
+
+/// The implementing type in a trait or impl, kept as a bare keyword rather than a path
+pub const SELF_TYPE: &str = "Self";
+
+/// A const generic value such as `16` or `N`. There is no node kind for these; they are
+/// stored as a path with the expression in braces, which is how Rust writes a const
+/// argument in any generic position (`GenericArray<u8, {16}>`).
+pub fn const_arg(expr: impl AsRef<str>) -> TypeAstNode {
+    TypeAstNode::Path { path: format!("{{{}}}", expr.as_ref().trim()) }
+}
+
+/// The expression of a node made by `const_arg`
+pub fn const_arg_expr(node: &TypeAstNode) -> Option<&str> {
+    match node {
+        TypeAstNode::Path { path } => path.strip_prefix('{')?.strip_suffix('}').map(str::trim),
+        _ => None,
+    }
+}
+
+/// Whether the tree mentions `Self` or one of its associated types (`Self::Item`)
+pub fn uses_self_type(node: &TypeAstNode) -> bool {
+    let mut names = Vec::new();
+    collect_type_names(node, &mut names);
+    names
+        .iter()
+        .any(|name| name == SELF_TYPE || name.strip_prefix(SELF_TYPE).is_some_and(|rest| rest.starts_with("::")))
+}
+
+/// Replace every reference to `alias` with its definition
+pub fn inline_alias(node: &TypeAstNode, alias: &str, definition: &TypeAstNode) -> TypeAstNode {
+    match node {
+        TypeAstNode::AliasRef { alias: name } if name == alias => definition.clone(),
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => TypeAstNode::Constructor {
+            name: name.clone(),
+            params: params.iter().map(|p| inline_alias(p, alias, definition)).collect(),
+            lifetimes: lifetimes.clone(),
+            const_generics: const_generics.clone(),
+        },
+        TypeAstNode::Tuple { elements } => TypeAstNode::Tuple {
+            elements: elements.iter().map(|e| inline_alias(e, alias, definition)).collect(),
+        },
+        TypeAstNode::FnPointer { params, return_type } => TypeAstNode::FnPointer {
+            params: params.iter().map(|p| inline_alias(p, alias, definition)).collect(),
+            return_type: Box::new(inline_alias(return_type, alias, definition)),
+        },
+        other => other.clone(),
+    }
+}
+
+/// Rename single-segment primitives and paths found in `table`, recording each distinct
+/// `(from, to)` pair in `renamed`. Targets that are Rust primitives become primitive nodes.
+pub fn rename_primitives(
+    node: &TypeAstNode,
+    table: &HashMap<String, String>,
+    renamed: &mut Vec<(String, String)>,
+) -> TypeAstNode {
+    match node {
+        TypeAstNode::Primitive { name } | TypeAstNode::Path { path: name } if table.contains_key(name) => {
+            let target = table[name].clone();
+            if !renamed.iter().any(|(from, _)| from == name) {
+                renamed.push((name.clone(), target.clone()));
+            }
+            if PRIMITIVES.contains(&target.as_str()) {
+                TypeAstNode::Primitive { name: target }
+            } else {
+                TypeAstNode::Path { path: target }
+            }
+        }
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => TypeAstNode::Constructor {
+            name: name.clone(),
+            params: params.iter().map(|p| rename_primitives(p, table, renamed)).collect(),
+            lifetimes: lifetimes.clone(),
+            const_generics: const_generics.clone(),
+        },
+        TypeAstNode::Tuple { elements } => TypeAstNode::Tuple {
+            elements: elements.iter().map(|e| rename_primitives(e, table, renamed)).collect(),
+        },
+        TypeAstNode::FnPointer { params, return_type } => TypeAstNode::FnPointer {
+            params: params.iter().map(|p| rename_primitives(p, table, renamed)).collect(),
+            return_type: Box::new(rename_primitives(return_type, table, renamed)),
+        },
+        other => other.clone(),
+    }
+}
+
+/// Splice directly-nested tuples into their parent: `((a, b), c)` becomes `(a, b, c)`.
+///
+/// With `all_levels` unset only one level is spliced per tuple, so `(((a, b), c), d)`
+/// becomes `((a, b), c, d)`. Single-element tuples like `(T,)` are never spliced since
+/// they are a distinct type, so `((T,),)` is left as is.
+pub fn flatten_tuples(node: &TypeAstNode, all_levels: bool) -> TypeAstNode {
+    match node {
+        TypeAstNode::Tuple { elements } => {
+            let mut flat = Vec::new();
+            for element in elements {
+                match element {
+                    TypeAstNode::Tuple { elements: inner } if inner.len() >= 2 => {
+                        if all_levels {
+                            match flatten_tuples(element, true) {
+                                TypeAstNode::Tuple { elements } => flat.extend(elements),
+                                other => flat.push(other),
+                            }
+                        } else {
+                            flat.extend(inner.iter().cloned());
+                        }
+                    }
+                    other => flat.push(flatten_tuples(other, all_levels)),
+                }
+            }
+            TypeAstNode::Tuple { elements: flat }
+        }
+        TypeAstNode::Constructor { name, params, lifetimes, const_generics } => TypeAstNode::Constructor {
+            name: name.clone(),
+            params: params.iter().map(|p| flatten_tuples(p, all_levels)).collect(),
+            lifetimes: lifetimes.clone(),
+            const_generics: const_generics.clone(),
+        },
+        TypeAstNode::FnPointer { params, return_type } => TypeAstNode::FnPointer {
+            params: params.iter().map(|p| flatten_tuples(p, all_levels)).collect(),
+            return_type: Box::new(flatten_tuples(return_type, all_levels)),
+        },
+        other => other.clone(),
+    }
+}
+
+/// Direct children of a node, in source order
+fn children(node: &TypeAstNode) -> Vec<&TypeAstNode> {
+    match node {
+        TypeAstNode::Constructor { params, .. } => params.iter().collect(),
+        TypeAstNode::Tuple { elements } => elements.iter().collect(),
+        TypeAstNode::FnPointer { params, return_type } => {
+            params.iter().chain(std::iter::once(return_type.as_ref())).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Slot-index path of every node, parents first; the root is the empty path
+pub fn node_paths(node: &TypeAstNode) -> Vec<Vec<usize>> {
+    fn visit(node: &TypeAstNode, path: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        out.push(path.clone());
+        for (index, child) in children(node).into_iter().enumerate() {
+            path.push(index);
+            visit(child, path, out);
+            path.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(node, &mut Vec::new(), &mut out);
+    out
+}
+
+/// Number of nodes in the tree, including the root
+pub fn node_count(node: &TypeAstNode) -> usize {
+    1 + children(node).into_iter().map(node_count).sum::<usize>()
+}
+
+/// Subtrees below the root that occur more than once and have at least `min_nodes`
+/// nodes, with their occurrence counts. Largest first, so a repeated subtree comes
+/// before the smaller repeats inside it.
+pub fn repeated_subtrees(root: &TypeAstNode, min_nodes: usize) -> Vec<(TypeAstNode, usize)> {
+    fn count(node: &TypeAstNode, min_nodes: usize, counts: &mut HashMap<AstKey, usize>) {
+        for child in children(node) {
+            if node_count(child) >= min_nodes {
+                *counts.entry(AstKey(child.clone())).or_default() += 1;
+            }
+            count(child, min_nodes, counts);
+        }
+    }
+
+    let mut counts = HashMap::new();
+    count(root, min_nodes, &mut counts);
+    let mut repeated: Vec<(TypeAstNode, usize)> = counts
+        .into_iter()
+        .filter(|(_, n)| *n > 1)
+        .map(|(key, n)| (key.0, n))
+        .collect();
+    repeated.sort_by_key(|(subtree, n)| (std::cmp::Reverse(node_count(subtree)), std::cmp::Reverse(*n)));
+    repeated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn primitive(name: &str) -> TypeAstNode {
+        TypeAstNode::Primitive { name: name.to_string() }
+    }
+
+    fn constructor(name: &str, params: Vec<TypeAstNode>, lifetimes: &[&str]) -> TypeAstNode {
+        TypeAstNode::Constructor {
+            name: name.to_string(),
+            params,
+            lifetimes: lifetimes.iter().map(|l| l.to_string()).collect(),
+            const_generics: Vec::new(),
+        }
+    }
+
+    /// One tree per variant, then nested trees mixing them
+    fn samples() -> Vec<TypeAstNode> {
+        vec![
+            primitive("u8"),
+            TypeAstNode::Path { path: "std::time::Duration".to_string() },
+            TypeAstNode::AliasRef { alias: "EntityId".to_string() },
+            const_arg("4"),
+            constructor("Vec", vec![primitive("u8")], &[]),
+            TypeAstNode::Constructor {
+                name: "SmallVec".to_string(),
+                params: vec![primitive("u8")],
+                lifetimes: Vec::new(),
+                const_generics: vec!["4".to_string()],
+            },
+            TypeAstNode::Tuple { elements: vec![primitive("u8"), primitive("bool")] },
+            TypeAstNode::FnPointer { params: vec![primitive("u8")], return_type: Box::new(primitive("bool")) },
+            TypeAstNode::None,
+            constructor(
+                "HashMap",
+                vec![
+                    TypeAstNode::AliasRef { alias: "EntityId".to_string() },
+                    constructor("Vec", vec![TypeAstNode::Tuple { elements: vec![primitive("u8"), TypeAstNode::None] }], &[]),
+                ],
+                &[],
+            ),
+            constructor(
+                "Option",
+                vec![TypeAstNode::FnPointer {
+                    params: vec![constructor("Ref", vec![primitive("str")], &["a"])],
+                    return_type: Box::new(constructor("Box", vec![TypeAstNode::Path { path: "dyn Any".to_string() }], &[])),
+                }],
+                &[],
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_variant_equals_its_serde_round_trip() {
+        for sample in samples() {
+            let json = serde_json::to_string(&sample).unwrap();
+            let restored: TypeAstNode = serde_json::from_str(&json).unwrap();
+            assert!(structural_eq(&sample, &restored), "{:?} changed through {}", sample, json);
+            assert_eq!(tree_hash(&sample), tree_hash(&restored));
+            assert!(AstKey(sample.clone()) == AstKey(restored));
+        }
+    }
+
+    #[test]
+    fn distinct_trees_are_unequal() {
+        let samples = samples();
+        for (i, a) in samples.iter().enumerate() {
+            for (j, b) in samples.iter().enumerate() {
+                assert_eq!(structural_eq(a, b), i == j, "{:?} vs {:?}", a, b);
+            }
+        }
+        let keys: HashSet<AstKey> = samples.iter().cloned().map(AstKey).collect();
+        assert_eq!(keys.len(), samples.len());
+    }
+
+    #[test]
+    fn nested_differences_are_detected() {
+        let tree = |leaf: &str| constructor("Option", vec![TypeAstNode::Tuple { elements: vec![primitive("u8"), primitive(leaf)] }], &[]);
+        assert!(structural_eq(&tree("u16"), &tree("u16")));
+        assert!(!structural_eq(&tree("u16"), &tree("u32")));
+        assert_ne!(tree_hash(&tree("u16")), tree_hash(&tree("u32")));
+    }
+
+    #[test]
+    fn const_args_are_braced_paths() {
+        let node = const_arg(" N ");
+        assert!(matches!(&node, TypeAstNode::Path { path } if path == "{N}"));
+        assert_eq!(const_arg_expr(&node), Some("N"));
+        assert_eq!(const_arg_expr(&TypeAstNode::Path { path: "std::time::Duration".to_string() }), None);
+
+        let mut names = Vec::new();
+        collect_type_names(&constructor("GenericArray", vec![primitive("u8"), node], &[]), &mut names);
+        assert_eq!(names, ["GenericArray", "u8"]);
+    }
+
+    #[test]
+    fn lifetime_names_are_ignored_but_not_their_count() {
+        let named = |lifetimes: &[&str]| constructor("Ref", vec![primitive("str")], lifetimes);
+        assert!(structural_eq(&named(&["a"]), &named(&["b"])));
+        assert_eq!(tree_hash(&named(&["a"])), tree_hash(&named(&["b"])));
+        assert!(!structural_eq(&named(&["a"]), &named(&[])));
+    }
+}
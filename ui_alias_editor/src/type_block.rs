@@ -1,7 +1,19 @@
 use gpui::{prelude::*, *};
-use ui::{h_flex, v_flex, ActiveTheme, StyledExt, Colorize};
+use serde::{Deserialize, Serialize};
+use ui::{h_flex, v_flex, ActiveTheme, StyledExt, Colorize, tooltip::Tooltip};
 use ui_types_common::TypeAstNode;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use crate::const_eval;
+use crate::type_ast;
+
+/// Click callbacks get the window and app so they can update the owning entity directly,
+/// instead of stashing the click for the next render to pick up
+pub type SlotHandler = Arc<dyn Fn(BlockId, usize, &mut Window, &mut App) + 'static>;
+pub type BlockHandler = Arc<dyn Fn(BlockId, &mut Window, &mut App) + 'static>;
+pub type AliasHandler = Arc<dyn Fn(String, &mut Window, &mut App) + 'static>;
+/// Called with a constructor block and the constructor to swap it for
+pub type ConstructorHandler = Arc<dyn Fn(BlockId, String, &mut Window, &mut App) + 'static>;
 
 /// Unique identifier for a block instance
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -16,6 +28,252 @@ impl BlockId {
     }
 }
 
+/// Constructors where identical params are expected (`HashMap<String, String>`)
+const IDENTICAL_PARAMS_ALLOWED: &[&str] = &["HashMap", "BTreeMap", "IndexMap"];
+
+/// Placeholder hints for the empty slots of constructors with conventional parameters
+const SLOT_HINTS: &[(&str, &[&str])] = &[
+    ("Result", &["Ok type", "Err type"]),
+    ("HashMap", &["key", "value", "hasher"]),
+    ("BTreeMap", &["key", "value"]),
+    ("IndexMap", &["key", "value", "hasher"]),
+    ("HashSet", &["element", "hasher"]),
+    ("BTreeSet", &["element"]),
+    ("Vec", &["element"]),
+    ("VecDeque", &["element"]),
+    ("Option", &["Some type"]),
+    ("Cow", &["borrowed type"]),
+    ("PhantomData", &["marker type"]),
+    ("dyn Future", &["output"]),
+    ("dyn Iterator", &["item"]),
+];
+
+/// Param positions that take a const value rather than a type, for constructors that
+/// mix the two (`GenericArray<u8, N>`)
+const CONST_PARAMS: &[(&str, &[usize])] = &[
+    ("GenericArray", &[1]),
+    ("ArrayVec", &[1]),
+    ("ArrayString", &[0]),
+    ("heapless::Vec", &[1]),
+    ("heapless::String", &[0]),
+    ("heapless::Deque", &[1]),
+];
+
+/// Positions of a constructor's const value params
+pub fn const_param_positions(name: &str) -> &'static [usize] {
+    CONST_PARAMS
+        .iter()
+        .find(|(constructor, _)| *constructor == name)
+        .map(|(_, positions)| *positions)
+        .unwrap_or(&[])
+}
+
+/// Lifetime params that come before the type params of borrowing std constructors.
+/// Project constructors declare theirs in `alias_editor.json`.
+const LIFETIME_PARAMS: &[(&str, usize)] = &[
+    ("Cow", 1),
+    ("Ref", 1),
+    ("RefMut", 1),
+    ("MutexGuard", 1),
+    ("RwLockReadGuard", 1),
+    ("RwLockWriteGuard", 1),
+];
+
+/// Number of lifetime params a built-in constructor takes
+pub fn lifetime_param_count(name: &str) -> usize {
+    LIFETIME_PARAMS
+        .iter()
+        .find(|(constructor, _)| *constructor == name)
+        .map_or(0, |(_, count)| *count)
+}
+
+/// Size in bytes of each primitive, shown as an FFI/layout aid. Pointer-sized types show `ptr`.
+const PRIMITIVE_SIZES: &[(&str, &str)] = &[
+    ("()", "0"),
+    ("bool", "1"),
+    ("char", "4"),
+    ("i8", "1"),
+    ("u8", "1"),
+    ("i16", "2"),
+    ("u16", "2"),
+    ("i32", "4"),
+    ("u32", "4"),
+    ("f32", "4"),
+    ("i64", "8"),
+    ("u64", "8"),
+    ("f64", "8"),
+    ("i128", "16"),
+    ("u128", "16"),
+    ("isize", "ptr"),
+    ("usize", "ptr"),
+];
+
+/// Size annotation for a primitive, e.g. `4 B` or `ptr`
+pub fn primitive_size(name: &str) -> Option<String> {
+    let (_, size) = PRIMITIVE_SIZES.iter().find(|(n, _)| *n == name)?;
+    Some(if *size == "ptr" { size.to_string() } else { format!("{} B", size) })
+}
+
+/// Numeric kind of a primitive: `"signed"`, `"unsigned"` or `"float"`
+pub fn primitive_kind(name: &str) -> Option<&'static str> {
+    if !name.is_ascii() || name.len() < 2 {
+        return None;
+    }
+    let (prefix, bits) = name.split_at(1);
+    if bits != "size" && bits.parse::<u32>().is_err() {
+        return None;
+    }
+    match prefix {
+        "i" => Some("signed"),
+        "u" => Some("unsigned"),
+        "f" => Some("float"),
+        _ => None,
+    }
+}
+
+/// Width in bits of a numeric primitive, counting `usize`/`isize` as 64
+fn primitive_bits(name: &str) -> u32 {
+    match &name[1..] {
+        "size" => 64,
+        bits => bits.parse().unwrap_or(0),
+    }
+}
+
+/// A numeric literal typed into the picker
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NumericLiteral {
+    Integer { negative: bool, magnitude: u128 },
+    Float(f64),
+}
+
+impl NumericLiteral {
+    /// Read `255`, `-3`, `0xff`, `1_000`, `2.5` or `1e9`. Names like `inf` are not literals.
+    fn parse(text: &str) -> Option<Self> {
+        let text: String = text.trim().chars().filter(|c| *c != '_').collect();
+        let (negative, body) = match text.strip_prefix('-') {
+            Some(body) => (true, body),
+            None => (false, text.as_str()),
+        };
+        let radix = [("0x", 16), ("0o", 8), ("0b", 2)]
+            .into_iter()
+            .find_map(|(prefix, radix)| body.strip_prefix(prefix).map(|digits| (digits, radix)));
+        if let Some((digits, radix)) = radix {
+            return u128::from_str_radix(digits, radix)
+                .ok()
+                .map(|magnitude| NumericLiteral::Integer { negative, magnitude });
+        }
+        if !body.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        if body.chars().all(|c| c.is_ascii_digit()) {
+            return body.parse().ok().map(|magnitude| NumericLiteral::Integer { negative, magnitude });
+        }
+        let value: f64 = body.parse().ok().filter(|value: &f64| value.is_finite())?;
+        Some(NumericLiteral::Float(if negative { -value } else { value }))
+    }
+
+    /// Whether `primitive` holds the value exactly
+    fn fits(&self, primitive: &str) -> bool {
+        let bits = primitive_bits(primitive);
+        match (*self, primitive_kind(primitive)) {
+            (NumericLiteral::Integer { negative, magnitude }, Some("unsigned")) => {
+                !negative && (bits >= 128 || magnitude < 1u128 << bits)
+            }
+            (NumericLiteral::Integer { negative, magnitude }, Some("signed")) => {
+                let limit = 1u128 << (bits - 1);
+                if negative { magnitude <= limit } else { magnitude < limit }
+            }
+            // Floats hold every integer up to the size of their mantissa
+            (NumericLiteral::Integer { magnitude, .. }, Some("float")) => {
+                magnitude <= 1u128 << if bits == 32 { 24 } else { 53 }
+            }
+            // `f32` only when it rounds the value no further than `f64` does
+            (NumericLiteral::Float(value), Some("float")) => bits == 64 || (value as f32) as f64 == value,
+            _ => false,
+        }
+    }
+}
+
+/// Primitives that hold the numeric literal `text` exactly, best fit first: integers from
+/// the narrowest type up, unsigned before signed at each width, then the floats. A literal
+/// with a type suffix (`255u16`) gives only that type. Empty for text that is not a number.
+pub fn primitives_for_literal(text: &str) -> Vec<&'static str> {
+    let text = text.trim();
+    let numeric = || {
+        ui_types_common::PRIMITIVES
+            .iter()
+            .copied()
+            .filter(|primitive| primitive_kind(primitive).is_some())
+    };
+
+    // Hex digits include `f`, so only decimal literals can end in a float suffix
+    let hex = text.trim_start_matches('-').starts_with("0x");
+    let suffix = numeric()
+        .filter(|primitive| !(hex && primitive_kind(primitive) == Some("float")))
+        .filter(|primitive| text.len() > primitive.len() && text.ends_with(primitive))
+        .max_by_key(|primitive| primitive.len());
+    if let Some(suffix) = suffix {
+        let value = text[..text.len() - suffix.len()].trim_end_matches('_');
+        return match NumericLiteral::parse(value) {
+            Some(literal) if literal.fits(suffix) => vec![suffix],
+            _ => Vec::new(),
+        };
+    }
+
+    let Some(literal) = NumericLiteral::parse(text) else {
+        return Vec::new();
+    };
+    let mut fitting: Vec<&'static str> = numeric().filter(|primitive| literal.fits(primitive)).collect();
+    fitting.sort_by_key(|primitive| {
+        let kind = match primitive_kind(primitive) {
+            Some("unsigned") => 0,
+            Some("signed") => 1,
+            _ => 2,
+        };
+        // Pointer-sized types come after the fixed 64-bit ones
+        (kind == 2, primitive_bits(primitive), primitive.ends_with("size"), kind)
+    });
+    fitting
+}
+
+/// Primitives that can stand in for each other, in the order they are cycled through.
+/// Built from `PRIMITIVES` by kind: signed ints, unsigned ints, floats.
+fn primitive_group(name: &str) -> Vec<&'static str> {
+    match primitive_kind(name) {
+        Some(kind) => ui_types_common::PRIMITIVES
+            .iter()
+            .copied()
+            .filter(|primitive| primitive_kind(primitive) == Some(kind))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Single-param wrappers that can be swapped for each other in place, keeping the inner type
+const CONSTRUCTOR_FAMILIES: &[&[&str]] = &[
+    &["Box", "Rc", "Arc"],
+    &["Cell", "RefCell"],
+];
+
+/// The constructors `name` can be swapped for (`Rc` and `Arc` for `Box`), in family order
+pub fn constructor_alternatives(name: &str) -> Vec<&'static str> {
+    CONSTRUCTOR_FAMILIES
+        .iter()
+        .find(|family| family.contains(&name))
+        .map(|family| family.iter().copied().filter(|other| *other != name).collect())
+        .unwrap_or_default()
+}
+
+/// Keyboard focus ring color, distinct from the selection ring
+const FOCUS_RING_COLOR: Hsla = Hsla { h: 0.13, s: 1.0, l: 0.6, a: 1.0 };
+
+/// A stop in keyboard focus order: a block, or an empty slot of a block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FocusTarget {
+    Block(BlockId),
+    Slot(BlockId, usize),
+}
+
 /// Slot label for constructor parameters
 #[derive(Clone, Debug)]
 pub struct SlotLabel {
@@ -23,6 +281,17 @@ pub struct SlotLabel {
     pub index: usize,
 }
 
+/// How a constructor's slots are arranged on the canvas
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LayoutDirection {
+    /// Slots stacked under the constructor header, suits deep trees
+    #[default]
+    TopDown,
+    /// Slots side by side, suits wide trees
+    LeftToRight,
+}
+
 /// Represents a visual block for a type node (like Scratch blocks)
 #[derive(Clone, Debug)]
 pub enum TypeBlock {
@@ -52,6 +321,20 @@ pub enum TypeBlock {
         slots: Vec<Option<Box<TypeBlock>>>,
         slot_labels: Vec<String>,  // Labels like "T", "E", "K", "V"
         expected_params: usize,
+        /// Slots past this index have a default and may be left empty
+        required_params: usize,
+        /// Leading lifetime params, without the `'` (`Cow<'a, T>` has `["a"]`)
+        lifetimes: Vec<String>,
+        /// Trailing const generic args, kept as written (`4 * 1024`)
+        const_generics: Vec<String>,
+        /// Slots that hold a const value block instead of a type
+        const_params: Vec<usize>,
+    },
+    /// Const value in a constructor's param list, kept as written (`N`, `{ 4 * 1024 }`)
+    ConstArg {
+        id: BlockId,
+        expr: String,
+        color: BlockColor,
     },
     /// Tuple block with multiple element slots
     Tuple {
@@ -59,6 +342,14 @@ pub enum TypeBlock {
         color: BlockColor,
         elements: Vec<Option<Box<TypeBlock>>>,
     },
+    /// Function pointer block - parameter slots followed by the return type slot.
+    /// An empty return slot means `()`.
+    FnPointer {
+        id: BlockId,
+        color: BlockColor,
+        params: Vec<Option<Box<TypeBlock>>>,
+        return_type: Option<Box<TypeBlock>>,
+    },
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -68,6 +359,8 @@ pub enum BlockColor {
     Alias,       // Purple
     Constructor, // Orange
     Tuple,       // Yellow
+    FnPointer,   // Teal
+    Const,       // Pink
 }
 
 impl BlockColor {
@@ -78,6 +371,80 @@ impl BlockColor {
             BlockColor::Alias => hsla(0.75, 0.7, 0.5, 1.0),      // Purple
             BlockColor::Constructor => hsla(0.08, 0.8, 0.6, 1.0), // Orange
             BlockColor::Tuple => hsla(0.15, 0.8, 0.6, 1.0),      // Yellow
+            BlockColor::FnPointer => hsla(0.48, 0.7, 0.45, 1.0), // Teal
+            BlockColor::Const => hsla(0.92, 0.6, 0.5, 1.0),      // Pink
+        }
+    }
+
+    /// Category fill for a dark or light theme. Light themes get a deeper shade so the
+    /// white labels stay legible on a pale canvas.
+    pub fn for_theme(&self, dark: bool) -> Hsla {
+        let color = self.to_hsla();
+        if dark {
+            color
+        } else {
+            color.darken(0.15)
+        }
+    }
+
+    /// Darker, fully saturated fills that keep white text above 7:1 contrast
+    /// whatever the theme
+    pub fn to_high_contrast_hsla(&self) -> Hsla {
+        match self {
+            BlockColor::Primitive => hsla(0.6, 1.0, 0.25, 1.0),
+            BlockColor::Path => hsla(0.35, 1.0, 0.2, 1.0),
+            BlockColor::Alias => hsla(0.75, 1.0, 0.3, 1.0),
+            BlockColor::Constructor => hsla(0.05, 1.0, 0.27, 1.0),
+            BlockColor::Tuple => hsla(0.13, 1.0, 0.22, 1.0),
+            BlockColor::FnPointer => hsla(0.48, 1.0, 0.18, 1.0),
+            BlockColor::Const => hsla(0.92, 1.0, 0.25, 1.0),
+        }
+    }
+}
+
+/// Theme colors the block views draw with. The canvas reads them from the app theme on
+/// every render, so a theme switch shows up straight away. High-contrast mode keeps its
+/// own fixed palette.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockTheme {
+    /// Whether the theme is dark, which picks the category fill shades
+    pub dark: bool,
+    /// Warning badges and the outline of required empty slots
+    pub warning: Hsla,
+    /// Selection outline and the hovered empty slot
+    pub selection: Hsla,
+    /// Background of empty slots
+    pub slot_well: Hsla,
+    /// Outline of optional empty slots
+    pub slot_border: Hsla,
+    /// Placeholder text in empty slots
+    pub hint: Hsla,
+}
+
+impl BlockTheme {
+    pub fn from_app(cx: &App) -> Self {
+        let theme = cx.theme();
+        Self {
+            dark: theme.mode.is_dark(),
+            warning: theme.warning,
+            selection: theme.ring,
+            slot_well: theme.muted,
+            slot_border: theme.muted_foreground,
+            hint: theme.muted_foreground,
+        }
+    }
+}
+
+impl Default for BlockTheme {
+    /// The dark palette, for views rendered outside a themed canvas
+    fn default() -> Self {
+        Self {
+            dark: true,
+            warning: hsla(0.12, 0.9, 0.45, 1.0),
+            selection: hsla(0.55, 0.9, 0.65, 1.0),
+            slot_well: hsla(0.0, 0.0, 0.2, 1.0),
+            slot_border: hsla(0.0, 0.0, 0.4, 1.0),
+            hint: hsla(0.0, 0.0, 0.65, 1.0),
         }
     }
 }
@@ -110,6 +477,15 @@ impl TypeBlock {
         }
     }
 
+    /// Create a const value block
+    pub fn const_arg(expr: impl Into<String>) -> Self {
+        TypeBlock::ConstArg {
+            id: BlockId::new(),
+            expr: expr.into(),
+            color: BlockColor::Const,
+        }
+    }
+
     /// Create a constructor block (Box, Arc, Vec, etc.) with labeled slots
     pub fn constructor(name: impl Into<String>, param_count: usize) -> Self {
         let name = name.into();
@@ -117,14 +493,80 @@ impl TypeBlock {
         
         // Generate default slot labels (T, E, K, V, etc.)
         let slot_labels = Self::generate_slot_labels(&name, param_count);
+        let required_params = Self::required_param_count(&name)
+            .unwrap_or(param_count)
+            .min(param_count);
+        let lifetimes = Self::default_lifetimes(lifetime_param_count(&name));
 
-        TypeBlock::Constructor {
+        let mut block = TypeBlock::Constructor {
             id: BlockId::new(),
-            name,
+            name: name.clone(),
             color: BlockColor::Constructor,
             slots,
             slot_labels,
             expected_params: param_count,
+            required_params,
+            lifetimes,
+            const_generics: Vec::new(),
+            const_params: Vec::new(),
+        };
+        block.set_const_params(const_param_positions(&name));
+        block
+    }
+
+    /// Mark which of a constructor's slots take a const value. Their labels become
+    /// `N`, `N1`, ... and any type already in them is dropped.
+    pub fn set_const_params(&mut self, positions: &[usize]) {
+        if let TypeBlock::Constructor { slots, slot_labels, const_params, .. } = self {
+            *const_params = positions.iter().copied().filter(|&i| i < slots.len()).collect();
+            for (n, &index) in const_params.iter().enumerate() {
+                if !matches!(slots[index].as_deref(), None | Some(TypeBlock::ConstArg { .. })) {
+                    slots[index] = None;
+                }
+                if let Some(label) = slot_labels.get_mut(index) {
+                    *label = if n == 0 { "N".to_string() } else { format!("N{}", n) };
+                }
+            }
+        }
+    }
+
+    /// Check if a slot takes a const value instead of a type
+    pub fn is_const_slot(&self, index: usize) -> bool {
+        matches!(self, TypeBlock::Constructor { const_params, .. } if const_params.contains(&index))
+    }
+
+    /// Lifetime names for `count` params: `a`, `b`, `c`, ...
+    fn default_lifetimes(count: usize) -> Vec<String> {
+        (b'a'..=b'z').take(count).map(|c| (c as char).to_string()).collect()
+    }
+
+    /// Give a constructor `count` lifetime params, keeping the names of those it already has
+    pub fn set_lifetime_count(&mut self, count: usize) {
+        if let TypeBlock::Constructor { lifetimes, .. } = self {
+            let defaults = Self::default_lifetimes(count);
+            lifetimes.truncate(count);
+            lifetimes.extend(defaults.into_iter().skip(lifetimes.len()));
+        }
+    }
+
+    /// Rename a constructor's lifetime param
+    pub fn set_lifetime(&mut self, index: usize, lifetime: impl Into<String>) -> bool {
+        match self {
+            TypeBlock::Constructor { lifetimes, .. } if index < lifetimes.len() => {
+                lifetimes[index] = lifetime.into().trim_start_matches('\'').to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of leading params that must be filled for constructors with defaulted
+    /// trailing params (hashers, allocators)
+    pub(crate) fn required_param_count(name: &str) -> Option<usize> {
+        match name {
+            "HashMap" | "BTreeMap" => Some(2),
+            "HashSet" | "BTreeSet" | "Vec" | "VecDeque" | "Box" | "Rc" | "Arc" => Some(1),
+            _ => None,
         }
     }
 
@@ -137,11 +579,26 @@ impl TypeBlock {
         }
     }
 
+    /// Create a function pointer block with empty parameter and return slots
+    pub fn fn_pointer(param_count: usize) -> Self {
+        TypeBlock::FnPointer {
+            id: BlockId::new(),
+            color: BlockColor::FnPointer,
+            params: vec![None; param_count],
+            return_type: None,
+        }
+    }
+
     /// Generate meaningful slot labels based on constructor name
     fn generate_slot_labels(name: &str, param_count: usize) -> Vec<String> {
         match name {
             "Result" if param_count == 2 => vec!["T".to_string(), "E".to_string()],
             "HashMap" | "BTreeMap" if param_count == 2 => vec!["K".to_string(), "V".to_string()],
+            "dyn Future" if param_count == 1 => vec!["Output".to_string()],
+            "dyn Iterator" if param_count == 1 => vec!["Item".to_string()],
+            "dyn Fn" | "dyn FnMut" | "dyn FnOnce" => (0..param_count)
+                .map(|i| if i + 1 == param_count { "Output".to_string() } else { format!("A{}", i + 1) })
+                .collect(),
             _ => (0..param_count).map(|i| {
                 if i == 0 { "T".to_string() }
                 else { format!("T{}", i) }
@@ -156,12 +613,18 @@ impl TypeBlock {
             | TypeBlock::Path { id, .. }
             | TypeBlock::AliasRef { id, .. }
             | TypeBlock::Constructor { id, .. }
-            | TypeBlock::Tuple { id, .. } => id,
+            | TypeBlock::Tuple { id, .. }
+            | TypeBlock::FnPointer { id, .. }
+            | TypeBlock::ConstArg { id, .. } => id,
         }
     }
 
-    /// Set a slot's content
+    /// Set a slot's content. Const slots only take const value blocks, and const
+    /// value blocks only go in const slots.
     pub fn set_slot(&mut self, slot_index: usize, block: TypeBlock) -> bool {
+        if self.is_const_slot(slot_index) != matches!(block, TypeBlock::ConstArg { .. }) {
+            return false;
+        }
         match self {
             TypeBlock::Constructor { slots, .. } => {
                 if slot_index < slots.len() {
@@ -179,10 +642,42 @@ impl TypeBlock {
                     false
                 }
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                if slot_index < params.len() {
+                    params[slot_index] = Some(Box::new(block));
+                    true
+                } else if slot_index == params.len() {
+                    *return_type = Some(Box::new(block));
+                    true
+                } else {
+                    false
+                }
+            }
             _ => false,
         }
     }
 
+    /// Whether params can be inserted anywhere in the block: tuple elements and fn pointer
+    /// params. Constructors have a fixed arity.
+    pub fn is_variadic(&self) -> bool {
+        matches!(self, TypeBlock::Tuple { .. } | TypeBlock::FnPointer { .. })
+    }
+
+    /// Insert an empty slot before `slot_index`, shifting the slots after it. An index one
+    /// past the last element (or fn param) appends. Returns false for fixed-arity blocks.
+    pub fn insert_slot(&mut self, slot_index: usize) -> bool {
+        let slots = match self {
+            TypeBlock::Tuple { elements, .. } => elements,
+            TypeBlock::FnPointer { params, .. } => params,
+            _ => return false,
+        };
+        if slot_index > slots.len() {
+            return false;
+        }
+        slots.insert(slot_index, None);
+        true
+    }
+
     /// Get a slot's content
     pub fn get_slot(&self, slot_index: usize) -> Option<&TypeBlock> {
         match self {
@@ -192,6 +687,33 @@ impl TypeBlock {
             TypeBlock::Tuple { elements, .. } => {
                 elements.get(slot_index).and_then(|e| e.as_ref()).map(|b| b.as_ref())
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                if slot_index == params.len() {
+                    return_type.as_deref()
+                } else {
+                    params.get(slot_index).and_then(|p| p.as_ref()).map(|b| b.as_ref())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to a slot's content
+    pub fn get_slot_mut(&mut self, slot_index: usize) -> Option<&mut TypeBlock> {
+        match self {
+            TypeBlock::Constructor { slots, .. } => {
+                slots.get_mut(slot_index).and_then(|s| s.as_mut()).map(|b| b.as_mut())
+            }
+            TypeBlock::Tuple { elements, .. } => {
+                elements.get_mut(slot_index).and_then(|e| e.as_mut()).map(|b| b.as_mut())
+            }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                if slot_index == params.len() {
+                    return_type.as_deref_mut()
+                } else {
+                    params.get_mut(slot_index).and_then(|p| p.as_mut()).map(|b| b.as_mut())
+                }
+            }
             _ => None,
         }
     }
@@ -205,6 +727,13 @@ impl TypeBlock {
             TypeBlock::Tuple { elements, .. } => {
                 elements.get_mut(slot_index).and_then(|e| e.take()).map(|b| *b)
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                if slot_index == params.len() {
+                    return_type.take().map(|b| *b)
+                } else {
+                    params.get_mut(slot_index).and_then(|p| p.take()).map(|b| *b)
+                }
+            }
             _ => None,
         }
     }
@@ -229,22 +758,24 @@ impl TypeBlock {
             TypeBlock::AliasRef { alias, .. } => Some(TypeAstNode::AliasRef {
                 alias: alias.clone(),
             }),
-            TypeBlock::Constructor { name, slots, .. } => {
-                let params: Vec<_> = slots
-                    .iter()
-                    .filter_map(|slot| slot.as_ref().and_then(|b| b.to_ast()))
-                    .collect();
-
-                // Check if all slots are filled
-                if params.len() != slots.len() {
+            TypeBlock::ConstArg { expr, .. } => Some(type_ast::const_arg(expr)),
+            TypeBlock::Constructor { name, slots, required_params, lifetimes, const_generics, .. } => {
+                // Optional slots can only be left empty at the end of the param list
+                let filled = slots.iter().rposition(|s| s.is_some()).map_or(0, |i| i + 1);
+                if filled < *required_params {
                     return None;
                 }
 
+                let params = slots[..filled]
+                    .iter()
+                    .map(|slot| slot.as_ref().and_then(|b| b.to_ast()))
+                    .collect::<Option<Vec<_>>>()?;
+
                 Some(TypeAstNode::Constructor {
                     name: name.clone(),
                     params,
-                    lifetimes: vec![],
-                    const_generics: vec![],
+                    lifetimes: lifetimes.clone(),
+                    const_generics: const_generics.clone(),
                 })
             }
             TypeBlock::Tuple { elements, .. } => {
@@ -261,6 +792,48 @@ impl TypeBlock {
                     elements: element_nodes,
                 })
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                let param_nodes: Vec<_> = params
+                    .iter()
+                    .filter_map(|p| p.as_ref().and_then(|b| b.to_ast()))
+                    .collect();
+
+                if param_nodes.len() != params.len() {
+                    return None;
+                }
+
+                let return_node = match return_type {
+                    Some(block) => block.to_ast()?,
+                    None => TypeAstNode::Primitive { name: "()".to_string() },
+                };
+
+                Some(TypeAstNode::FnPointer {
+                    params: param_nodes,
+                    return_type: Box::new(return_node),
+                })
+            }
+        }
+    }
+
+    /// Like `to_ast`, but empty slots become `TypeAstNode::None` instead of failing the
+    /// whole tree, so `from_ast` gives back the same shape
+    pub fn to_ast_with_holes(&self) -> TypeAstNode {
+        let hole = |slot: &Option<Box<TypeBlock>>| slot.as_ref().map_or(TypeAstNode::None, |b| b.to_ast_with_holes());
+        match self {
+            TypeBlock::Constructor { name, slots, lifetimes, const_generics, .. } => TypeAstNode::Constructor {
+                name: name.clone(),
+                params: slots.iter().map(hole).collect(),
+                lifetimes: lifetimes.clone(),
+                const_generics: const_generics.clone(),
+            },
+            TypeBlock::Tuple { elements, .. } => TypeAstNode::Tuple {
+                elements: elements.iter().map(hole).collect(),
+            },
+            TypeBlock::FnPointer { params, return_type, .. } => TypeAstNode::FnPointer {
+                params: params.iter().map(hole).collect(),
+                return_type: Box::new(hole(return_type)),
+            },
+            _ => self.to_ast().unwrap_or(TypeAstNode::None),
         }
     }
 
@@ -268,16 +841,45 @@ impl TypeBlock {
     pub fn from_ast(node: &TypeAstNode) -> Self {
         match node {
             TypeAstNode::Primitive { name } => TypeBlock::primitive(name.clone()),
+            TypeAstNode::Path { .. } if type_ast::const_arg_expr(node).is_some() => {
+                TypeBlock::const_arg(type_ast::const_arg_expr(node).unwrap_or_default())
+            }
             TypeAstNode::Path { path } => TypeBlock::path(path.clone()),
             TypeAstNode::AliasRef { alias } => TypeBlock::alias(alias.clone()),
-            TypeAstNode::Constructor { name, params, .. } => {
+            TypeAstNode::Constructor { name, params, lifetimes, const_generics } => {
                 let mut block = TypeBlock::constructor(name.clone(), params.len());
-                if let TypeBlock::Constructor { slots, .. } = &mut block {
+                let known_consts = const_param_positions(name);
+                // Const values keep their position even on constructors we have no metadata for
+                let mut positions = known_consts.to_vec();
+                positions.extend(params.iter().enumerate().filter_map(|(i, param)| {
+                    type_ast::const_arg_expr(param).map(|_| i)
+                }));
+                positions.sort_unstable();
+                positions.dedup();
+                block.set_const_params(&positions);
+                if let TypeBlock::Constructor {
+                    slots,
+                    lifetimes: block_lifetimes,
+                    const_generics: block_consts,
+                    ..
+                } = &mut block {
+                    *block_lifetimes = lifetimes
+                        .iter()
+                        .map(|l| l.trim_start_matches('\'').to_string())
+                        .collect();
+                    *block_consts = const_generics.clone();
                     for (i, param) in params.iter().enumerate() {
                         match param {
                             TypeAstNode::None => {
                                 // Leave slot empty for None
                             }
+                            // Files written before const slots existed store the value as a path
+                            TypeAstNode::Path { path: expr } | TypeAstNode::Primitive { name: expr }
+                                if known_consts.contains(&i) && i < slots.len() =>
+                            {
+                                let expr = type_ast::const_arg_expr(param).unwrap_or(expr);
+                                slots[i] = Some(Box::new(TypeBlock::const_arg(expr)));
+                            }
                             _ => {
                                 if i < slots.len() {
                                     slots[i] = Some(Box::new(TypeBlock::from_ast(param)));
@@ -306,7 +908,18 @@ impl TypeBlock {
                 }
                 block
             }
-            TypeAstNode::FnPointer { .. } => TypeBlock::primitive("FnPtr"),
+            TypeAstNode::FnPointer { params, return_type } => {
+                let mut block = TypeBlock::fn_pointer(params.len());
+                for (i, param) in params.iter().enumerate() {
+                    if !matches!(param, TypeAstNode::None) {
+                        block.set_slot(i, TypeBlock::from_ast(param));
+                    }
+                }
+                if !matches!(return_type.as_ref(), TypeAstNode::None) {
+                    block.set_slot(params.len(), TypeBlock::from_ast(return_type));
+                }
+                block
+            }
             TypeAstNode::None => {
                 // Do not fill slot, leave it empty
                 TypeBlock::primitive("") // Or handle as needed
@@ -314,6 +927,39 @@ impl TypeBlock {
         }
     }
 
+    /// Swap a primitive for the next one in its group (`i32` → `i64` → ... → `i8`),
+    /// wrapping at the end. Returns false for blocks that have no alternatives.
+    pub fn cycle_primitive(&mut self) -> bool {
+        let TypeBlock::Primitive { name, .. } = self else {
+            return false;
+        };
+        let group = primitive_group(name);
+        let Some(position) = group.iter().position(|primitive| primitive == name) else {
+            return false;
+        };
+        *name = group[(position + 1) % group.len()].to_string();
+        group.len() > 1
+    }
+
+    /// Turn a constructor into another with the same number of params (`Box<T>` → `Rc<T>`),
+    /// keeping its slots. Returns false for other blocks or a param count mismatch.
+    pub fn replace_constructor(&mut self, new_name: &str) -> bool {
+        let TypeBlock::Constructor { name, slots, slot_labels, required_params, lifetimes, const_params, .. } = self else {
+            return false;
+        };
+        if name == new_name || !const_params.is_empty() || !const_param_positions(new_name).is_empty() {
+            return false;
+        }
+        if Self::required_param_count(new_name).is_some_and(|required| required > slots.len()) {
+            return false;
+        }
+        *name = new_name.to_string();
+        *slot_labels = Self::generate_slot_labels(new_name, slots.len());
+        *required_params = Self::required_param_count(new_name).unwrap_or(slots.len()).min(slots.len());
+        *lifetimes = Self::default_lifetimes(lifetime_param_count(new_name));
+        true
+    }
+
     /// Get display name for the block
     pub fn display_name(&self) -> String {
         match self {
@@ -322,6 +968,8 @@ impl TypeBlock {
             TypeBlock::AliasRef { alias, .. } => alias.clone(),
             TypeBlock::Constructor { name, .. } => name.clone(),
             TypeBlock::Tuple { .. } => "Tuple".to_string(),
+            TypeBlock::FnPointer { .. } => "fn".to_string(),
+            TypeBlock::ConstArg { expr, .. } => expr.clone(),
         }
     }
 
@@ -332,13 +980,15 @@ impl TypeBlock {
             | TypeBlock::Path { color, .. }
             | TypeBlock::AliasRef { color, .. }
             | TypeBlock::Constructor { color, .. }
-            | TypeBlock::Tuple { color, .. } => *color,
+            | TypeBlock::Tuple { color, .. }
+            | TypeBlock::FnPointer { color, .. }
+            | TypeBlock::ConstArg { color, .. } => *color,
         }
     }
 
     /// Check if this is a container block (has slots)
     pub fn is_container(&self) -> bool {
-        matches!(self, TypeBlock::Constructor { .. } | TypeBlock::Tuple { .. })
+        matches!(self, TypeBlock::Constructor { .. } | TypeBlock::Tuple { .. } | TypeBlock::FnPointer { .. })
     }
 
     /// Get number of slots (0 for leaf nodes)
@@ -346,12 +996,82 @@ impl TypeBlock {
         match self {
             TypeBlock::Constructor { slots, .. } => slots.len(),
             TypeBlock::Tuple { elements, .. } => elements.len(),
+            TypeBlock::FnPointer { params, .. } => params.len() + 1,
             _ => 0,
         }
     }
     
 
 
+    /// Check if a slot may be left empty
+    pub fn is_slot_optional(&self, index: usize) -> bool {
+        match self {
+            TypeBlock::Constructor { required_params, .. } => index >= *required_params,
+            TypeBlock::FnPointer { params, .. } => index == params.len(),
+            _ => false,
+        }
+    }
+
+    /// Placeholder text describing what belongs in an empty slot
+    pub fn slot_hint(&self, index: usize) -> Option<String> {
+        if self.is_const_slot(index) {
+            return Some("const value".to_string());
+        }
+        match self {
+            TypeBlock::Constructor { name, .. } => SLOT_HINTS
+                .iter()
+                .find(|(constructor, _)| *constructor == name.as_str())
+                .and_then(|(_, hints)| hints.get(index))
+                .map(|hint| hint.to_string()),
+            TypeBlock::Tuple { .. } => Some(format!("element {}", index)),
+            TypeBlock::FnPointer { params, .. } if index == params.len() => Some("return type".to_string()),
+            TypeBlock::FnPointer { .. } => Some(format!("argument {}", index + 1)),
+            _ => None,
+        }
+    }
+
+    /// What a slot is for: its hint (`value`), its param label (`T`), or its position
+    pub fn slot_description(&self, index: usize) -> String {
+        self.slot_hint(index)
+            .or_else(|| self.slot_labels().and_then(|labels| labels.get(index).cloned()))
+            .unwrap_or_else(|| format!("#{}", index))
+    }
+
+    /// Spoken-style description of the block, e.g. "HashMap, generic type, 1 of 2 params filled"
+    pub fn accessible_label(&self) -> String {
+        let filled = (0..self.slot_count()).filter(|i| self.is_slot_filled(*i)).count();
+        match self {
+            TypeBlock::Primitive { name, .. } => format!("{}, primitive type", name),
+            TypeBlock::Path { path, .. } => format!("{}, path type", path),
+            TypeBlock::AliasRef { alias, .. } => format!("{}, alias reference", alias),
+            TypeBlock::ConstArg { expr, .. } => format!("const value {}", expr),
+            TypeBlock::Constructor { name, .. } => {
+                format!("{}, generic type, {} of {} params filled", name, filled, self.slot_count())
+            }
+            TypeBlock::Tuple { .. } => format!("tuple, {} of {} elements filled", filled, self.slot_count()),
+            TypeBlock::FnPointer { .. } => {
+                format!("function pointer, {} of {} arguments filled", filled, self.slot_count().saturating_sub(1))
+            }
+        }
+    }
+
+    /// Description of an empty slot, e.g. "empty slot for HashMap value"
+    pub fn slot_accessible_label(&self, index: usize) -> String {
+        let optional = if self.is_slot_optional(index) { "optional " } else { "" };
+        format!("{}empty slot for {} {}", optional, self.display_name(), self.slot_description(index))
+    }
+
+    /// Blocks and empty slots in Tab order: each block, then its slots in order
+    pub fn focus_order(&self, out: &mut Vec<FocusTarget>) {
+        out.push(FocusTarget::Block(self.id().clone()));
+        for index in 0..self.slot_count() {
+            match self.get_slot(index) {
+                Some(child) => child.focus_order(out),
+                None => out.push(FocusTarget::Slot(self.id().clone(), index)),
+            }
+        }
+    }
+
     /// Check if a slot is filled
     pub fn is_slot_filled(&self, index: usize) -> bool {
         match self {
@@ -361,89 +1081,757 @@ impl TypeBlock {
             TypeBlock::Tuple { elements, .. } => {
                 elements.get(index).and_then(|e| e.as_ref()).is_some()
             }
+            TypeBlock::FnPointer { .. } => self.get_slot(index).is_some(),
             _ => false,
         }
     }
 
-    /// Find a block by ID in this tree
-    pub fn find_block_mut(&mut self, target_id: &BlockId) -> Option<&mut TypeBlock> {
-        if self.id() == target_id {
-            return Some(self);
+    /// Visit this block and every block below it, parents first
+    pub fn walk(&self, f: &mut impl FnMut(&TypeBlock)) {
+        f(self);
+        for child in self.children() {
+            child.walk(f);
         }
+    }
 
-        match self {
-            TypeBlock::Constructor { slots, .. } => {
-                for slot in slots {
-                    if let Some(block) = slot {
-                        if let Some(found) = block.find_block_mut(target_id) {
-                            return Some(found);
-                        }
-                    }
+    /// Visit every block with its slot-index path from this block, parents first
+    pub fn walk_paths(&self, f: &mut impl FnMut(&[usize], &TypeBlock)) {
+        fn visit(block: &TypeBlock, path: &mut Vec<usize>, f: &mut impl FnMut(&[usize], &TypeBlock)) {
+            f(path, block);
+            for index in 0..block.slot_count() {
+                if let Some(child) = block.get_slot(index) {
+                    path.push(index);
+                    visit(child, path, f);
+                    path.pop();
                 }
             }
-            TypeBlock::Tuple { elements, .. } => {
-                for element in elements {
-                    if let Some(block) = element {
-                        if let Some(found) = block.find_block_mut(target_id) {
-                            return Some(found);
-                        }
+        }
+        visit(self, &mut Vec::new(), f);
+    }
+
+    /// The block reached by following slot indices from this block
+    pub fn block_at_path(&self, path: &[usize]) -> Option<&TypeBlock> {
+        match path.split_first() {
+            Some((index, rest)) => self.get_slot(*index)?.block_at_path(rest),
+            None => Some(self),
+        }
+    }
+
+    /// First empty required slot in slot order, descending into filled slots
+    /// before moving on to the next sibling
+    pub fn first_empty_slot(&self) -> Option<(BlockId, usize)> {
+        for index in 0..self.slot_count() {
+            match self.get_slot(index) {
+                Some(child) => {
+                    if let Some(found) = child.first_empty_slot() {
+                        return Some(found);
                     }
                 }
+                None if !self.is_slot_optional(index) => return Some((self.id().clone(), index)),
+                None => {}
             }
-            _ => {}
         }
-
         None
     }
-}
 
-/// Visual representation of a type block
-pub struct TypeBlockView {
-    block: TypeBlock,
-    id: ElementId,
-    on_slot_click: Option<Arc<dyn Fn(BlockId, usize) + Send + Sync + 'static>>,
-}
+    /// Filled child blocks in slot order
+    pub fn children(&self) -> Vec<&TypeBlock> {
+        (0..self.slot_count()).filter_map(|i| self.get_slot(i)).collect()
+    }
 
-impl TypeBlockView {
-    pub fn new(block: TypeBlock, id: impl Into<ElementId>) -> Self {
-        Self {
-            block,
-            id: id.into(),
-            on_slot_click: None,
+    /// Collect multi-param constructors whose filled params are structurally identical
+    /// (`Result<T, T>`), which is usually a mistake
+    pub fn find_duplicate_params(&self, out: &mut Vec<BlockId>) {
+        if let TypeBlock::Constructor { id, name, slots, .. } = self {
+            if slots.len() > 1 && !IDENTICAL_PARAMS_ALLOWED.contains(&name.as_str()) {
+                let params: Vec<_> = slots
+                    .iter()
+                    .filter_map(|slot| slot.as_ref().and_then(|b| b.to_ast()))
+                    .collect();
+                let has_duplicate = params.iter().enumerate().any(|(i, a)| {
+                    params[i + 1..].iter().any(|b| crate::type_ast::structural_eq(a, b))
+                });
+                if has_duplicate {
+                    out.push(id.clone());
+                }
+            }
         }
-    }
-    
-    pub fn on_slot_click(mut self, handler: impl Fn(BlockId, usize) + Send + Sync + 'static) -> Self {
-        self.on_slot_click = Some(Arc::new(handler));
-        self
-    }
+
+        for child in self.children() {
+            child.find_duplicate_params(out);
+        }
+    }
+
+    /// The longest chain of nested constructors below and including this block, outermost
+    /// first. Tuples and fn pointers are passed through without counting.
+    pub fn deepest_constructor_chain(&self) -> Vec<&TypeBlock> {
+        let mut chain = self
+            .children()
+            .into_iter()
+            .map(TypeBlock::deepest_constructor_chain)
+            .max_by_key(Vec::len)
+            .unwrap_or_default();
+        if matches!(self, TypeBlock::Constructor { .. }) {
+            chain.insert(0, self);
+        }
+        chain
+    }
+
+    /// Whether the block is a trait object (`dyn Iterator<Item = T>`, `dyn Display`)
+    pub fn is_trait_object(&self) -> bool {
+        match self {
+            TypeBlock::Constructor { name, .. } => name.starts_with("dyn "),
+            TypeBlock::Path { path, .. } => path.starts_with("dyn "),
+            _ => false,
+        }
+    }
+
+    /// Replace every descendant subtree structurally equal to `pattern` with a fresh copy
+    /// of `replacement`. Replaced subtrees are not searched again. Returns the count.
+    pub fn replace_matching(&mut self, pattern: &TypeAstNode, replacement: &TypeAstNode) -> usize {
+        let mut count = 0;
+        for index in 0..self.slot_count() {
+            let matches = self
+                .get_slot(index)
+                .and_then(|child| child.to_ast())
+                .map(|ast| crate::type_ast::structural_eq(&ast, pattern))
+                .unwrap_or(false);
+
+            if matches {
+                self.set_slot(index, TypeBlock::from_ast(replacement));
+                count += 1;
+            } else if let Some(child) = self.get_slot_mut(index) {
+                count += child.replace_matching(pattern, replacement);
+            }
+        }
+        count
+    }
+
+    /// Rename the generic param `from` to `to` on every primitive or path leaf that
+    /// names it. Returns the number of renamed uses.
+    pub fn rename_type_param(&mut self, from: &str, to: &str) -> usize {
+        match self {
+            TypeBlock::Primitive { name, .. } if name == from => {
+                *name = to.to_string();
+                1
+            }
+            TypeBlock::Path { path, .. } if path == from => {
+                *path = to.to_string();
+                1
+            }
+            _ => {
+                let mut count = 0;
+                for index in 0..self.slot_count() {
+                    if let Some(child) = self.get_slot_mut(index) {
+                        count += child.rename_type_param(from, to);
+                    }
+                }
+                count
+            }
+        }
+    }
+
+    /// Parent block and slot index holding the block with `child_id`
+    pub fn parent_of(&self, child_id: &BlockId) -> Option<(BlockId, usize)> {
+        for index in 0..self.slot_count() {
+            if let Some(child) = self.get_slot(index) {
+                if child.id() == child_id {
+                    return Some((self.id().clone(), index));
+                }
+                if let Some(found) = child.parent_of(child_id) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Find a block by ID in this tree, read-only
+    pub fn find_block(&self, target_id: &BlockId) -> Option<&TypeBlock> {
+        if self.id() == target_id {
+            return Some(self);
+        }
+        self.children().into_iter().find_map(|child| child.find_block(target_id))
+    }
+
+    /// Find a block by ID in this tree
+    pub fn find_block_mut(&mut self, target_id: &BlockId) -> Option<&mut TypeBlock> {
+        if self.id() == target_id {
+            return Some(self);
+        }
+
+        match self {
+            TypeBlock::Constructor { slots, .. } => {
+                for slot in slots {
+                    if let Some(block) = slot {
+                        if let Some(found) = block.find_block_mut(target_id) {
+                            return Some(found);
+                        }
+                    }
+                }
+            }
+            TypeBlock::Tuple { elements, .. } => {
+                for element in elements {
+                    if let Some(block) = element {
+                        if let Some(found) = block.find_block_mut(target_id) {
+                            return Some(found);
+                        }
+                    }
+                }
+            }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                for block in params.iter_mut().chain(std::iter::once(return_type)).flatten() {
+                    if let Some(found) = block.find_block_mut(target_id) {
+                        return Some(found);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// Bounds of rendered blocks and empty slots, recorded during layout
+#[derive(Clone, Debug, Default)]
+pub struct BlockLayout {
+    pub blocks: HashMap<BlockId, Bounds<Pixels>>,
+    /// Empty slots keyed by (parent_id, slot_index)
+    pub slots: HashMap<(BlockId, usize), Bounds<Pixels>>,
+}
+
+impl BlockLayout {
+    /// Elbow lines from each block to its slots, filled or empty: a start under the
+    /// parent's label, the corner, and an end on the slot. Top-down elbows run down then
+    /// across to the slot's left edge; left-to-right ones run across then down to its top
+    /// edge, so they don't cross the slots beside it. Slots the elbow cannot reach from
+    /// above and to the left are skipped.
+    pub fn connectors(&self, root: &TypeBlock, direction: LayoutDirection) -> Vec<[Point<Pixels>; 3]> {
+        let mut lines = Vec::new();
+        root.walk(&mut |block| {
+            let Some(parent) = self.blocks.get(block.id()) else {
+                return;
+            };
+            let start = point(parent.origin.x + px(6.0), parent.origin.y + px(24.0));
+            for index in 0..block.slot_count() {
+                let target = match block.get_slot(index) {
+                    Some(child) => self.blocks.get(child.id()),
+                    None => self.slots.get(&(block.id().clone(), index)),
+                };
+                let Some(target) = target else {
+                    continue;
+                };
+                let (corner, end) = match direction {
+                    LayoutDirection::TopDown => {
+                        let end = point(target.origin.x, target.origin.y + px(14.0));
+                        (point(start.x, end.y), end)
+                    }
+                    LayoutDirection::LeftToRight => {
+                        let end = point(target.origin.x + px(6.0), target.origin.y);
+                        (point(end.x, start.y), end)
+                    }
+                };
+                if end.x > start.x && end.y > start.y {
+                    lines.push([start, corner, end]);
+                }
+            }
+        });
+        lines
+    }
+}
+
+/// Invisible element covering its parent that reports the parent's bounds once laid out
+fn layout_probe(
+    layout: Arc<Mutex<BlockLayout>>,
+    record: impl FnOnce(&mut BlockLayout, Bounds<Pixels>) + 'static,
+) -> impl IntoElement {
+    canvas(
+        move |bounds, _window, _cx| {
+            if let Ok(mut layout) = layout.lock() {
+                record(&mut layout, bounds);
+            }
+        },
+        |_, _, _, _| {},
+    )
+    .absolute()
+    .top_0()
+    .left_0()
+    .size_full()
+}
+
+/// A block dragged out of a canvas by its grip. Hosts accept it on their dock and pass it
+/// to the source editor's `extract_dragged_block`.
+#[derive(Clone, Debug)]
+pub struct DraggedBlock {
+    /// Entity of the editor the block came from
+    pub source: EntityId,
+    pub block_id: BlockId,
+    /// The block's subtree, with empty slots as `TypeAstNode::None`
+    pub ast: TypeAstNode,
+    pub label: String,
+}
+
+impl Render for DraggedBlock {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_3()
+            .py_1()
+            .rounded(px(6.0))
+            .bg(cx.theme().secondary)
+            .border_1()
+            .border_color(cx.theme().border)
+            .shadow_md()
+            .text_sm()
+            .text_color(cx.theme().foreground)
+            .child(format!("⠿ {}", self.label))
+    }
+}
+
+/// Visual representation of a type block
+pub struct TypeBlockView {
+    block: TypeBlock,
+    id: ElementId,
+    on_slot_click: Option<SlotHandler>,
+    /// Non-blocking lint warnings keyed by block, shown as badges
+    warnings: Arc<HashMap<BlockId, String>>,
+    on_dismiss_warning: Option<BlockHandler>,
+    /// Called with the alias name when an alias reference block is double-clicked
+    on_alias_open: Option<AliasHandler>,
+    /// Called with (parent_id, slot_index) when a filled slot is right-clicked to clear it
+    on_slot_clear: Option<SlotHandler>,
+    /// Called when a smart-pointer or cell chip is clicked to swap the constructor
+    on_constructor_swap: Option<ConstructorHandler>,
+    /// Called with (block_id, index) when a "+" between tuple elements or fn params is clicked
+    on_slot_insert: Option<SlotHandler>,
+    /// Called with (block_id, index) when a lifetime chip is clicked to rename it
+    on_lifetime_edit: Option<SlotHandler>,
+    /// Bold white outlines, larger text and theme-independent colors
+    high_contrast: bool,
+    direction: LayoutDirection,
+    /// Where to record block and slot bounds, if anywhere
+    layout: Option<Arc<Mutex<BlockLayout>>>,
+    /// Called with the block's ID when a primitive block is clicked
+    on_block_select: Option<BlockHandler>,
+    /// Block drawn with the selection ring
+    selected: Option<BlockId>,
+    /// Block or empty slot drawn with the keyboard focus ring
+    focused: Option<FocusTarget>,
+    /// Annotate primitives with their size in bytes
+    show_sizes: bool,
+    /// Blocks outlined in a color of their own, e.g. differences in the compare view
+    highlights: Arc<HashMap<BlockId, Hsla>>,
+    /// Blocks still as a Rust import left them, tagged with a small marker
+    imported: Arc<HashSet<BlockId>>,
+    /// Editor named when a block is dragged out by its grip; no grips when unset
+    drag_source: Option<EntityId>,
+    theme: BlockTheme,
+}
+
+impl TypeBlockView {
+    pub fn new(block: TypeBlock, id: impl Into<ElementId>) -> Self {
+        Self {
+            block,
+            id: id.into(),
+            on_slot_click: None,
+            warnings: Arc::new(HashMap::new()),
+            on_dismiss_warning: None,
+            on_alias_open: None,
+            on_slot_clear: None,
+            on_constructor_swap: None,
+            on_slot_insert: None,
+            on_lifetime_edit: None,
+            high_contrast: false,
+            direction: LayoutDirection::default(),
+            layout: None,
+            on_block_select: None,
+            selected: None,
+            focused: None,
+            show_sizes: false,
+            highlights: Arc::new(HashMap::new()),
+            imported: Arc::new(HashSet::new()),
+            drag_source: None,
+            theme: BlockTheme::default(),
+        }
+    }
+
+    pub fn highlights(mut self, highlights: Arc<HashMap<BlockId, Hsla>>) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    pub fn show_sizes(mut self, show_sizes: bool) -> Self {
+        self.show_sizes = show_sizes;
+        self
+    }
+
+    pub fn on_block_select(mut self, handler: Option<BlockHandler>) -> Self {
+        self.on_block_select = handler;
+        self
+    }
+
+    pub fn selected(mut self, selected: Option<BlockId>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn focused(mut self, focused: Option<FocusTarget>) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Record the bounds of this block, its nested blocks and its empty slots during layout
+    pub fn record_layout(mut self, layout: Option<Arc<Mutex<BlockLayout>>>) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Wrap the rendered block so its bounds are recorded, when recording, and in its
+    /// highlight outline and focus ring, if it has them
+    fn with_layout_probe(&self, content: Div) -> Div {
+        let content = if self.focused == Some(FocusTarget::Block(self.block.id().clone())) {
+            div()
+                .p_0p5()
+                .rounded(px(10.0))
+                .border_2()
+                .border_color(FOCUS_RING_COLOR)
+                .child(content)
+        } else {
+            content
+        };
+        let content = match self.highlights.get(self.block.id()) {
+            Some(color) => div()
+                .p_0p5()
+                .rounded(px(9.0))
+                .border_2()
+                .border_color(*color)
+                .bg(color.opacity(0.15))
+                .child(content),
+            None => content,
+        };
+        match &self.layout {
+            Some(layout) => {
+                let block_id = self.block.id().clone();
+                div()
+                    .relative()
+                    .child(content)
+                    .child(layout_probe(layout.clone(), move |layout, bounds| {
+                        layout.blocks.insert(block_id, bounds);
+                    }))
+            }
+            None => content,
+        }
+    }
+
+    pub fn direction(mut self, direction: LayoutDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn high_contrast(mut self, high_contrast: bool) -> Self {
+        self.high_contrast = high_contrast;
+        self
+    }
+
+    fn fill_color(&self) -> Hsla {
+        if self.high_contrast {
+            self.block.color().to_high_contrast_hsla()
+        } else {
+            self.block.color().for_theme(self.theme.dark)
+        }
+    }
+
+    fn outline_color(&self, color: Hsla, lighten: f32) -> Hsla {
+        if self.high_contrast {
+            gpui::white()
+        } else {
+            color.lighten(lighten)
+        }
+    }
+
+    /// Outline of a block segment, bolder in high-contrast mode
+    fn outline<E: Styled>(&self, element: E, color: Hsla) -> E {
+        if self.high_contrast {
+            element.border_2().border_color(gpui::white())
+        } else {
+            element.border_1().border_color(color.lighten(0.1))
+        }
+    }
+
+    /// Outline of a constructor's header and footer, bolder in high-contrast mode
+    fn heavy_outline<E: Styled>(&self, element: E, color: Hsla) -> E {
+        if self.high_contrast {
+            element.border_3().border_color(gpui::white())
+        } else {
+            element.border_2().border_color(color.lighten(0.15))
+        }
+    }
+
+    pub fn on_slot_clear(mut self, handler: Option<SlotHandler>) -> Self {
+        self.on_slot_clear = handler;
+        self
+    }
+
+    pub fn on_constructor_swap(mut self, handler: Option<ConstructorHandler>) -> Self {
+        self.on_constructor_swap = handler;
+        self
+    }
+
+    pub fn on_slot_insert(mut self, handler: Option<SlotHandler>) -> Self {
+        self.on_slot_insert = handler;
+        self
+    }
+
+    pub fn on_lifetime_edit(mut self, handler: Option<SlotHandler>) -> Self {
+        self.on_lifetime_edit = handler;
+        self
+    }
+
+    pub fn on_alias_open(mut self, handler: Option<AliasHandler>) -> Self {
+        self.on_alias_open = handler;
+        self
+    }
+    
+    pub fn on_slot_click(mut self, handler: Option<SlotHandler>) -> Self {
+        self.on_slot_click = handler;
+        self
+    }
+
+    pub fn imported(mut self, imported: Arc<HashSet<BlockId>>) -> Self {
+        self.imported = imported;
+        self
+    }
+
+    pub fn drag_source(mut self, source: Option<EntityId>) -> Self {
+        self.drag_source = source;
+        self
+    }
+
+    pub fn theme(mut self, theme: BlockTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Show warning badges on blocks, with an optional handler to dismiss them
+    pub fn warnings(
+        mut self,
+        warnings: Arc<HashMap<BlockId, String>>,
+        on_dismiss: Option<BlockHandler>,
+    ) -> Self {
+        self.warnings = warnings;
+        self.on_dismiss_warning = on_dismiss;
+        self
+    }
+
+    /// Small warning badge for this block, if it has a warning
+    fn render_warning_badge(&self) -> Option<Div> {
+        let message = self.warnings.get(self.block.id())?;
+        let block_id = self.block.id().clone();
+
+        let mut dismiss = div()
+            .px_1()
+            .text_xs()
+            .text_color(gpui::white())
+            .cursor_pointer()
+            .child("✕");
+        if let Some(handler) = &self.on_dismiss_warning {
+            let handler = Arc::clone(handler);
+            dismiss = dismiss.on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                handler(block_id.clone(), window, cx);
+                window.refresh();
+                cx.stop_propagation();
+            });
+        }
+
+        Some(
+            h_flex()
+                .gap_1()
+                .px_2()
+                .py_0p5()
+                .bg(self.theme.warning.opacity(0.9))
+                .rounded(px(4.0))
+                .items_center()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(gpui::white())
+                        .child(format!("⚠ {}", message))
+                )
+                .child(dismiss)
+        )
+    }
+
+    /// Faint tag on blocks that are unchanged since a Rust import
+    fn render_imported_marker(&self) -> Option<Div> {
+        if !self.imported.contains(self.block.id()) {
+            return None;
+        }
+        Some(
+            div()
+                .px_1()
+                .rounded(px(3.0))
+                .border_1()
+                .border_color(gpui::white().opacity(0.3))
+                .text_xs()
+                .text_color(gpui::white().opacity(0.6))
+                .child("imported")
+        )
+    }
+
+    /// Grip for dragging the block out of the canvas, e.g. onto the dock to open it as an
+    /// alias of its own
+    fn render_drag_grip(&self) -> Option<Stateful<Div>> {
+        let source = self.drag_source?;
+        let payload = DraggedBlock {
+            source,
+            block_id: self.block.id().clone(),
+            ast: self.block.to_ast_with_holes(),
+            label: self.block.display_name(),
+        };
+        Some(
+            div()
+                .id("drag-grip")
+                .px_0p5()
+                .text_xs()
+                .text_color(gpui::white().opacity(0.5))
+                .hover(|this| this.text_color(gpui::white()))
+                .cursor_grab()
+                .child("⠿")
+                .on_drag(payload, |drag, _offset, _window, cx| cx.new(|_| drag.clone()))
+        )
+    }
+
+    /// One-click chips that swap a constructor for the others in its family (`Box` → `Rc`)
+    fn render_swap_chips(&self, name: &str) -> Vec<Stateful<Div>> {
+        let Some(handler) = &self.on_constructor_swap else {
+            return Vec::new();
+        };
+        constructor_alternatives(name)
+            .into_iter()
+            .enumerate()
+            .map(|(i, alternative)| {
+                let handler = Arc::clone(handler);
+                let block_id = self.block.id().clone();
+                div()
+                    .id(("swap", i))
+                    .px_1p5()
+                    .py_0p5()
+                    .rounded(px(4.0))
+                    .bg(gpui::white().opacity(0.1))
+                    .hover(|this| this.bg(gpui::white().opacity(0.3)))
+                    .text_xs()
+                    .text_color(gpui::white().opacity(0.8))
+                    .cursor_pointer()
+                    .child(format!("⇄ {}", alternative))
+                    .on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                        handler(block_id.clone(), alternative.to_string(), window, cx);
+                        window.refresh();
+                        cx.stop_propagation();
+                    })
+            })
+            .collect()
+    }
+
+    /// Small "+" that inserts an empty slot at `index`, shifting the rest
+    fn render_insert_gap(&self, index: usize, color: Hsla) -> Option<Stateful<Div>> {
+        let handler = Arc::clone(self.on_slot_insert.as_ref()?);
+        let block_id = self.block.id().clone();
+        Some(
+            div()
+                .id(("insert-slot", index))
+                .px_1()
+                .rounded(px(4.0))
+                .text_xs()
+                .font_bold()
+                .text_color(color.opacity(0.5))
+                .hover(|this| this.text_color(color).bg(color.opacity(0.15)))
+                .cursor_pointer()
+                .child("+")
+                .on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                    handler(block_id.clone(), index, window, cx);
+                    window.refresh();
+                    cx.stop_propagation();
+                })
+        )
+    }
 
     fn render_leaf_block(&self, cx: Option<&App>) -> Div {
-        let color = self.block.color().to_hsla();
+        let color = self.fill_color();
 
-        h_flex()
+        let mut leaf = h_flex()
             .px_3()
             .py_2()
             .gap_2()
             .bg(color)
             .rounded(px(6.0))
-            .border_1()
-            .border_color(color.lighten(0.1))
+            .map(|this| self.outline(this, color))
             .shadow_sm()
             .child(
                 div()
                     .text_sm()
+                    .when(self.high_contrast, |this| this.text_base())
                     .font_medium()
                     .text_color(gpui::white())
                     .child(self.block.display_name())
             )
+            .when_some(
+                match &self.block {
+                    TypeBlock::Primitive { name, .. } if self.show_sizes => primitive_size(name),
+                    _ => None,
+                },
+                |this, size| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(gpui::white().opacity(0.6))
+                            .child(size)
+                    )
+                },
+            )
+            .children(self.render_imported_marker())
+            .children(self.render_warning_badge())
+            .children(self.render_drag_grip());
+
+        if self.selected.as_ref() == Some(self.block.id()) {
+            leaf = leaf.border_2().border_color(self.theme.selection);
+        }
+
+        // Click a primitive to select it for keyboard cycling, or a const value to edit it
+        if let (TypeBlock::Primitive { id, .. } | TypeBlock::ConstArg { id, .. }, Some(handler)) = (&self.block, &self.on_block_select) {
+            let handler = Arc::clone(handler);
+            let id = id.clone();
+            leaf = leaf
+                .cursor_pointer()
+                .on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                    handler(id.clone(), window, cx);
+                    window.refresh();
+                    cx.stop_propagation();
+                });
+        }
+
+        // Double-click an alias reference to open its definition
+        if let (TypeBlock::AliasRef { alias, .. }, Some(handler)) = (&self.block, &self.on_alias_open) {
+            let handler = Arc::clone(handler);
+            let alias = alias.clone();
+            leaf = leaf
+                .cursor_pointer()
+                .on_mouse_down(gpui::MouseButton::Left, move |event, window, cx| {
+                    if event.click_count == 2 {
+                        handler(alias.clone(), window, cx);
+                        window.refresh();
+                        cx.stop_propagation();
+                    }
+                });
+        }
+
+        leaf
     }
 
     fn render_container_block(&self, cx: Option<&App>) -> Div {
-        let color = self.block.color().to_hsla();
+        let color = self.fill_color();
 
         match &self.block {
-            TypeBlock::Constructor { name, slots, slot_labels, .. } => {
+            TypeBlock::Constructor { name, slots, slot_labels, lifetimes, const_generics, .. } => {
                 v_flex()
                     .gap_0()
                     .min_w(px(200.0))
@@ -454,13 +1842,13 @@ impl TypeBlockView {
                             .py_2()
                             .bg(color)
                             .rounded_t(px(8.0))
-                            .border_2()
-                            .border_color(color.lighten(0.15))
+                            .map(|this| self.heavy_outline(this, color))
                             .items_center()
                             .gap_2()
                             .child(
                                 div()
                                     .text_sm()
+                                    .when(self.high_contrast, |this| this.text_base())
                                     .font_bold()
                                     .text_color(gpui::white())
                                     .child(name.clone())
@@ -471,41 +1859,99 @@ impl TypeBlockView {
                                     .text_color(gpui::white().opacity(0.7))
                                     .child("<")
                             )
+                            .children(lifetimes.iter().enumerate().map(|(i, lifetime)| {
+                                // Lifetime slot - always filled, renders before the type params; click to rename
+                                let chip = div()
+                                    .id(("lifetime", i))
+                                    .px_2()
+                                    .py_0p5()
+                                    .bg(gpui::white().opacity(0.2))
+                                    .rounded(px(4.0))
+                                    .text_xs()
+                                    .font_semibold()
+                                    .text_color(gpui::white())
+                                    .child(format!("'{}", lifetime));
+                                match self.on_lifetime_edit.clone() {
+                                    Some(handler) => {
+                                        let block_id = self.block.id().clone();
+                                        chip.cursor_pointer()
+                                            .hover(|this| this.bg(gpui::white().opacity(0.3)))
+                                            .on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                                                handler(block_id.clone(), i, window, cx);
+                                                window.refresh();
+                                                cx.stop_propagation();
+                                            })
+                                    }
+                                    None => chip,
+                                }
+                            }))
+                            .children(const_generics.iter().enumerate().map(|(i, expr)| {
+                                // Const arg - shows the evaluated value on hover when it is constant arithmetic
+                                let chip = div()
+                                    .id(("const-arg", i))
+                                    .px_2()
+                                    .py_0p5()
+                                    .bg(gpui::white().opacity(0.2))
+                                    .rounded(px(4.0))
+                                    .text_xs()
+                                    .font_semibold()
+                                    .text_color(gpui::white())
+                                    .child(expr.clone());
+                                match const_eval::preview(expr) {
+                                    Some(value) => chip.tooltip(move |window, cx| {
+                                        Tooltip::new(value.clone()).build(window, cx)
+                                    }),
+                                    None => chip,
+                                }
+                            }))
+                            .children(self.render_swap_chips(name))
+                            .children(self.render_imported_marker())
+                            .children(self.render_warning_badge())
+                            .children(self.render_drag_grip())
                     )
-                    .children(slots.iter().enumerate().map(|(i, slot)| {
-                        // Each slot has a label and wraps with notches (Scratch-style)
-                        let label = slot_labels.get(i).map(|s| s.as_str()).unwrap_or("T");
+                    .child(
+                        div()
+                            .flex()
+                            .map(|this| match self.direction {
+                                LayoutDirection::TopDown => this.flex_col(),
+                                LayoutDirection::LeftToRight => this.flex_row().items_stretch(),
+                            })
+                            .children(slots.iter().enumerate().map(|(i, slot)| {
+                                // Each slot has a label and wraps with notches (Scratch-style)
+                                let label = slot_labels.get(i).map(|s| s.as_str()).unwrap_or("T");
                         
-                        v_flex()
-                            .gap_0()
-                            .child(
-                                // Slot label and notch top
-                                h_flex()
-                                    .bg(color.opacity(0.3))
-                                    .border_x_2()
-                                    .border_color(color.lighten(0.15))
-                                    .pl_4()
-                                    .pr_2()
-                                    .py_1()
+                                v_flex()
+                                    .gap_0()
+                                    .when(self.direction == LayoutDirection::LeftToRight, |this| this.flex_1())
                                     .child(
-                                        div()
-                                            .text_xs()
-                                            .font_semibold()
-                                            .text_color(color.lighten(0.3))
-                                            .child(format!("{}: ", label))
+                                        // Slot label and notch top
+                                        h_flex()
+                                            .bg(color.opacity(0.3))
+                                            .border_x_2()
+                                            .border_color(self.outline_color(color, 0.15))
+                                            .pl_4()
+                                            .pr_2()
+                                            .py_1()
+                                            .child(
+                                                div()
+                                                    .text_xs()
+                                                    .font_semibold()
+                                                    .text_color(self.outline_color(color, 0.3))
+                                                    .child(format!("{}: ", label))
+                                            )
                                     )
-                            )
-                            .child(
-                                // Slot content area with inset
-                                h_flex()
-                                    .bg(color.opacity(0.15))
-                                    .border_x_2()
-                                    .border_color(color.lighten(0.15))
-                                    .px_3()
-                                    .py_3()
-                                    .child(self.render_slot(i, slot, cx))
-                            )
-                    }))
+                                    .child(
+                                        // Slot content area with inset
+                                        h_flex()
+                                            .bg(color.opacity(0.15))
+                                            .border_x_2()
+                                            .border_color(self.outline_color(color, 0.15))
+                                            .px_3()
+                                            .py_3()
+                                            .child(self.render_slot(i, slot, cx))
+                                    )
+                            }))
+                    )
                     .child(
                         // Footer - curved bottom, closes the wrap
                         h_flex()
@@ -513,8 +1959,7 @@ impl TypeBlockView {
                             .py_2()
                             .bg(color)
                             .rounded_b(px(8.0))
-                            .border_2()
-                            .border_color(color.lighten(0.15))
+                            .map(|this| self.heavy_outline(this, color))
                             .items_center()
                             .child(
                                 div()
@@ -535,8 +1980,7 @@ impl TypeBlockView {
                             .py_2()
                             .bg(color)
                             .rounded_l(px(6.0))
-                            .border_1()
-                            .border_color(color.lighten(0.1))
+                            .map(|this| self.outline(this, color))
                             .text_sm()
                             .font_bold()
                             .text_color(gpui::white())
@@ -546,8 +1990,10 @@ impl TypeBlockView {
                         elements.iter().enumerate().map(|(i, el)| {
                             h_flex()
                                 .gap_1()
+                                .children(self.render_insert_gap(i, color))
                                 .child(self.render_slot(i, el, cx))
-                                .when(i < elements.len() - 1, |this| {
+                                // One-element tuples keep their trailing comma, as in `(T,)`
+                                .when(i < elements.len() - 1 || elements.len() == 1, |this| {
                                     this.child(
                                         div()
                                             .text_sm()
@@ -557,20 +2003,76 @@ impl TypeBlockView {
                                 })
                         })
                     )
+                    .children(self.render_insert_gap(elements.len(), color))
                     .child(
                         div()
                             .px_2()
                             .py_2()
                             .bg(color)
                             .rounded_r(px(6.0))
-                            .border_1()
-                            .border_color(color.lighten(0.1))
+                            .map(|this| self.outline(this, color))
                             .text_sm()
                             .font_bold()
                             .text_color(gpui::white())
                             .child(")")
                     )
             }
+            TypeBlock::FnPointer { params, return_type, .. } => {
+                let return_index = params.len();
+
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_2()
+                            .bg(color)
+                            .rounded_l(px(6.0))
+                            .map(|this| self.outline(this, color))
+                            .text_sm()
+                            .font_bold()
+                            .text_color(gpui::white())
+                            .child("fn(")
+                    )
+                    .children(
+                        params.iter().enumerate().map(|(i, param)| {
+                            h_flex()
+                                .gap_1()
+                                .children(self.render_insert_gap(i, color))
+                                .child(self.render_slot(i, param, cx))
+                                .when(i < params.len() - 1, |this| {
+                                    this.child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(color)
+                                            .child(",")
+                                    )
+                                })
+                        })
+                    )
+                    .children(self.render_insert_gap(return_index, color))
+                    .child(
+                        div()
+                            .px_2()
+                            .py_2()
+                            .bg(color)
+                            .map(|this| self.outline(this, color))
+                            .text_sm()
+                            .font_bold()
+                            .text_color(gpui::white())
+                            .child(") ->")
+                    )
+                    .child(self.render_slot(return_index, return_type, cx))
+                    .child(
+                        div()
+                            .px_1()
+                            .py_2()
+                            .bg(color)
+                            .rounded_r(px(6.0))
+                            .map(|this| self.outline(this, color))
+                    )
+            }
             _ => div().child(self.render_leaf_block(cx)),
         }
     }
@@ -582,51 +2084,138 @@ impl TypeBlockView {
                 ("slot", index),
             );
             
-            // Pass down the click handler to nested blocks
-            if let Some(handler) = &self.on_slot_click {
+            // Pass the handlers down to nested blocks
+            nested_view = nested_view
+                .on_slot_click(self.on_slot_click.clone())
+                .warnings(self.warnings.clone(), self.on_dismiss_warning.clone())
+                .on_alias_open(self.on_alias_open.clone())
+                .on_slot_clear(self.on_slot_clear.clone())
+                .on_constructor_swap(self.on_constructor_swap.clone())
+                .on_slot_insert(self.on_slot_insert.clone())
+                .on_lifetime_edit(self.on_lifetime_edit.clone())
+                .high_contrast(self.high_contrast)
+                .direction(self.direction)
+                .record_layout(self.layout.clone())
+                .on_block_select(self.on_block_select.clone())
+                .selected(self.selected.clone())
+                .focused(self.focused.clone())
+                .show_sizes(self.show_sizes)
+                .highlights(self.highlights.clone())
+                .imported(self.imported.clone())
+                .drag_source(self.drag_source)
+                .theme(self.theme);
+
+            let mut slot_div = div().child(nested_view);
+
+            // Right-click clears the slot, taking the whole subtree with it
+            if let Some(handler) = &self.on_slot_clear {
                 let handler = Arc::clone(handler);
-                nested_view = nested_view.on_slot_click(move |id, idx| handler(id, idx));
+                let parent_id = self.block.id().clone();
+                slot_div = slot_div.on_mouse_down(gpui::MouseButton::Right, move |_event, window, cx| {
+                    handler(parent_id.clone(), index, window, cx);
+                    window.refresh();
+                    cx.stop_propagation();
+                });
             }
-
-            div()
-                .child(nested_view)
+            slot_div
         } else {
-            // Empty slot - clickable drop zone
+            // Empty slot - clickable drop zone. Optional slots get a dashed outline,
+            // required ones a solid warning outline since save needs them filled.
             let parent_id = self.block.id();
             let slot_idx = index;
+            let optional = self.block.is_slot_optional(index);
+            let hint = self.block.slot_hint(index);
             
+            // High contrast: black well with a bold yellow outline, cyan while hovered
+            let high_contrast = self.high_contrast;
+            let (optional_border, required_border, hover_border) = if high_contrast {
+                (hsla(0.0, 0.0, 1.0, 1.0), hsla(0.15, 1.0, 0.5, 1.0), hsla(0.5, 1.0, 0.5, 1.0))
+            } else {
+                (self.theme.slot_border.opacity(0.6), self.theme.warning.opacity(0.8), self.theme.selection.opacity(0.8))
+            };
+            let (hint_color, note_color) = if high_contrast {
+                (gpui::white(), gpui::white())
+            } else {
+                (self.theme.hint, self.theme.hint.opacity(0.75))
+            };
+            let (well, hover_well) = if high_contrast {
+                (gpui::black(), hsla(0.0, 0.0, 0.15, 1.0))
+            } else {
+                (self.theme.slot_well.opacity(0.2), self.theme.slot_well.opacity(0.35))
+            };
+
             let mut slot_div = div()
                 .min_w(px(150.0))
                 .px_4()
                 .py_4()
-                .bg(hsla(0.0, 0.0, 0.2, 0.2))
+                .bg(well)
                 .rounded(px(6.0))
-                .border_2()
-                .border_color(hsla(0.0, 0.0, 0.4, 0.6))
-                .border_dashed()
+                .map(|this| if high_contrast { this.border_3() } else { this.border_2() })
+                .when(optional, |this| {
+                    this.border_color(optional_border).border_dashed()
+                })
+                .when(!optional, |this| {
+                    this.border_color(required_border)
+                })
                 .items_center()
                 .justify_center()
-                .hover(|style| {
+                .hover(move |style| {
                     style
-                        .bg(hsla(0.0, 0.0, 0.3, 0.3))
-                        .border_color(hsla(0.6, 0.7, 0.6, 0.8))
+                        .bg(hover_well)
+                        .border_color(hover_border)
                         .cursor_pointer()
                 })
                 .child(
-                    div()
-                        .text_xs()
-                        .text_color(hsla(0.0, 0.0, 0.5, 1.0))
-                        .child("click to select slot")
+                    v_flex()
+                        .items_center()
+                        .gap_0p5()
+                        .when_some(hint, |this, hint| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .when(high_contrast, |this| this.text_base())
+                                    .italic()
+                                    .text_color(hint_color)
+                                    .child(hint)
+                            )
+                        })
+                        .child(
+                            div()
+                                .text_xs()
+                                .when(high_contrast, |this| this.text_sm())
+                                .text_color(note_color)
+                                .child(if self.block.is_const_slot(index) {
+                                    "click to enter value"
+                                } else if optional {
+                                    "optional - click to fill"
+                                } else {
+                                    "click to select slot"
+                                })
+                        )
                 );
             
             // Add click handler if provided
             if let Some(handler) = &self.on_slot_click {
                 let handler = Arc::clone(handler);
                 let parent_id = parent_id.clone();
-                slot_div = slot_div.on_mouse_down(gpui::MouseButton::Left, move |_event, _window, _cx| {
-                    handler(parent_id.clone(), slot_idx);
+                slot_div = slot_div.on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                    handler(parent_id.clone(), slot_idx, window, cx);
                 });
             }
+            if let Some(layout) = &self.layout {
+                let slot = (parent_id.clone(), slot_idx);
+                slot_div = slot_div.relative().child(layout_probe(layout.clone(), move |layout, bounds| {
+                    layout.slots.insert(slot, bounds);
+                }));
+            }
+            if self.focused == Some(FocusTarget::Slot(parent_id.clone(), slot_idx)) {
+                slot_div = div()
+                    .p_0p5()
+                    .rounded(px(8.0))
+                    .border_2()
+                    .border_color(FOCUS_RING_COLOR)
+                    .child(slot_div);
+            }
             
             slot_div
         }
@@ -644,7 +2233,7 @@ impl IntoElement for TypeBlockView {
             self.render_leaf_block(None)
         };
         
-        div().id(id).child(content)
+        div().id(id).child(self.with_layout_probe(content))
     }
 }
 
@@ -656,6 +2245,91 @@ impl RenderOnce for TypeBlockView {
             self.render_leaf_block(Some(cx))
         };
         
+        let content = self.with_layout_probe(content);
         div().id(self.id).child(content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals_pick_the_narrowest_primitive_first() {
+        assert_eq!(primitives_for_literal("255").first(), Some(&"u8"));
+        assert_eq!(primitives_for_literal("256").first(), Some(&"u16"));
+        assert_eq!(primitives_for_literal("1_000_000").first(), Some(&"u32"));
+        assert!(primitives_for_literal("inf").is_empty());
+        assert!(primitives_for_literal("u8").is_empty());
+    }
+
+    #[test]
+    fn suffixed_literals_give_only_their_type() {
+        assert_eq!(primitives_for_literal("255u16"), vec!["u16"]);
+        assert_eq!(primitives_for_literal("1_i64"), vec!["i64"]);
+        assert_eq!(primitives_for_literal("2.5f32"), vec!["f32"]);
+        // Out of range for the suffix
+        assert!(primitives_for_literal("256u8").is_empty());
+    }
+
+    #[test]
+    fn hex_octal_and_binary_literals() {
+        assert_eq!(primitives_for_literal("0xff").first(), Some(&"u8"));
+        assert_eq!(primitives_for_literal("0x100").first(), Some(&"u16"));
+        assert_eq!(primitives_for_literal("0o777").first(), Some(&"u16"));
+        assert_eq!(primitives_for_literal("0b1010").first(), Some(&"u8"));
+        // `f` is a hex digit, not a float suffix: this is 3890, not `0x` in an `f32`
+        assert_eq!(primitives_for_literal("0xf32").first(), Some(&"u16"));
+    }
+
+    #[test]
+    fn negative_literals_only_fit_signed_and_float_types() {
+        let fitting = primitives_for_literal("-128");
+        assert_eq!(fitting.first(), Some(&"i8"));
+        assert!(fitting.iter().all(|primitive| !primitive.starts_with('u')));
+        assert_eq!(primitives_for_literal("-129").first(), Some(&"i16"));
+    }
+
+    #[test]
+    fn floats_fit_f32_only_without_extra_rounding() {
+        assert_eq!(primitives_for_literal("0.5"), vec!["f32", "f64"]);
+        assert_eq!(primitives_for_literal("0.1"), vec!["f64"]);
+        assert_eq!(primitives_for_literal("1e9"), vec!["f32", "f64"]);
+        // Integers past the f32 mantissa only fit f64 among the floats
+        let fitting = primitives_for_literal("16777217");
+        assert!(fitting.contains(&"f64") && !fitting.contains(&"f32"));
+    }
+
+    /// `Result<_, _>` with its slots side by side below the header, as `LeftToRight` lays it out
+    fn side_by_side() -> (TypeBlock, BlockLayout) {
+        let root = TypeBlock::constructor("Result", 2);
+        let mut layout = BlockLayout::default();
+        layout.blocks.insert(root.id().clone(), Bounds::new(point(px(0.0), px(0.0)), size(px(300.0), px(120.0))));
+        for index in 0..2 {
+            let origin = point(px(14.0 + 140.0 * index as f32), px(60.0));
+            layout.slots.insert((root.id().clone(), index), Bounds::new(origin, size(px(120.0), px(40.0))));
+        }
+        (root, layout)
+    }
+
+    #[test]
+    fn left_to_right_connectors_run_across_then_down() {
+        let (root, layout) = side_by_side();
+        let lines = layout.connectors(&root, LayoutDirection::LeftToRight);
+        assert_eq!(lines.len(), 2);
+        for [start, corner, end] in lines {
+            assert_eq!(corner.y, start.y);
+            assert_eq!(corner.x, end.x);
+            assert_eq!(end.y, px(60.0));
+        }
+    }
+
+    #[test]
+    fn top_down_connectors_run_down_then_across() {
+        let (root, layout) = side_by_side();
+        for [start, corner, end] in layout.connectors(&root, LayoutDirection::TopDown) {
+            assert_eq!(corner.x, start.x);
+            assert_eq!(corner.y, end.y);
+        }
+    }
+}
@@ -1,92 +1,384 @@
+use gpui::App;
 use ui::IconName;
 use ui_common::command_palette::{PaletteDelegate, PaletteItem};
-use crate::{TypeBlock, BlockId};
+use crate::{TypeBlock, BlockId, ShowTypePickerRequest};
+use crate::project::{ProjectTypeConfig, SlotConstraint};
+use crate::settings::EditorSettings;
+use crate::type_ast;
+use crate::type_block::{lifetime_param_count, primitives_for_literal};
+use std::collections::HashMap;
+use std::sync::Mutex;
 
+/// Where the user last picked a type, remembered for the rest of the session
+/// so reopening the picker lands where they were.
+static LAST_POSITION: Mutex<Option<PickerPosition>> = Mutex::new(None);
+
+/// A confirmed item and the category it was listed under
+#[derive(Clone, Debug)]
+struct PickerPosition {
+    category: String,
+    item: String,
+}
+
+/// Icon names accepted in the `categoryIcons` config
+const ICON_NAMES: &[(&str, IconName)] = &[
+    ("Box", IconName::Box),
+    ("Code", IconName::Code),
+    ("BookOpen", IconName::BookOpen),
+    ("Calendar", IconName::Calendar),
+    ("Copy", IconName::Copy),
+    ("ExternalLink", IconName::ExternalLink),
+    ("File", IconName::File),
+    ("Folder", IconName::Folder),
+    ("Frame", IconName::Frame),
+    ("Globe", IconName::Globe),
+    ("Heart", IconName::Heart),
+    ("Inbox", IconName::Inbox),
+    ("Info", IconName::Info),
+    ("Map", IconName::Map),
+    ("Palette", IconName::Palette),
+    ("Settings", IconName::Settings),
+    ("Star", IconName::Star),
+    ("User", IconName::User),
+];
+
+/// Look up an icon by its config name
+pub fn icon_from_name(name: &str) -> Option<IconName> {
+    ICON_NAMES.iter().find(|(n, _)| *n == name).map(|(_, icon)| icon.clone())
+}
+
+/// Slots pre-filled with a primitive when smart defaults are on: (constructor, slot, type)
+const SMART_DEFAULT_SLOTS: &[(&str, usize, &str)] = &[
+    ("HashMap", 0, "String"),
+    ("BTreeMap", 0, "String"),
+    ("IndexMap", 0, "String"),
+];
+
+/// Base URL of the standard library docs
+const STD_DOCS_URL: &str = "https://doc.rust-lang.org/std";
+
+/// Module path and item kind of std constructors, used to build rustdoc URLs
+const STD_DOC_PATHS: &[(&str, &str, &str)] = &[
+    ("Box", "boxed", "struct"),
+    ("Rc", "rc", "struct"),
+    ("Arc", "sync", "struct"),
+    ("Mutex", "sync", "struct"),
+    ("RwLock", "sync", "struct"),
+    ("MutexGuard", "sync", "struct"),
+    ("RwLockReadGuard", "sync", "struct"),
+    ("RwLockWriteGuard", "sync", "struct"),
+    ("Weak", "sync", "struct"),
+    ("Cell", "cell", "struct"),
+    ("RefCell", "cell", "struct"),
+    ("Ref", "cell", "struct"),
+    ("RefMut", "cell", "struct"),
+    ("Vec", "vec", "struct"),
+    ("VecDeque", "collections", "struct"),
+    ("HashMap", "collections", "struct"),
+    ("HashSet", "collections", "struct"),
+    ("BTreeMap", "collections", "struct"),
+    ("BTreeSet", "collections", "struct"),
+    ("BinaryHeap", "collections", "struct"),
+    ("LinkedList", "collections", "struct"),
+    ("Option", "option", "enum"),
+    ("Result", "result", "enum"),
+    ("Cow", "borrow", "enum"),
+    ("Pin", "pin", "struct"),
+    ("PhantomData", "marker", "struct"),
+    ("dyn Future", "future", "trait"),
+    ("dyn Iterator", "iter", "trait"),
+];
+
+/// Crate a built-in constructor comes from: `std` for the ones with std docs,
+/// `pulsar_std` for the rest of the registry
+fn builtin_crate(name: &str) -> &'static str {
+    if STD_DOC_PATHS.iter().any(|(n, _, _)| *n == name) {
+        "std"
+    } else {
+        "pulsar_std"
+    }
+}
+
+/// Rustdoc URL for a std constructor or primitive, if it is one we know
+pub fn documentation_url(name: &str) -> Option<String> {
+    if ui_types_common::PRIMITIVES.contains(&name) {
+        let primitive = if name == "()" { "unit" } else { name };
+        return Some(format!("{}/primitive.{}.html", STD_DOCS_URL, primitive));
+    }
+
+    let (_, module, kind) = STD_DOC_PATHS.iter().find(|(n, _, _)| *n == name)?;
+    let item = name.trim_start_matches("dyn ");
+    Some(format!("{}/{}/{}.{}.html", STD_DOCS_URL, module, kind, item))
+}
+
+/// Category listed first while the search is a numeric literal, see `query_changed`
+const LITERAL_CATEGORY: &str = "Fits the literal";
+
+/// `icon` is the item's category icon from the `categoryIcons` config, if it has one
 #[derive(Clone)]
 pub enum TypeItem {
-    Primitive(String),
-    Constructor { name: String, params_count: usize, description: String },
+    /// `literal` is the numeric literal the primitive was suggested for, matched as a keyword
+    Primitive { name: String, icon: Option<IconName>, literal: Option<String> },
+    /// `const_params` adds const value positions to the built-in ones, for project constructors;
+    /// `lifetimes` is how many lifetime params come before the type params
+    Constructor { name: String, params_count: usize, description: String, const_params: Vec<usize>, lifetimes: usize, icon: Option<IconName> },
+    /// Pre-assembled block structure with the interesting slots left empty
+    Combo { name: String, description: String, build: fn() -> TypeBlock, icon: Option<IconName> },
 }
 
 impl PaletteItem for TypeItem {
     fn name(&self) -> &str {
         match self {
-            TypeItem::Primitive(name) => name,
+            TypeItem::Primitive { name, .. } => name,
             TypeItem::Constructor { name, .. } => name,
+            TypeItem::Combo { name, .. } => name,
         }
     }
 
     fn description(&self) -> &str {
         match self {
-            TypeItem::Primitive(_) => "Primitive type",
+            TypeItem::Primitive { name, .. } if name == type_ast::SELF_TYPE => "The implementing type, inside a trait or impl",
+            TypeItem::Primitive { .. } => "Primitive type",
             TypeItem::Constructor { description, .. } => description,
+            TypeItem::Combo { description, .. } => description,
         }
     }
 
     fn icon(&self) -> IconName {
         match self {
-            TypeItem::Primitive(_) => IconName::Code,
-            TypeItem::Constructor { .. } => IconName::Box,
+            TypeItem::Primitive { icon, .. } => icon.clone().unwrap_or(IconName::Code),
+            TypeItem::Constructor { icon, .. } | TypeItem::Combo { icon, .. } => icon.clone().unwrap_or(IconName::Box),
         }
     }
 
     fn keywords(&self) -> Vec<&str> {
-        vec![]
+        match self {
+            TypeItem::Primitive { literal: Some(literal), .. } => vec![literal.as_str()],
+            _ => vec![],
+        }
     }
 
     fn documentation(&self) -> Option<String> {
-        None
+        let url = self.documentation_url()?;
+        Some(format!("{}\n\n[Docs]({})", self.description(), url))
     }
 }
 
 pub struct TypeLibraryPalette {
     categories: Vec<(String, Vec<TypeItem>)>,
+    placeholder: String,
+    last_position: Option<PickerPosition>,
     selected_item: Option<TypeItem>,
     target_slot: Option<(BlockId, usize)>,
+    target_slots: Vec<(BlockId, usize)>,
+    grouped_by_crate: bool,
 }
 
 impl TypeLibraryPalette {
-    pub fn new(target_slot: Option<(BlockId, usize)>) -> Self {
+    /// Create the palette without any project config; editors should use `for_request`
+    pub fn new(target_slot: Option<(BlockId, usize)>, cx: &App) -> Self {
+        Self::new_with_config(target_slot, &ProjectTypeConfig::default(), cx)
+    }
+
+    /// Create the palette an editor asked for: its project config, every target slot
+    /// and the target slot's constraint
+    pub fn for_request(request: &ShowTypePickerRequest, cx: &App) -> Self {
+        Self::new_with_config(request.target_slot.clone(), &request.type_config, cx)
+            .with_target_slots(request.target_slots.clone())
+            .with_slot_constraint(request.slot_constraint.clone())
+    }
+
+    /// Create the palette with the project's allow/deny lists applied
+    pub fn new_with_config(target_slot: Option<(BlockId, usize)>, config: &ProjectTypeConfig, cx: &App) -> Self {
         use pulsar_std::get_all_type_constructors;
         use ui_types_common::PRIMITIVES;
-        use std::collections::HashMap;
+        use std::collections::BTreeMap;
 
         let mut categories: Vec<(String, Vec<TypeItem>)> = Vec::new();
 
         // Add primitives category
         let primitives: Vec<TypeItem> = PRIMITIVES
             .iter()
-            .map(|&name| TypeItem::Primitive(name.to_string()))
+            .chain([type_ast::SELF_TYPE].iter())
+            .map(|&name| TypeItem::Primitive { name: name.to_string(), icon: None, literal: None })
             .collect();
         categories.push(("Primitives".to_string(), primitives));
 
-        // Group constructors by category
+        // Group constructors by category, or by crate then category. Keys sort std first,
+        // then pulsar_std, then project crates.
+        let grouped_by_crate = EditorSettings::global(cx).group_by_crate;
+        let group_key = |krate: &str, category: &str| -> (u8, String) {
+            if !grouped_by_crate {
+                return (0, category.to_string());
+            }
+            let rank = match krate {
+                "std" => 0,
+                "pulsar_std" => 1,
+                _ => 2,
+            };
+            (rank, format!("{} › {}", krate, category))
+        };
         let constructors = get_all_type_constructors();
-        let mut by_category: HashMap<&str, Vec<TypeItem>> = HashMap::new();
+        let mut by_category: BTreeMap<(u8, String), Vec<TypeItem>> = BTreeMap::new();
         for ctor in constructors {
             by_category
-                .entry(ctor.category)
+                .entry(group_key(builtin_crate(ctor.name), ctor.category))
                 .or_insert_with(Vec::new)
                 .push(TypeItem::Constructor {
                     name: ctor.name.to_string(),
                     params_count: ctor.params_count,
                     description: ctor.description.to_string(),
+                    const_params: Vec::new(),
+                    lifetimes: lifetime_param_count(ctor.name),
+                    icon: None,
+                });
+        }
+        for ctor in &config.constructors {
+            by_category
+                .entry(group_key(&ctor.crate_name, &ctor.category))
+                .or_insert_with(Vec::new)
+                .push(TypeItem::Constructor {
+                    name: ctor.name.clone(),
+                    params_count: ctor.params_count,
+                    description: ctor.description.clone(),
+                    const_params: ctor.const_params.clone(),
+                    lifetimes: ctor.lifetimes,
+                    icon: None,
                 });
         }
 
-        // Sort categories for stable order
-        let mut category_list: Vec<_> = by_category.into_iter().collect();
-        category_list.sort_by_key(|(name, _)| *name);
+        for ((_, category_name), items) in by_category {
+            categories.push((category_name, items));
+        }
+
+        categories.push(("Combos".to_string(), TypeItem::combos()));
 
-        for (category_name, items) in category_list {
-            categories.push((category_name.to_string(), items));
+        // Hide types the project does not allow
+        let mut hidden = 0;
+        for (_, items) in categories.iter_mut() {
+            let before = items.len();
+            items.retain(|item| match item {
+                TypeItem::Primitive { name, .. } | TypeItem::Constructor { name, .. } => config.is_type_allowed(name),
+                TypeItem::Combo { .. } => true,
+            });
+            hidden += before - items.len();
         }
+        categories.retain(|(_, items)| !items.is_empty());
+
+        let placeholder = if hidden > 0 {
+            format!("Search for types... ({} hidden by project config)", hidden)
+        } else {
+            "Search for types...".to_string()
+        };
+
+        let last_position = LAST_POSITION.lock().ok().and_then(|guard| guard.clone());
+
+        let category_icons = config
+            .category_icons
+            .iter()
+            .filter_map(|(category, icon)| Some((category.clone(), icon_from_name(icon)?)))
+            .collect();
 
         Self {
             categories,
+            placeholder,
+            last_position,
             selected_item: None,
+            target_slots: target_slot.iter().cloned().collect(),
             target_slot,
+            grouped_by_crate,
         }
+        .with_category_icons(category_icons)
+    }
+
+    /// Show `icons` for the items of the mapped categories. Under crate grouping a
+    /// category also matches by its own name (`Collections` for `std › Collections`).
+    pub fn with_category_icons(mut self, icons: HashMap<String, IconName>) -> Self {
+        for (category, items) in self.categories.iter_mut() {
+            let base = category.rsplit(" › ").next().unwrap_or(category);
+            if let Some(icon) = icons.get(category.as_str()).or_else(|| icons.get(base)) {
+                for item in items.iter_mut() {
+                    item.set_icon(icon.clone());
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether categories are nested under their crate (`std › Collections`)
+    pub fn groups_by_crate(&self) -> bool {
+        self.grouped_by_crate
+    }
+
+    /// Category to expand when the picker opens, if the user picked from it earlier in the session
+    pub fn last_category(&self) -> Option<&str> {
+        let position = self.last_position.as_ref()?;
+        self.categories
+            .iter()
+            .any(|(name, _)| *name == position.category)
+            .then_some(position.category.as_str())
+    }
+
+    /// Item to select and scroll to when the picker opens: the one confirmed last this
+    /// session, if this picker still lists it. The category order is left as is.
+    pub fn last_item(&self) -> Option<&TypeItem> {
+        let position = self.last_position.as_ref()?;
+        self.categories
+            .iter()
+            .find(|(name, _)| *name == position.category)?
+            .1
+            .iter()
+            .find(|item| item.name() == position.item)
+    }
+
+    /// Call as the search text changes. Typing a query forgets the remembered
+    /// position, so only an explicit search moves the picker back to the top.
+    /// A numeric literal such as `255` lists the primitives that hold it first.
+    pub fn query_changed(&mut self, query: &str) {
+        if !query.trim().is_empty() {
+            self.reset_last_category();
+        }
+        self.suggest_for_literal(query.trim());
+    }
+
+    /// List the offered primitives that hold `literal` under `LITERAL_CATEGORY`, best fit
+    /// first; the category goes away once the search is not a number
+    fn suggest_for_literal(&mut self, literal: &str) {
+        self.categories.retain(|(name, _)| name != LITERAL_CATEGORY);
+        let offered = |name: &str| {
+            self.categories
+                .iter()
+                .flat_map(|(_, items)| items)
+                .any(|item| matches!(item, TypeItem::Primitive { name: offered, .. } if offered == name))
+        };
+        let items: Vec<TypeItem> = primitives_for_literal(literal)
+            .into_iter()
+            .filter(|name| offered(name))
+            .map(|name| TypeItem::Primitive {
+                name: name.to_string(),
+                icon: None,
+                literal: Some(literal.to_string()),
+            })
+            .collect();
+        if !items.is_empty() {
+            self.categories.insert(0, (LITERAL_CATEGORY.to_string(), items));
+        }
+    }
+
+    /// Forget the remembered position, see `query_changed`
+    pub fn reset_last_category(&mut self) {
+        self.last_position = None;
+        if let Ok(mut guard) = LAST_POSITION.lock() {
+            *guard = None;
+        }
+    }
+
+    fn category_of(&self, item: &TypeItem) -> Option<&str> {
+        self.categories
+            .iter()
+            .filter(|(name, _)| name != LITERAL_CATEGORY)
+            .find(|(_, items)| items.iter().any(|i| i.name() == item.name()))
+            .map(|(name, _)| name.as_str())
     }
 
     pub fn take_selected_item(&mut self) -> Option<TypeItem> {
@@ -96,13 +388,40 @@ impl TypeLibraryPalette {
     pub fn target_slot(&self) -> Option<(BlockId, usize)> {
         self.target_slot.clone()
     }
+
+    /// Every slot the confirmed type fills, see `ShowTypePickerRequest::target_slots`.
+    /// Pass these to `add_type_from_picker`.
+    pub fn target_slots(&self) -> Vec<(BlockId, usize)> {
+        self.target_slots.clone()
+    }
+
+    /// Fill several slots from one confirmation; an empty list keeps the single target slot
+    pub fn with_target_slots(mut self, slots: Vec<(BlockId, usize)>) -> Self {
+        if !slots.is_empty() {
+            self.target_slots = slots;
+        }
+        self
+    }
+
+    /// Only offer types the target slot accepts, see `ShowTypePickerRequest::slot_constraint`
+    pub fn with_slot_constraint(mut self, constraint: Option<SlotConstraint>) -> Self {
+        let Some(constraint) = constraint else {
+            return self;
+        };
+        for (_, items) in self.categories.iter_mut() {
+            items.retain(|item| item.fits(&constraint));
+        }
+        self.categories.retain(|(_, items)| !items.is_empty());
+        self.placeholder = format!("Search for types... (this slot takes {})", constraint.describe());
+        self
+    }
 }
 
 impl PaletteDelegate for TypeLibraryPalette {
     type Item = TypeItem;
 
     fn placeholder(&self) -> &str {
-        "Search for types..."
+        &self.placeholder
     }
 
     fn categories(&self) -> Vec<(String, Vec<Self::Item>)> {
@@ -110,6 +429,13 @@ impl PaletteDelegate for TypeLibraryPalette {
     }
 
     fn confirm(&mut self, item: &Self::Item) {
+        if let Some(category) = self.category_of(item).map(str::to_string) {
+            let position = PickerPosition { category, item: item.name().to_string() };
+            if let Ok(mut guard) = LAST_POSITION.lock() {
+                *guard = Some(position.clone());
+            }
+            self.last_position = Some(position);
+        }
         self.selected_item = Some(item.clone());
     }
 
@@ -123,12 +449,219 @@ impl PaletteDelegate for TypeLibraryPalette {
 }
 
 impl TypeItem {
-    pub fn to_block(&self) -> TypeBlock {
+    /// Show `icon` for this item instead of the default, see `with_category_icons`
+    pub fn set_icon(&mut self, icon: IconName) {
         match self {
-            TypeItem::Primitive(name) => TypeBlock::primitive(name),
-            TypeItem::Constructor { name, params_count, .. } => {
-                TypeBlock::constructor(name, *params_count)
+            TypeItem::Primitive { icon: slot, .. }
+            | TypeItem::Constructor { icon: slot, .. }
+            | TypeItem::Combo { icon: slot, .. } => *slot = Some(icon),
+        }
+    }
+
+    /// Block for the item, with smart defaults filled in if they are on
+    pub fn to_block(&self, cx: &App) -> TypeBlock {
+        match self {
+            TypeItem::Primitive { name, .. } => TypeBlock::primitive(name),
+            TypeItem::Constructor { name, params_count, const_params, lifetimes, .. } => {
+                let mut block = TypeBlock::constructor(name, *params_count);
+                if !const_params.is_empty() {
+                    block.set_const_params(const_params);
+                }
+                block.set_lifetime_count(*lifetimes);
+                if EditorSettings::global(cx).smart_defaults {
+                    // Ordinary blocks, so the user can clear or replace them like any other
+                    for (_, slot, default) in SMART_DEFAULT_SLOTS.iter().filter(|(n, _, _)| n == name) {
+                        block.set_slot(*slot, TypeBlock::primitive(*default));
+                    }
+                }
+                block
             }
+            TypeItem::Combo { build, .. } => build(),
+        }
+    }
+
+    /// Whether a slot with this constraint accepts the item
+    pub fn fits(&self, constraint: &SlotConstraint) -> bool {
+        match self {
+            TypeItem::Primitive { name, .. } => constraint.allows_primitive(name),
+            TypeItem::Constructor { name, .. } => constraint.allows_constructor(name),
+            TypeItem::Combo { build, .. } => match build() {
+                TypeBlock::Constructor { name, .. } => constraint.allows_constructor(&name),
+                _ => false,
+            },
+        }
+    }
+
+    /// Link to the item's documentation, for primitives and std constructors
+    pub fn documentation_url(&self) -> Option<String> {
+        match self {
+            TypeItem::Primitive { name, .. } | TypeItem::Constructor { name, .. } => documentation_url(name),
+            TypeItem::Combo { .. } => None,
+        }
+    }
+
+    /// Curated multi-block structures offered in the "Combos" category
+    pub fn combos() -> Vec<TypeItem> {
+        vec![
+            TypeItem::Combo {
+                name: "async fn".to_string(),
+                description: "fn(..) -> Pin<Box<dyn Future<Output = T>>>".to_string(),
+                build: async_fn_block,
+                icon: None,
+            },
+            TypeItem::Combo {
+                name: "PhantomData<T>".to_string(),
+                description: "Covariant marker that also owns T for drop checking".to_string(),
+                build: phantom_block,
+                icon: None,
+            },
+            TypeItem::Combo {
+                name: "PhantomData<fn() -> T>".to_string(),
+                description: "Covariant marker without owning T; stays Send + Sync whatever T is".to_string(),
+                build: phantom_return_block,
+                icon: None,
+            },
+            TypeItem::Combo {
+                name: "PhantomData<fn(T)>".to_string(),
+                description: "Contravariant marker in T".to_string(),
+                build: phantom_arg_block,
+                icon: None,
+            },
+            TypeItem::Combo {
+                name: "PhantomData<fn(T) -> T>".to_string(),
+                description: "Invariant marker in the generic param T; rename T if yours is called differently".to_string(),
+                build: phantom_invariant_block,
+                icon: None,
+            },
+        ]
+    }
+}
+
+/// `fn(P) -> Pin<Box<dyn Future<Output = T>>>` with the param and output slots left empty
+fn async_fn_block() -> TypeBlock {
+    let future = TypeBlock::constructor("dyn Future", 1);
+    let mut boxed = TypeBlock::constructor("Box", 1);
+    let mut pinned = TypeBlock::constructor("Pin", 1);
+    let mut fn_ptr = TypeBlock::fn_pointer(1);
+
+    boxed.set_slot(0, future);
+    pinned.set_slot(0, boxed);
+    fn_ptr.set_slot(1, pinned);
+    fn_ptr
+}
+
+/// `PhantomData<T>` with the marker slot left empty
+fn phantom_block() -> TypeBlock {
+    TypeBlock::constructor("PhantomData", 1)
+}
+
+/// `PhantomData<fn() -> T>`; T goes in the return slot
+fn phantom_return_block() -> TypeBlock {
+    let mut phantom = phantom_block();
+    phantom.set_slot(0, TypeBlock::fn_pointer(0));
+    phantom
+}
+
+/// `PhantomData<fn(T)>`; T goes in the argument slot and the return slot is `()`
+fn phantom_arg_block() -> TypeBlock {
+    let mut fn_ptr = TypeBlock::fn_pointer(1);
+    fn_ptr.set_slot(1, TypeBlock::primitive("()"));
+    let mut phantom = phantom_block();
+    phantom.set_slot(0, fn_ptr);
+    phantom
+}
+
+/// `PhantomData<fn(T) -> T>` with the generic param `T` already in the argument and
+/// return slots, since invariance needs the same type in both
+fn phantom_invariant_block() -> TypeBlock {
+    let mut fn_ptr = TypeBlock::fn_pointer(1);
+    fn_ptr.set_slot(0, TypeBlock::primitive("T"));
+    fn_ptr.set_slot(1, TypeBlock::primitive("T"));
+    let mut phantom = phantom_block();
+    phantom.set_slot(0, fn_ptr);
+    phantom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen;
+    use ui_types_common::TypeAstNode;
+
+    fn combo(name: &str) -> TypeBlock {
+        match TypeItem::combos().into_iter().find(|item| item.name() == name) {
+            Some(TypeItem::Combo { build, .. }) => build(),
+            _ => panic!("no combo named {}", name),
         }
     }
+
+    /// Fill every empty slot with `u8` so the tree can be generated
+    fn fill_holes(ast: &TypeAstNode) -> TypeAstNode {
+        match ast {
+            TypeAstNode::None => TypeAstNode::Primitive { name: "u8".to_string() },
+            TypeAstNode::Constructor { name, params, lifetimes, const_generics } => TypeAstNode::Constructor {
+                name: name.clone(),
+                params: params.iter().map(fill_holes).collect(),
+                lifetimes: lifetimes.clone(),
+                const_generics: const_generics.clone(),
+            },
+            TypeAstNode::Tuple { elements } => TypeAstNode::Tuple { elements: elements.iter().map(fill_holes).collect() },
+            TypeAstNode::FnPointer { params, return_type } => TypeAstNode::FnPointer {
+                params: params.iter().map(fill_holes).collect(),
+                return_type: Box::new(fill_holes(return_type)),
+            },
+            other => other.clone(),
+        }
+    }
+
+    #[test]
+    fn combos_round_trip_through_the_ast() {
+        for item in TypeItem::combos() {
+            let TypeItem::Combo { name, build, .. } = item else {
+                continue;
+            };
+            let ast = build().to_ast_with_holes();
+            let rebuilt = TypeBlock::from_ast(&ast).to_ast_with_holes();
+            assert!(type_ast::structural_eq(&ast, &rebuilt), "{} changed shape", name);
+        }
+    }
+
+    #[test]
+    fn combos_generate_valid_rust() {
+        for item in TypeItem::combos() {
+            let TypeItem::Combo { name, build, .. } = item else {
+                continue;
+            };
+            let code = codegen::ast_to_rust_string(&fill_holes(&build().to_ast_with_holes()));
+            assert!(syn::parse_str::<syn::Type>(&code).is_ok(), "{} generated `{}`", name, code);
+        }
+    }
+
+    #[test]
+    fn phantom_markers_have_the_right_structure() {
+        // Empty slots print as `_`
+        let generate = |name: &str| codegen::ast_to_rust_string(&combo(name).to_ast_with_holes());
+        assert_eq!(generate("PhantomData<T>"), "PhantomData<_>");
+        assert_eq!(generate("PhantomData<fn() -> T>"), "PhantomData<fn() -> _>");
+        assert_eq!(generate("PhantomData<fn(T)>"), "PhantomData<fn(_) -> ()>");
+        assert_eq!(
+            combo("PhantomData<fn(T) -> T>").to_ast().map(|ast| codegen::ast_to_rust_string(&ast)).as_deref(),
+            Some("PhantomData<fn(T) -> T>")
+        );
+    }
+
+    #[gpui::test]
+    fn literal_search_lists_fitting_primitives_first(cx: &mut gpui::TestAppContext) {
+        let mut palette = cx.update(|cx| TypeLibraryPalette::new(None, cx));
+        palette.query_changed("300");
+        let (category, items) = &palette.categories()[0];
+        assert_eq!(category, LITERAL_CATEGORY);
+        let names: Vec<&str> = items.iter().map(|item| item.name()).collect();
+        assert_eq!(names.first(), Some(&"u16"));
+        assert!(!names.contains(&"u8"));
+        assert!(items.iter().all(|item| item.keywords() == vec!["300"]));
+
+        palette.query_changed("Vec");
+        assert!(palette.categories().iter().all(|(name, _)| name != LITERAL_CATEGORY));
+    }
 }
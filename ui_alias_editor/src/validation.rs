@@ -0,0 +1,473 @@
+// Validation passes shared by save, the Validate button and headless tooling
+
+use ui_types_common::{AliasAsset, TypeAstNode};
+use crate::codegen;
+use crate::placement;
+use crate::project::{self, ProjectTypeConfig};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::type_ast;
+use crate::type_block::TypeBlock;
+
+/// How serious a diagnostic is. Errors block saving, warnings do not; info reports the
+/// outcome of an operation that went fine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A single finding from validation or an editor operation
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorDiagnostic {
+    /// The canvas has no type yet
+    EmptyType,
+    /// Required slots are still empty
+    EmptySlots,
+    /// The alias refers to itself directly
+    SelfReference(String),
+    /// An alias reference that no file in the project defines
+    UnknownAlias(String),
+    /// Generated code did not parse
+    ParseError(String),
+    /// References that loop back through other aliases, first name repeated at the end
+    CycleDetected(Vec<String>),
+    /// A type the project config does not allow
+    TypeNotAllowed(String),
+    /// The alias name shadows a std or project name
+    ShadowsName { name: String, shadowed: String },
+    /// The file was upgraded to this schema version on load
+    SchemaUpgraded(u32),
+    /// `Self` is used but the alias is not declared in a trait or impl
+    SelfOutsideImpl,
+    /// Constructors nest deeper than the project allows; the names along the deepest path
+    NestedTooDeep { limit: usize, path: Vec<String> },
+    /// A param holds a type its constructor does not accept
+    SlotConstraintViolated { constructor: String, slot: usize, found: String, expected: String },
+    /// A type where it can never work, from `placement::PLACEMENT_RULES`; `parent` is unset at the root
+    MisplacedType { child: String, parent: Option<String>, reason: String },
+    /// Foreign primitive names were rewritten: (from, to) pairs
+    PrimitivesNormalized(Vec<(String, String)>),
+    /// A finding for another file, from project-wide validation
+    InFile(PathBuf, Box<EditorDiagnostic>),
+    /// Anything without its own variant: compiler output, I/O failures
+    Message { severity: Severity, message: String },
+}
+
+impl EditorDiagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::Message { severity: Severity::Error, message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::Message { severity: Severity::Warning, message: message.into() }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::Message { severity: Severity::Info, message: message.into() }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::TypeNotAllowed(_)
+            | Self::ShadowsName { .. }
+            | Self::SchemaUpgraded(_)
+            | Self::SelfOutsideImpl
+            | Self::NestedTooDeep { .. }
+            | Self::MisplacedType { .. }
+            | Self::PrimitivesNormalized(_) => Severity::Warning,
+            Self::InFile(_, inner) => inner.severity(),
+            Self::Message { severity, .. } => *severity,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity() == Severity::Warning
+    }
+
+    pub fn is_info(&self) -> bool {
+        self.severity() == Severity::Info
+    }
+}
+
+impl fmt::Display for EditorDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyType => write!(f, "Cannot save empty type"),
+            Self::EmptySlots => write!(f, "Type has empty slots - fill all slots before saving"),
+            Self::SelfReference(name) => write!(f, "{} refers to itself", name),
+            Self::UnknownAlias(alias) => write!(f, "Unknown alias `{}`", alias),
+            Self::ParseError(e) => write!(f, "{}", e),
+            Self::CycleDetected(cycle) => write!(f, "Reference cycle: {}", cycle.join(" -> ")),
+            Self::TypeNotAllowed(name) => write!(f, "`{}` is not allowed in this project", name),
+            Self::ShadowsName { name, shadowed } => {
+                write!(f, "\"{}\" shadows `{}` and may break code that uses it", name, shadowed)
+            }
+            Self::SchemaUpgraded(version) => {
+                write!(f, "Upgraded to schema version {} - save to keep the upgrade", version)
+            }
+            Self::SelfOutsideImpl => {
+                write!(f, "`Self` only means something in an alias declared inside a trait or impl")
+            }
+            Self::NestedTooDeep { limit, path } => write!(
+                f,
+                "Generics nest {} deep, over the limit of {}: {}",
+                path.len(),
+                limit,
+                path.join(" › ")
+            ),
+            Self::SlotConstraintViolated { constructor, slot, found, expected } => write!(
+                f,
+                "`{}` cannot be param {} of `{}`, which takes {}",
+                found,
+                slot + 1,
+                constructor,
+                expected
+            ),
+            Self::MisplacedType { child, parent: Some(parent), reason } => {
+                write!(f, "`{}` inside `{}`: {}", child, parent, reason)
+            }
+            Self::MisplacedType { child, parent: None, reason } => write!(f, "`{}` as the whole type: {}", child, reason),
+            Self::PrimitivesNormalized(renamed) => write!(
+                f,
+                "Normalized primitive names: {} - save to keep them",
+                renamed
+                    .iter()
+                    .map(|(from, to)| format!("`{}` → `{}`", from, to))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::InFile(path, inner) => write!(f, "{}: {}", path.display(), inner),
+            Self::Message { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Run every validation pass over the canvas tree.
+///
+/// `alias_name` is the type name of the alias being edited, used for cycle detection.
+/// `known_aliases` lists the aliases that exist in the project; `None` skips the
+/// unknown-reference check (e.g. when the file is not inside a project).
+pub fn validate_tree(
+    root: Option<&TypeBlock>,
+    alias_name: &str,
+    known_aliases: Option<&[String]>,
+) -> Vec<EditorDiagnostic> {
+    let Some(root) = root else {
+        return vec![EditorDiagnostic::EmptyType];
+    };
+    let Some(ast) = root.to_ast() else {
+        return vec![EditorDiagnostic::EmptySlots];
+    };
+
+    validate_ast(&ast, alias_name, known_aliases)
+}
+
+/// Every validation pass over one alias, shared by the editor and `validate_project` so
+/// the banner and the project report agree. An empty slot is `TypeAstNode::None` and a
+/// `None` root means there is no type yet. `known_aliases` is as for `validate_tree`.
+/// Cycles through other aliases need the whole project, so callers check those.
+pub fn validate_asset(
+    asset: &AliasAsset,
+    config: &ProjectTypeConfig,
+    known_aliases: Option<&[String]>,
+) -> Vec<EditorDiagnostic> {
+    let ast = &asset.ast;
+    let options = codegen::ExportOptions::from_meta(&asset.meta);
+    let mut diagnostics = Vec::new();
+
+    if matches!(ast, TypeAstNode::None) {
+        diagnostics.push(EditorDiagnostic::EmptyType);
+    } else {
+        diagnostics.extend(validate_ast(ast, &asset.display_name, known_aliases));
+        let root = TypeBlock::from_ast(ast);
+        diagnostics.extend(check_placements(&root));
+        if let Some(limit) = config.max_nesting_depth {
+            let path = root.deepest_constructor_chain();
+            if path.len() > limit {
+                diagnostics.push(EditorDiagnostic::NestedTooDeep {
+                    limit,
+                    path: path.into_iter().map(TypeBlock::display_name).collect(),
+                });
+            }
+        }
+        if codegen::is_complete(ast) {
+            diagnostics.extend(check_type_filter(ast, config));
+            diagnostics.extend(check_self_type(ast, &asset.meta));
+            diagnostics.extend(check_slot_constraints(ast, config));
+            diagnostics.extend(check_conversions(ast, &options));
+        }
+    }
+
+    if let Err(e) = options.visibility.validate() {
+        diagnostics.push(EditorDiagnostic::error(e));
+    }
+    diagnostics.extend(check_generic_bounds(&asset.meta, options.mode));
+    diagnostics.extend(check_cfg(&asset.meta));
+    if let Some(e) = config.alias_template.as_deref().and_then(|t| codegen::validate_template(t).err()) {
+        diagnostics.push(EditorDiagnostic::warning(format!(
+            "Alias template in alias_editor.json: {}; using the built-in layout",
+            e
+        )));
+    }
+    diagnostics
+}
+
+/// Validation passes that only need the AST
+pub fn validate_ast(
+    ast: &TypeAstNode,
+    alias_name: &str,
+    known_aliases: Option<&[String]>,
+) -> Vec<EditorDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if !codegen::is_complete(ast) {
+        diagnostics.push(EditorDiagnostic::EmptySlots);
+    }
+
+    let mut refs = Vec::new();
+    codegen::collect_alias_refs(ast, &mut refs);
+
+    if !alias_name.is_empty() && refs.iter().any(|r| r == alias_name) {
+        diagnostics.push(EditorDiagnostic::SelfReference(alias_name.to_string()));
+    }
+
+    if let Some(known) = known_aliases {
+        for alias in refs.iter().filter(|r| r.as_str() != alias_name) {
+            if !known.contains(alias) {
+                diagnostics.push(EditorDiagnostic::UnknownAlias(alias.clone()));
+            }
+        }
+    }
+
+    let code = codegen::ast_to_rust_string(ast);
+    if let Err(e) = syn::parse_str::<syn::Type>(&code) {
+        diagnostics.push(EditorDiagnostic::ParseError(format!("Generated type does not parse: {}", e)));
+    }
+
+    diagnostics
+}
+
+/// Warn about blocks placed where they can never work, see `placement::PLACEMENT_RULES`
+pub fn check_placements(root: &TypeBlock) -> Vec<EditorDiagnostic> {
+    placement::check_placements(root)
+        .into_iter()
+        .map(|finding| EditorDiagnostic::MisplacedType {
+            child: finding.child,
+            parent: finding.parent,
+            reason: finding.rule.reason.to_string(),
+        })
+        .collect()
+}
+
+/// Warn about types the project config does not allow, e.g. from an imported file
+pub fn check_type_filter(ast: &TypeAstNode, config: &ProjectTypeConfig) -> Vec<EditorDiagnostic> {
+    let mut names = Vec::new();
+    type_ast::collect_type_names(ast, &mut names);
+
+    names
+        .into_iter()
+        .filter(|name| name != type_ast::SELF_TYPE && !config.is_type_allowed(name))
+        .map(EditorDiagnostic::TypeNotAllowed)
+        .collect()
+}
+
+/// Flag params that hold a type their constructor's slot constraint does not accept
+pub fn check_slot_constraints(ast: &TypeAstNode, config: &ProjectTypeConfig) -> Vec<EditorDiagnostic> {
+    let mut diagnostics = Vec::new();
+    collect_constraint_violations(ast, config, &mut diagnostics);
+    diagnostics
+}
+
+fn collect_constraint_violations(ast: &TypeAstNode, config: &ProjectTypeConfig, out: &mut Vec<EditorDiagnostic>) {
+    let children: Vec<&TypeAstNode> = match ast {
+        TypeAstNode::Constructor { name, params, .. } => {
+            for (slot, param) in params.iter().enumerate() {
+                if let Some(constraint) = config.slot_constraint(name, slot).filter(|c| !c.allows(param)) {
+                    out.push(EditorDiagnostic::SlotConstraintViolated {
+                        constructor: name.clone(),
+                        slot,
+                        found: codegen::ast_to_rust_string(param),
+                        expected: constraint.describe(),
+                    });
+                }
+            }
+            params.iter().collect()
+        }
+        TypeAstNode::Tuple { elements } => elements.iter().collect(),
+        TypeAstNode::FnPointer { params, return_type } => {
+            params.iter().chain(std::iter::once(return_type.as_ref())).collect()
+        }
+        _ => Vec::new(),
+    };
+    for child in children {
+        collect_constraint_violations(child, config, out);
+    }
+}
+
+/// Warn about `Self` in an alias whose meta does not place it in a trait or impl
+pub fn check_self_type(ast: &TypeAstNode, meta: &serde_json::Value) -> Vec<EditorDiagnostic> {
+    if type_ast::uses_self_type(ast) && !codegen::has_self_context(meta) {
+        vec![EditorDiagnostic::SelfOutsideImpl]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Check the generic bounds in the meta: each must name a declared param and parse as
+/// Rust bounds. Aliases can't enforce bounds, so they only take effect on struct exports.
+pub fn check_generic_bounds(meta: &serde_json::Value, mode: codegen::ExportMode) -> Vec<EditorDiagnostic> {
+    let bounds = codegen::generic_bounds(meta);
+    let declared = codegen::declared_generics(meta);
+    let mut diagnostics = Vec::new();
+    for (param, param_bounds) in &bounds {
+        if !declared.contains(param) {
+            diagnostics.push(EditorDiagnostic::error(format!("Bounds given for undeclared generic param `{}`", param)));
+            continue;
+        }
+        let predicate = format!("{}: {}", param, param_bounds.join(" + "));
+        if syn::parse_str::<syn::WherePredicate>(&predicate).is_err() {
+            diagnostics.push(EditorDiagnostic::error(format!("`{}` is not a valid bound", predicate)));
+        }
+    }
+    if !bounds.is_empty() && mode == codegen::ExportMode::Alias {
+        diagnostics.push(EditorDiagnostic::warning(
+            "Type aliases ignore generic bounds; export as a struct to emit a where clause",
+        ));
+    }
+    diagnostics
+}
+
+/// Check the `cfg` predicate in the meta parses, see `codegen::format_cfg_predicate`
+pub fn check_cfg(meta: &serde_json::Value) -> Vec<EditorDiagnostic> {
+    codegen::cfg_predicate(meta)
+        .and_then(|predicate| codegen::format_cfg_predicate(&predicate).err())
+        .map(|e| EditorDiagnostic::error(format!("Invalid cfg: {}", e)))
+        .into_iter()
+        .collect()
+}
+
+/// `From` conversions are only generated for struct exports with a single field
+pub fn check_conversions(ast: &TypeAstNode, options: &codegen::ExportOptions) -> Vec<EditorDiagnostic> {
+    if !options.conversions {
+        return Vec::new();
+    }
+    if options.mode != codegen::ExportMode::Struct {
+        return vec![EditorDiagnostic::warning("From conversions are only generated for struct exports")];
+    }
+    match codegen::struct_fields(ast).len() {
+        1 => Vec::new(),
+        count => vec![EditorDiagnostic::error(format!(
+            "From conversions need a newtype with exactly one field; this struct has {}",
+            count
+        ))],
+    }
+}
+
+/// Validate every alias file in a project, for release checks and CI.
+///
+/// Runs the same passes as the editor, `validate_asset`, on each file, resolving
+/// references against the whole project, and adds reference cycles that span several files.
+/// Every file is listed, with an empty list when it is clean.
+pub fn validate_project(root: &Path) -> Vec<(PathBuf, Vec<EditorDiagnostic>)> {
+    let config = ProjectTypeConfig::load(root);
+    let loaded: Vec<_> = project::find_alias_files(root)
+        .into_iter()
+        .map(|path| {
+            let asset = project::load_alias_asset(&path);
+            (path, asset)
+        })
+        .collect();
+
+    let known: Vec<String> = loaded
+        .iter()
+        .filter_map(|(_, asset)| asset.as_ref().ok().map(|a| a.display_name.clone()))
+        .collect();
+    let references: HashMap<&str, Vec<String>> = loaded
+        .iter()
+        .filter_map(|(_, asset)| asset.as_ref().ok())
+        .map(|asset| {
+            let mut refs = Vec::new();
+            codegen::collect_alias_refs(&asset.ast, &mut refs);
+            (asset.display_name.as_str(), refs)
+        })
+        .collect();
+
+    loaded
+        .iter()
+        .map(|(path, asset)| {
+            let diagnostics = match asset {
+                Ok(asset) => {
+                    let mut diagnostics = validate_asset(asset, &config, Some(known.as_slice()));
+                    if let Some(cycle) = find_cycle(&asset.display_name, &references) {
+                        diagnostics.push(EditorDiagnostic::CycleDetected(cycle));
+                    }
+                    diagnostics
+                }
+                Err(e) => vec![EditorDiagnostic::error(e.clone())],
+            };
+            (path.clone(), diagnostics)
+        })
+        .collect()
+}
+
+/// Check whether `ast`, the tree of `alias_name`, closes a reference cycle through the
+/// other aliases in `project`. A saved copy of `alias_name` in `project` is ignored.
+pub fn check_cycles(ast: &TypeAstNode, alias_name: &str, project: &[AliasAsset]) -> Option<EditorDiagnostic> {
+    let mut references: HashMap<&str, Vec<String>> = project
+        .iter()
+        .map(|asset| {
+            let mut refs = Vec::new();
+            codegen::collect_alias_refs(&asset.ast, &mut refs);
+            (asset.display_name.as_str(), refs)
+        })
+        .collect();
+    let mut refs = Vec::new();
+    codegen::collect_alias_refs(ast, &mut refs);
+    references.insert(alias_name, refs);
+    find_cycle(alias_name, &references).map(EditorDiagnostic::CycleDetected)
+}
+
+/// Find a path of references from `start` back to itself through at least one other
+/// alias. Direct self-references are reported by `validate_ast` instead.
+fn find_cycle(start: &str, references: &HashMap<&str, Vec<String>>) -> Option<Vec<String>> {
+    let mut path = vec![start.to_string()];
+    let mut visited = Vec::new();
+    if visit(start, start, references, &mut path, &mut visited) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+fn visit(
+    current: &str,
+    start: &str,
+    references: &HashMap<&str, Vec<String>>,
+    path: &mut Vec<String>,
+    visited: &mut Vec<String>,
+) -> bool {
+    for next in references.get(current).into_iter().flatten() {
+        if next == start && path.len() > 1 {
+            path.push(next.clone());
+            return true;
+        }
+        if next == start || visited.contains(next) {
+            continue;
+        }
+        visited.push(next.clone());
+        path.push(next.clone());
+        if visit(next, start, references, path, visited) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
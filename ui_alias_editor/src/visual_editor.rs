@@ -5,18 +5,255 @@ use ui::{
     button::{Button, ButtonVariant, ButtonVariants}, 
     divider::Divider,
     resizable::{h_resizable, resizable_panel, ResizableState},
-    input::{InputState, TextInput},
+    input::{InputEvent, InputState, TextInput},
+    checkbox::Checkbox,
 };
+use ui_common::command_palette::PaletteItem;
 use ui_types_common::{AliasAsset, TypeAstNode};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use crate::{TypeBlock, BlockId, BlockCanvas, ConstructorPalette};
+use crate::block_canvas::{CanvasHandlers, EmptyStateChoice, EmptyStateHandler, SlotPathSegment};
+use crate::examples;
+use crate::type_block::{AliasHandler, DraggedBlock, BlockHandler, BlockLayout, ConstructorHandler, FocusTarget, LayoutDirection, SlotHandler};
+use crate::codegen::{self, ExportMode, ExportOptions, Visibility};
+use crate::compile_check;
+use crate::dsl;
+use crate::explain;
+use crate::file_provider::{FileProvider, LocalFileProvider};
+use crate::import;
+use crate::placement;
+use crate::project;
+use crate::recent;
+use crate::repair::{self, Repair};
+use crate::schema;
+use crate::settings::{self, EditorSetting, EditorSettings};
+use crate::share;
+use crate::svg_export;
+use crate::type_ast;
+use crate::validation::{self, EditorDiagnostic, Severity};
 
-actions!(visual_alias_editor, [Save, TogglePalette]);
+actions!(visual_alias_editor, [Save, SaveAs, Undo, Validate, ValidateProject, TogglePalette, TogglePreview, DuplicateAlias, FindReplace, FlattenTuples, ToggleHighContrast, RevealInFolder, CancelPlacement, ToggleSmartDefaults, ToggleLayoutDirection, ImportRustFile, CyclePrimitive, SaveAll, ToggleSizes, ExportSvg, PasteType, RepeatLastType, TogglePickerOnClick, ToggleGroupByCrate, RegenerateAll, FindUsages, DeleteAlias, ImportAstJson, FocusNext, FocusPrevious, ActivateFocused, CopyShareLink, PasteShareLink, RevertToSaved, FindInPreview, FindNextInPreview, FindPreviousInPreview, QuickSwitch, ShowDependencies, BuildCallback, CopyDsl, PasteDsl, ToggleSettings]);
+
+/// Most arguments the callback wizard offers; more can be inserted on the block afterwards
+const MAX_CALLBACK_ARGUMENTS: usize = 8;
+
+/// Smallest subtree, in nodes, worth offering to extract on save
+const DEDUPE_MIN_NODES: usize = 4;
+
+/// Key in `AliasAsset.meta` holding the canvas layout direction the file pins, if any
+const LAYOUT_META_KEY: &str = "layoutDirection";
+
+/// Key in `AliasAsset.meta` marking a canonical alias that should open read-only
+pub const LOCK_META_KEY: &str = "locked";
+
+/// Whether an alias file should open read-only: the file is not writable, or its meta
+/// carries the lock marker
+pub fn is_locked(file_path: &Path, meta: &serde_json::Value) -> bool {
+    is_locked_in(&LocalFileProvider, file_path, meta)
+}
+
+/// `is_locked` for a file behind `files`
+fn is_locked_in(files: &dyn FileProvider, file_path: &Path, meta: &serde_json::Value) -> bool {
+    let marked = meta.get(LOCK_META_KEY).and_then(serde_json::Value::as_bool).unwrap_or(false);
+    marked || files.is_read_only(file_path)
+}
+
+/// Key context for bindings that should only fire while the alias editor is focused.
+/// Every binding is scoped to it so shortcuts like `secondary-s` don't collide with other panels.
+pub const KEY_CONTEXT: &str = "VisualAliasEditor";
+
+/// Quiet period after an edit before the preview is regenerated, so bursts of edits
+/// regenerate once
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Register the alias editor key bindings
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("secondary-s", Save, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-shift-s", SaveAs, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-alt-s", SaveAll, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-z", Undo, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-shift-v", Validate, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-alt-shift-v", ValidateProject, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-k", TogglePalette, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-shift-d", DuplicateAlias, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-p", TogglePreview, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-h", FindReplace, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-f", FindInPreview, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-e", QuickSwitch, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-g", FindNextInPreview, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-shift-g", FindPreviousInPreview, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-alt-r", RevealInFolder, Some(KEY_CONTEXT)),
+        KeyBinding::new("escape", CancelPlacement, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-.", CyclePrimitive, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-v", PasteType, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-r", RepeatLastType, Some(KEY_CONTEXT)),
+        KeyBinding::new("tab", FocusNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-tab", FocusPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", ActivateFocused, Some(KEY_CONTEXT)),
+    ]);
+}
+
+/// Prelude and commonly imported std names that an alias should not shadow
+const STD_TYPE_NAMES: &[&str] = &[
+    "Option", "Some", "None", "Result", "Ok", "Err", "Vec", "String", "Box",
+    "Rc", "Arc", "Weak", "Cell", "RefCell", "Mutex", "RwLock", "Cow",
+    "HashMap", "HashSet", "BTreeMap", "BTreeSet", "VecDeque", "BinaryHeap",
+    "Path", "PathBuf", "Pin", "PhantomData", "Duration", "Instant",
+    "Clone", "Copy", "Default", "Drop", "Eq", "PartialEq", "Ord", "PartialOrd",
+    "Fn", "FnMut", "FnOnce", "From", "Into", "Iterator", "IntoIterator",
+    "Send", "Sync", "Sized", "ToString", "ToOwned", "AsRef", "AsMut", "Self",
+];
 
 #[derive(Clone)]
 pub struct ShowTypePickerRequest {
     pub target_slot: Option<(BlockId, usize)>,
+    /// Every slot the confirmed type should fill: the target slot plus any shift-clicked
+    /// empty slots. Pass it to `TypeLibraryPalette::with_target_slots`.
+    pub target_slots: Vec<(BlockId, usize)>,
+    /// Slots already filled in this guided fill, outermost first. Empty unless the
+    /// picker was reopened automatically to continue filling.
+    pub slot_chain: Vec<(BlockId, usize)>,
+    /// What the target slot accepts; pass it to `TypeLibraryPalette::with_slot_constraint`
+    pub slot_constraint: Option<project::SlotConstraint>,
+    /// The editor's project config: allow/deny lists, project constructors and category
+    /// icons. `TypeLibraryPalette::for_request` builds the palette from it.
+    pub type_config: project::ProjectTypeConfig,
+}
+
+/// Asks the host to save every open alias editor, e.g. with `save_all`
+#[derive(Clone, Debug)]
+pub struct SaveAllRequest;
+
+/// Combined result of saving several editors
+#[derive(Clone, Debug, Default)]
+pub struct SaveAllReport {
+    pub saved: Vec<PathBuf>,
+    /// Display name of each editor that failed, with why
+    pub failed: Vec<(String, Vec<EditorDiagnostic>)>,
+}
+
+impl SaveAllReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Save every dirty editor in `editors` without prompting. Clean editors are skipped.
+pub fn save_all(editors: &[Entity<VisualAliasEditor>], cx: &mut App) -> SaveAllReport {
+    let mut report = SaveAllReport::default();
+    for editor in editors {
+        editor.update(cx, |editor, cx| {
+            if !editor.is_dirty() {
+                return;
+            }
+            match editor.save_now(cx) {
+                Ok(path) => report.saved.push(path),
+                Err(diagnostics) => report.failed.push((editor.display_name.clone(), diagnostics)),
+            }
+        });
+    }
+    report
+}
+
+/// Asks the host to open an alias file in a new editor panel
+#[derive(Clone, Debug)]
+pub struct OpenAliasRequest {
+    pub path: PathBuf,
+}
+
+/// Asks the host to show the recent files switcher, a `RecentAliasPalette` built with `current`
+#[derive(Clone, Debug)]
+pub struct ShowRecentFilesRequest {
+    pub current: Option<PathBuf>,
+}
+
+/// Emitted when the tree becomes valid or invalid, or gains or loses empty slots, so a host
+/// can enable its own save button without polling `is_valid` and `is_complete`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidityChanged {
+    pub valid: bool,
+    pub complete: bool,
+}
+
+/// Asks the host to open a comparison of two alias files, see `AliasCompareView`
+#[derive(Clone, Debug)]
+pub struct CompareAliasRequest {
+    pub left: PathBuf,
+    pub right: PathBuf,
+}
+
+/// Notifies the host that alias files changed on disk so it can update the type index
+#[derive(Clone, Debug)]
+pub enum AliasFileEvent {
+    Created(PathBuf),
+    Saved(PathBuf),
+    Deleted(PathBuf),
+}
+
+/// A Save As location in use while its save is in progress
+struct SaveTarget {
+    path: PathBuf,
+    /// Where the editor pointed before, restored if the save fails or is cancelled
+    previous: Option<PathBuf>,
+    was_dirty: bool,
+    is_new: bool,
+}
+
+/// Aliases read from a `.rs` file, waiting for the user to pick which to import
+struct RustImport {
+    source: PathBuf,
+    result: import::RustFileImport,
+    /// Parallel to `result.aliases`
+    selected: Vec<bool>,
+}
+
+/// Steps of the guided callback builder
+enum CallbackWizard {
+    /// Choosing how many arguments the callback takes
+    Arguments(usize),
+    /// Picking a type for each slot of the fn pointer in turn, the return type last
+    Slots { fn_id: BlockId, arguments: usize, remaining: Vec<usize> },
+    /// Optionally wrapping the finished fn pointer in `Box<dyn Fn(..) -> R>`
+    Wrap { fn_id: BlockId },
+}
+
+/// Repeated subtree proposed for extraction into a helper alias before saving
+struct DedupeProposal {
+    subtree: TypeAstNode,
+    /// Occurrences in the current tree
+    count: usize,
+    name_input: Entity<InputState>,
+}
+
+/// Find bar over the code preview
+struct PreviewSearch {
+    input: Entity<InputState>,
+    /// Index of the highlighted match
+    current: usize,
+}
+
+/// ASCII case-insensitive matches of `query` in `text`: (line index, byte range in the line).
+/// ASCII folding keeps byte offsets, so the ranges index the original lines.
+fn preview_matches(text: &str, query: &str) -> Vec<(usize, std::ops::Range<usize>)> {
+    let query = query.to_ascii_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let lower = line.to_ascii_lowercase();
+        let mut start = 0;
+        while let Some(offset) = lower[start..].find(&query) {
+            let begin = start + offset;
+            matches.push((line_index, begin..begin + query.len()));
+            start = begin + query.len();
+        }
+    }
+    matches
 }
 
 /// Visual block-based type alias editor with Scratch-style interface
@@ -26,54 +263,229 @@ pub struct VisualAliasEditor {
     display_name: String,
     description: String,
     
+    /// Asset metadata, preserved across load/save
+    meta: serde_json::Value,
+    
+    /// Options for the generated `.rs` file
+    export_options: ExportOptions,
+    
+    /// Serde `rename` for struct exports
+    serde_rename_input: Entity<InputState>,
+    
     /// Canvas for composing type blocks
     canvas: BlockCanvas,
     
     /// Code preview input state
     preview_input: Entity<InputState>,
     
+    /// Code of the alias as last saved, shown beside the live preview while comparing
+    saved_preview_input: Option<Entity<InputState>>,
+    
+    /// Generated file awaiting review before save writes it: (path, contents)
+    pending_codegen: Option<(PathBuf, String)>,
+    
+    /// Save As location, kept while a prompt holds its save
+    pending_save_target: Option<SaveTarget>,
+    
+    /// Code view of the generated file under review
+    codegen_review_input: Entity<InputState>,
+    
     /// Resizable state for canvas/preview split
     horizontal_resizable_state: Entity<ResizableState>,
     
-    /// Flag to update preview on next render
+    /// The preview no longer matches the blocks; cleared by `update_preview`
     preview_needs_update: bool,
     
-    /// Error message to display
-    error_message: Option<String>,
+    /// Pending debounced preview regeneration
+    preview_task: Option<Task<()>>,
+    
+    /// Worst severity from `validate()`, refreshed with the preview. Drives the save button style.
+    validity: Option<Severity>,
+    
+    /// State last sent as `ValidityChanged`; `None` until the first preview update
+    reported_validity: Option<ValidityChanged>,
+    
+    /// Errors and warnings shown in the banners above the canvas
+    diagnostics: Vec<EditorDiagnostic>,
+    
+    /// Unsaved changes (including schema upgrades applied on load)
+    dirty: bool,
+    
+    /// Viewing only: saving, slot filling and the picker are disabled
+    read_only: bool,
+    
+    /// Unknown top-level JSON fields from the loaded file, preserved on save
+    extra_fields: serde_json::Map<String, serde_json::Value>,
+    
+    /// Project-specific names that aliases should not use, in addition to the std names
+    reserved_names: Vec<String>,
+    
+    /// Name the user chose to save despite it shadowing a reserved name
+    shadow_override: Option<String>,
     
     /// Code preview panel visible
     show_preview: bool,
     
+    /// Plain-English explanation panel expanded
+    show_explanation: bool,
+    
+    /// JSON editor for `meta`, while the metadata panel is open
+    meta_input: Option<Entity<InputState>>,
+    
+    /// Why the metadata being edited is not a valid JSON object; blocks saving
+    meta_error: Option<String>,
+    
     focus_handle: FocusHandle,
     
     /// Currently selected slot to fill (parent_block_id, slot_index)
     selected_slot: Option<(BlockId, usize)>,
+
+    /// Empty slots picked with shift-click, including `selected_slot`; the next picker
+    /// confirmation fills them all
+    selected_slots: Vec<(BlockId, usize)>,
+    
+    /// Type most recently added from the picker, for repeat-last-type
+    last_picked: Option<crate::TypeItem>,
+    
+    /// After placing a type, select the next empty slot and reopen the picker
+    continue_filling: bool,
+    
+    /// Slots filled so far in the current guided fill
+    fill_chain: Vec<(BlockId, usize)>,
+    
+    /// Callback wizard bar, at its current step
+    callback_wizard: Option<CallbackWizard>,
     
     /// Block pending placement (from palette)
     pending_block: Option<TypeBlock>,
     
-    /// Pending slot selection (shared state for click handler)
-    pending_slot_selection: Arc<Mutex<Option<(BlockId, usize)>>>,
+    /// New root waiting for the user to confirm discarding the current tree
+    pending_root_replacement: Option<TypeBlock>,
+    
+    /// Name input shown while duplicating the alias
+    duplicate_name_input: Option<Entity<InputState>>,
+    
+    /// Warn on constructors with structurally identical params (`Result<T, T>`)
+    lint_duplicate_params: bool,
+    
+    /// Warn when constructors nest deeper than this, from the project config
+    max_nesting_depth: Option<usize>,
+    
+    /// Blocks whose lint warnings the user dismissed
+    dismissed_warnings: HashSet<BlockId>,
+    
+    /// Blocks tagged by a Rust import, with their subtree as imported
+    imported_blocks: HashMap<BlockId, TypeAstNode>,
+    
+    /// Aliases in the project, used to resolve alias references
+    alias_index: Option<project::AliasIndex>,
+    
+    /// Generic param declarations before each rename, keyed by the canvas snapshot
+    /// taken for it, so undo restores the declaration along with the tree
+    generics_undo: Vec<(u64, Vec<String>)>,
+    
+    /// Find and replace type inputs, shown while the replace bar is open
+    replace_inputs: Option<(Entity<InputState>, Entity<InputState>)>,
+    
+    /// Value field for the const slot being edited: (parent_id, slot_index, input)
+    const_arg_input: Option<(BlockId, usize, Entity<InputState>)>,
+    
+    /// Name field for the lifetime param being renamed: (block_id, lifetime_index, input)
+    lifetime_input: Option<(BlockId, usize, Entity<InputState>)>,
+    
+    /// Preview or result of the last find/replace
+    replace_status: Option<String>,
+    
+    /// Flatten-tuples bar, holding the one-level and all-levels results to preview
+    flatten_preview: Option<(String, String)>,
+    
+    /// Import bar listing the aliases found in a `.rs` file
+    rust_import: Option<RustImport>,
+    
+    /// Dependencies panel listing the aliases and external paths the tree uses
+    show_dependencies: bool,
+    
+    /// Settings panel for the file's overrides of the global toggles
+    show_settings: bool,
+    
+    /// Files referencing this alias, from the last "Find usages"
+    usages: Option<Vec<project::AliasUsage>>,
+    
+    /// Delete confirmation bar, with the aliases that would be left dangling
+    delete_prompt: Option<Vec<project::AliasUsage>>,
+    
+    /// Confirmation bar before reverting unsaved changes
+    revert_prompt: bool,
+    
+    /// Deduplicate bar shown by save, with the subtree it would extract
+    dedupe_prompt: Option<DedupeProposal>,
+    
+    /// Repeated subtrees the user chose to keep inline, so saving stops offering them
+    dedupe_declined: Vec<TypeAstNode>,
+    
+    /// Repairs found by validating the alias when it was opened, see `EditorSetting::ValidateOnLoad`
+    repairs: Vec<Repair>,
+    
+    /// Find bar over the code preview, while open
+    preview_search: Option<PreviewSearch>,
+    
+    /// Project allow/deny lists for types
+    type_config: project::ProjectTypeConfig,
+    
+    /// `cargo check` results keyed by tree hash
+    type_check_cache: HashMap<u64, Vec<EditorDiagnostic>>,
+    
+    /// A `cargo check` is running in the background
+    type_check_running: bool,
+    
+    /// Where the alias file is read from and saved to
+    files: Arc<dyn FileProvider>,
 }
 
 impl VisualAliasEditor {
     pub fn new_with_file(file_path: PathBuf, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self::new_with_provider(file_path, Arc::new(LocalFileProvider), window, cx)
+    }
+
+    /// Open an alias stored behind `files`, e.g. a virtual or remote asset store.
+    /// Saves go through the same provider.
+    pub fn new_with_provider(
+        file_path: PathBuf,
+        files: Arc<dyn FileProvider>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         // Try to load the alias data
-        let (name, display_name, description, root_block, error_message) =
-            match std::fs::read_to_string(&file_path) {
+        let empty_meta = || serde_json::Value::Object(serde_json::Map::new());
+        let type_config = project::ProjectTypeConfig::for_file(&file_path);
+        let mut migrated = false;
+        let mut normalized = Vec::new();
+        let mut extra_fields = serde_json::Map::new();
+        let (name, display_name, description, meta, root_block, error_message) =
+            match files.read_to_string(&file_path) {
                 Ok(json_content) => {
-                    match serde_json::from_str::<AliasAsset>(&json_content) {
-                        Ok(asset) => (
-                            asset.name.clone(),
-                            asset.display_name.clone(),
-                            asset.description.unwrap_or_default(),
-                            Some(TypeBlock::from_ast(&asset.ast)),
-                            None,
-                        ),
+                    match schema::parse_alias_asset(&json_content) {
+                        Ok(parsed) => {
+                            recent::record_opened(&file_path, cx);
+                            let asset = parsed.asset;
+                            migrated = parsed.migrated;
+                            extra_fields = parsed.extra_fields;
+                            let (ast, renamed) = type_config.normalize_primitives(&asset.ast);
+                            normalized = renamed;
+                            (
+                                asset.name.clone(),
+                                asset.display_name.clone(),
+                                asset.description.unwrap_or_default(),
+                                if asset.meta.is_object() { asset.meta } else { empty_meta() },
+                                Some(TypeBlock::from_ast(&ast)),
+                                None,
+                            )
+                        }
                         Err(e) => (
                             String::new(),
                             "New Alias".to_string(),
                             String::new(),
+                            empty_meta(),
                             None,
                             Some(format!("Failed to parse: {}", e)),
                         ),
@@ -85,53 +497,114 @@ impl VisualAliasEditor {
                         String::new(),
                         "New Alias".to_string(),
                         String::new(),
+                        empty_meta(),
                         None,
                         None,
                     )
                 }
             };
+        let export_options = ExportOptions::from_meta(&meta);
+        let read_only = is_locked_in(files.as_ref(), &file_path, &meta);
 
         let canvas = if let Some(block) = root_block {
             BlockCanvas::with_root(block)
         } else {
             BlockCanvas::new()
         };
+        let imported_blocks = imported_snapshot(canvas.root_block(), &meta);
         
         let horizontal_resizable_state = ResizableState::new(cx);
         
-        // Create preview input with code editor setup (same as script editor)
-        let preview_input = cx.new(|cx| {
-            use ui::input::TabSize;
-            InputState::new(window, cx)
-                .code_editor("rust")
-                .line_number(true)
-                .minimap(true)
-                .tab_size(TabSize {
-                    tab_size: 4,
-                    hard_tabs: false,
-                })
+        let preview_input = code_input("rust", window, cx);
+        let codegen_review_input = code_input("rust", window, cx);
+        
+        let serde_rename_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("serde rename (optional)");
+            if let Some(rename) = &export_options.serde.rename {
+                state.set_value(rename, window, cx);
+            }
+            state
         });
+        cx.subscribe_in(&serde_rename_input, window, Self::on_serde_rename_changed)
+            .detach();
         
         let mut editor = Self {
-            file_path: Some(file_path),
+            file_path: Some(file_path.clone()),
             name,
             display_name,
             description,
+            meta,
+            export_options,
+            serde_rename_input,
             canvas,
             preview_input,
+            saved_preview_input: None,
+            pending_codegen: None,
+            pending_save_target: None,
+            codegen_review_input,
             horizontal_resizable_state,
             preview_needs_update: true,
-            error_message,
+            preview_task: None,
+            validity: None,
+            reported_validity: None,
+            diagnostics: error_message
+                .map(EditorDiagnostic::error)
+                .into_iter()
+                .chain(migrated.then_some(EditorDiagnostic::SchemaUpgraded(schema::CURRENT_SCHEMA_VERSION)))
+                .chain((!normalized.is_empty()).then(|| EditorDiagnostic::PrimitivesNormalized(normalized.clone())))
+                .collect(),
+            dirty: migrated || !normalized.is_empty(),
+            read_only,
+            extra_fields,
+            reserved_names: Vec::new(),
+            shadow_override: None,
             show_preview: true,
+            show_explanation: false,
+            meta_input: None,
+            meta_error: None,
             focus_handle: cx.focus_handle(),
             selected_slot: None,
+            selected_slots: Vec::new(),
+            last_picked: None,
+            continue_filling: false,
+            fill_chain: Vec::new(),
+            callback_wizard: None,
             pending_block: None,
-            pending_slot_selection: Arc::new(Mutex::new(None)),
+            pending_root_replacement: None,
+            duplicate_name_input: None,
+            lint_duplicate_params: true,
+            max_nesting_depth: type_config.max_nesting_depth,
+            dismissed_warnings: HashSet::new(),
+            imported_blocks,
+            alias_index: project::AliasIndex::for_file(&file_path),
+            generics_undo: Vec::new(),
+            replace_inputs: None,
+            const_arg_input: None,
+            lifetime_input: None,
+            replace_status: None,
+            flatten_preview: None,
+            rust_import: None,
+            show_dependencies: false,
+            show_settings: false,
+            usages: None,
+            delete_prompt: None,
+            revert_prompt: false,
+            dedupe_prompt: None,
+            dedupe_declined: Vec::new(),
+            repairs: Vec::new(),
+            preview_search: None,
+            type_config,
+            type_check_cache: HashMap::new(),
+            type_check_running: false,
+            files,
         };
         
+        if editor.setting(EditorSetting::ValidateOnLoad, cx) {
+            editor.validate_loaded();
+        }
+
         // Initialize preview input with current content
         editor.update_preview(window, cx);
-        editor.preview_needs_update = false;
         
         editor
     }
@@ -140,303 +613,4487 @@ impl VisualAliasEditor {
         self.file_path.clone()
     }
 
-    fn save(&mut self, _: &Save, _window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(file_path) = &self.file_path {
-            if let Some(root_block) = self.canvas.root_block() {
-                if let Some(ast) = root_block.to_ast() {
-                    let asset = AliasAsset {
-                        schema_version: 1,
-                        type_kind: ui_types_common::TypeKind::Alias,
-                        name: self.name.clone(),
-                        display_name: self.display_name.clone(),
-                        description: if self.description.is_empty() {
-                            None
-                        } else {
-                            Some(self.description.clone())
-                        },
-                        ast,
-                        meta: serde_json::Value::Object(serde_json::Map::new()),
-                    };
+    /// Whether the editor has changes that are not on disk
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
 
-                    match serde_json::to_string_pretty(&asset) {
-                        Ok(json) => {
-                            if let Err(e) = std::fs::write(file_path, json) {
-                                self.error_message = Some(format!("Failed to save: {}", e));
-                            } else {
-                                self.error_message = None;
-                                // TODO: Generate Rust code and update type index
-                                eprintln!("✅ Saved type alias to {:?}", file_path);
-                            }
-                        }
-                        Err(e) => {
-                            self.error_message = Some(format!("Failed to serialize: {}", e));
-                        }
-                    }
-                } else {
-                    self.error_message = Some("Type has empty slots - fill all slots before saving".to_string());
-                }
-            } else {
-                self.error_message = Some("Cannot save empty type".to_string());
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Lock or unlock the editor. The host decides, e.g. from `is_locked`; locking drops
+    /// any half-finished edit.
+    pub fn set_read_only(&mut self, read_only: bool, cx: &mut Context<Self>) {
+        self.read_only = read_only;
+        if read_only {
+            self.selected_slot = None;
+            self.pending_block = None;
+            self.pending_root_replacement = None;
+            self.revert_prompt = false;
+            self.dedupe_prompt = None;
+            self.repairs.clear();
+            self.callback_wizard = None;
+            self.const_arg_input = None;
+            self.lifetime_input = None;
+            self.replace_inputs = None;
+            self.flatten_preview = None;
+            self.rust_import = None;
+            self.fill_chain.clear();
+            self.settle_save_target(cx);
+        }
+        cx.notify();
+    }
+
+    /// Save without any prompts, for host commands like "Save all". The generated code is
+    /// written without the review step, and a shadowed name the user has not already
+    /// overridden fails the save. Returns the asset path or the errors that stopped it.
+    pub fn save_now(&mut self, cx: &mut Context<Self>) -> Result<PathBuf, Vec<EditorDiagnostic>> {
+        if self.read_only {
+            return Err(vec![EditorDiagnostic::error("This alias is read-only")]);
+        }
+        let Some(file_path) = self.file_path.clone() else {
+            return Err(vec![EditorDiagnostic::error("Not saved yet - use Save As to pick a location")]);
+        };
+
+        if let Some(shadowed) = self.shadowed_name() {
+            if self.shadow_override.as_deref() != Some(shadowed.as_str()) {
+                let diagnostic = EditorDiagnostic::ShadowsName {
+                    name: self.display_name.clone(),
+                    shadowed,
+                };
+                self.set_warning(diagnostic.clone());
+                cx.notify();
+                return Err(vec![diagnostic]);
             }
         }
+
+        let diagnostics = self.validate();
+        if diagnostics.iter().any(EditorDiagnostic::is_error) {
+            self.show_diagnostics(&diagnostics);
+            cx.notify();
+            return Err(diagnostics.into_iter().filter(EditorDiagnostic::is_error).collect());
+        }
+
+        let generated = self.generated_file().map_err(|e| vec![EditorDiagnostic::error(e)])?;
+        self.finish_save(generated, cx);
+        if self.dirty {
+            Err(self.diagnostics.iter().filter(|d| d.is_error()).cloned().collect())
+        } else {
+            Ok(file_path)
+        }
+    }
+
+    fn request_save_all(&mut self, _: &SaveAll, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(SaveAllRequest);
+    }
+
+    /// Rescan the project so alias references resolve against the files on disk
+    fn refresh_alias_index(&mut self) {
+        self.alias_index = self.file_path.as_deref().and_then(project::AliasIndex::for_file);
+    }
+
+    /// Open the definition of a referenced alias, or flag it when it cannot be resolved
+    fn open_alias_ref(&mut self, alias: String, cx: &mut Context<Self>) {
+        match self.alias_index.as_ref().and_then(|index| index.resolve(&alias)) {
+            Some(path) => cx.emit(OpenAliasRequest { path: path.to_path_buf() }),
+            None => self.set_error(format!("Cannot find the definition of `{}`", alias)),
+        }
         cx.notify();
     }
 
-    fn toggle_palette(&mut self, _: &TogglePalette, _window: &mut Window, cx: &mut Context<Self>) {
-        // Open the centered type picker with no target slot
-        cx.emit(ShowTypePickerRequest {
-            target_slot: self.selected_slot.clone(),
-        });
+    /// Whether the tree passes validation with no errors; warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        !self.validate().iter().any(EditorDiagnostic::is_error)
     }
 
+    /// Whether there is a tree and every required slot in it is filled
+    pub fn is_complete(&self) -> bool {
+        self.canvas.root_block().and_then(TypeBlock::to_ast).is_some()
+    }
 
+    /// Project type config, for creating a filtered type picker
+    pub fn type_config(&self) -> &project::ProjectTypeConfig {
+        &self.type_config
+    }
 
-    /// Add a block to the canvas
-    fn add_block_to_canvas(&mut self, block: TypeBlock, cx: &mut Context<Self>) {
-        if self.canvas.root_block().is_none() {
-            // No root block yet - place as root
-            self.canvas.set_root_block(Some(block));
-            self.error_message = None;
-            self.pending_block = None;
-            self.selected_slot = None;
-        } else if let Some((parent_id, slot_idx)) = &self.selected_slot {
-            // Slot is selected - fill it
-            if self.canvas.fill_slot(parent_id.clone(), *slot_idx, block) {
-                self.error_message = None;
-                self.selected_slot = None;
-                self.pending_block = None;
-            } else {
-                self.error_message = Some("Failed to fill slot".to_string());
-            }
-        } else {
-            // Has root but no slot selected - store as pending and prompt user
-            self.pending_block = Some(block);
-            self.error_message = Some("Click on an empty slot to place this type".to_string());
+    /// Run all validation passes over the current tree, including reference cycles
+    /// through the project's saved aliases
+    pub fn validate(&self) -> Vec<EditorDiagnostic> {
+        let known_aliases = self.alias_index.as_ref().map(|index| index.names());
+        // The depth limit can be set per editor, over the project's
+        let config = project::ProjectTypeConfig {
+            max_nesting_depth: self.max_nesting_depth,
+            ..self.type_config.clone()
+        };
+        let mut meta = self.meta.clone();
+        self.export_options.write_to_meta(&mut meta);
+        let asset = AliasAsset {
+            schema_version: schema::CURRENT_SCHEMA_VERSION,
+            type_kind: ui_types_common::TypeKind::Alias,
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            description: None,
+            ast: self.canvas.root_block().map_or(TypeAstNode::None, TypeBlock::to_ast_with_holes),
+            meta,
+        };
+        let mut diagnostics = validation::validate_asset(&asset, &config, known_aliases.as_deref());
+
+        if let (Some(index), true) = (&self.alias_index, codegen::is_complete(&asset.ast)) {
+            diagnostics.extend(validation::check_cycles(&asset.ast, &self.display_name, &index.load_assets()));
         }
-        self.preview_needs_update = true;
+        if let Some(e) = &self.meta_error {
+            diagnostics.push(EditorDiagnostic::error(format!("Metadata is not saved: {}", e)));
+        }
+        diagnostics
+    }
+
+    /// Show the result of validation without touching the file
+    fn run_validation(&mut self, _: &Validate, _window: &mut Window, cx: &mut Context<Self>) {
+        self.refresh_alias_index();
+        let mut diagnostics = self.validate();
+        if diagnostics.is_empty() {
+            diagnostics.push(EditorDiagnostic::info(format!("✅ {} is valid", self.display_name)));
+        }
+        self.show_diagnostics(&diagnostics);
         cx.notify();
     }
-    
-    /// Select a slot to fill - opens the type picker
-    fn select_slot(&mut self, parent_id: BlockId, slot_idx: usize, cx: &mut Context<Self>) {
-        self.selected_slot = Some((parent_id.clone(), slot_idx));
-        
-        // If we have a pending block, fill the slot immediately
-        if let Some(block) = self.pending_block.take() {
-            self.add_block_to_canvas(block, cx);
-        } else {
-            // Open the centered type picker for this slot
-            cx.emit(ShowTypePickerRequest {
-                target_slot: Some((parent_id, slot_idx)),
+
+    /// Validate every alias in the project and summarize the files with problems
+    fn run_project_validation(&mut self, _: &ValidateProject, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(root) = self.file_path.as_deref().and_then(project::find_project_root) else {
+            self.set_error("This alias is not inside a project");
+            cx.notify();
+            return;
+        };
+
+        let report = validation::validate_project(&root);
+        let total = report.len();
+        self.diagnostics = report
+            .iter()
+            .flat_map(|(path, diagnostics)| {
+                let file = path.strip_prefix(&root).unwrap_or(path).to_path_buf();
+                diagnostics
+                    .iter()
+                    .map(move |d| EditorDiagnostic::InFile(file.clone(), Box::new(d.clone())))
+            })
+            .collect();
+
+        let failing = report.iter().filter(|(_, d)| d.iter().any(EditorDiagnostic::is_error)).count();
+        self.diagnostics.insert(
+            0,
+            EditorDiagnostic::info(format!("Validated {} aliases, {} with errors", total, failing)),
+        );
+        cx.notify();
+    }
+
+    /// List the alias files in the project that reference this alias
+    fn find_usages(&mut self, _: &FindUsages, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(root) = self.file_path.as_deref().and_then(project::find_project_root) else {
+            self.set_error("This alias is not inside a project");
+            cx.notify();
+            return;
+        };
+        self.usages = Some(project::find_usages(&root, &self.display_name));
+        cx.notify();
+    }
+
+    /// The aliases and external paths this alias depends on, each in order of first use.
+    /// Works on unfinished trees too, so the panel stays live while editing.
+    pub fn dependencies(&self) -> (Vec<String>, Vec<String>) {
+        let generics = codegen::declared_generics(&self.meta);
+        let mut aliases = Vec::new();
+        let mut paths = Vec::new();
+        if let Some(root) = self.canvas.root_block() {
+            root.walk(&mut |block| match block {
+                TypeBlock::AliasRef { alias, .. } if !aliases.contains(alias) => aliases.push(alias.clone()),
+                TypeBlock::Path { path, .. }
+                    if codegen::is_external_path(path, &generics) && !paths.contains(path) =>
+                {
+                    paths.push(path.clone())
+                }
+                _ => {}
             });
         }
+        (aliases, paths)
     }
-    
-    /// Add a block from the type picker
-    pub fn add_type_from_picker(&mut self, type_item: &crate::TypeItem, target_slot: Option<(BlockId, usize)>, cx: &mut Context<Self>) {
-        let block = type_item.to_block();
-        
-        if let Some((parent_id, slot_idx)) = target_slot {
-            // Fill the specific slot
-            if self.canvas.fill_slot(parent_id, slot_idx, block) {
-                self.error_message = None;
-                self.selected_slot = None;
-            } else {
-                self.error_message = Some("Failed to fill slot".to_string());
-            }
+
+    fn toggle_dependencies(&mut self, _: &ShowDependencies, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_dependencies = !self.show_dependencies;
+        cx.notify();
+    }
+
+    /// Ask the host for the recent files switcher
+    fn quick_switch(&mut self, _: &QuickSwitch, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ShowRecentFilesRequest { current: self.file_path.clone() });
+    }
+
+    /// Open a file picked in the recent files switcher
+    pub fn open_recent(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if !path.is_file() {
+            self.set_error(format!("{:?} no longer exists", path));
+            cx.notify();
+            return;
+        }
+        cx.emit(OpenAliasRequest { path });
+    }
+
+    /// Reload the file from disk, asking first if there are unsaved changes
+    fn revert_to_saved(&mut self, _: &RevertToSaved, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        if self.dirty {
+            self.revert_prompt = true;
+            cx.notify();
         } else {
-            // No slot specified - add to canvas
-            self.add_block_to_canvas(block, cx);
+            self.confirm_revert(window, cx);
         }
-        self.preview_needs_update = true;
-        cx.notify();
     }
-    
-    /// Update the preview input with current code
-    fn update_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let code = if let Some(root) = self.canvas.root_block() {
-            if let Some(ast) = root.to_ast() {
-                self.generate_preview_code(&ast)
-            } else {
-                "// Fill all slots to see generated code".to_string()
+
+    /// Replace the tree, name and metadata with the saved file. Undo brings the discarded
+    /// tree back. Leaves everything as is if the file cannot be read.
+    fn confirm_revert(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.revert_prompt = false;
+        let Some(file_path) = self.file_path.clone() else {
+            return;
+        };
+        let parsed = self
+            .files
+            .read_to_string(&file_path)
+            .map_err(|e| format!("Cannot read {:?}: {}", file_path, e))
+            .and_then(|json| schema::parse_alias_asset(&json).map_err(|e| format!("Failed to parse: {}", e)));
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.set_error(format!("Cannot revert: {}", e));
+                cx.notify();
+                return;
             }
+        };
+
+        let asset = parsed.asset;
+        let (ast, normalized) = self.type_config.normalize_primitives(&asset.ast);
+        self.name = asset.name;
+        self.display_name = asset.display_name;
+        self.description = asset.description.unwrap_or_default();
+        self.meta = if asset.meta.is_object() {
+            asset.meta
         } else {
-            "// Click to add a type or use the Add Type button".to_string()
+            serde_json::Value::Object(serde_json::Map::new())
         };
-        
-        self.preview_input.update(cx, |input, cx| {
-            input.set_value(&code, window, cx);
-        });
+        self.extra_fields = parsed.extra_fields;
+        self.export_options = ExportOptions::from_meta(&self.meta);
+        let rename = self.export_options.serde.rename.clone().unwrap_or_default();
+        self.serde_rename_input.update(cx, |input, cx| input.set_value(rename, window, cx));
+        self.canvas.set_root_block(Some(TypeBlock::from_ast(&ast)));
+        self.imported_blocks = imported_snapshot(self.canvas.root_block(), &self.meta);
+
+        self.selected_slot = None;
+        self.selected_slots.clear();
+        self.pending_block = None;
+        self.pending_root_replacement = None;
+        self.dedupe_prompt = None;
+        self.const_arg_input = None;
+        self.lifetime_input = None;
+        self.fill_chain.clear();
+        self.dismissed_warnings.clear();
+        self.diagnostics = parsed
+            .migrated
+            .then_some(EditorDiagnostic::SchemaUpgraded(schema::CURRENT_SCHEMA_VERSION))
+            .into_iter()
+            .chain((!normalized.is_empty()).then(|| EditorDiagnostic::PrimitivesNormalized(normalized.clone())))
+            .collect();
+        self.dirty = parsed.migrated || !normalized.is_empty();
+        self.repairs.clear();
+        if self.setting(EditorSetting::ValidateOnLoad, cx) {
+            self.validate_loaded();
+        }
+        self.preview_needs_update = true;
+        cx.notify();
     }
 
+    /// Start deleting the alias file, first checking what references it
+    fn delete_alias(&mut self, _: &DeleteAlias, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+        let usages = project::find_project_root(file_path)
+            .map(|root| project::find_usages(&root, &self.display_name))
+            .unwrap_or_default();
+        self.delete_prompt = Some(usages);
+        cx.notify();
+    }
 
+    /// Delete the alias file and its generated code. With `inline`, references in other
+    /// aliases are first replaced by this alias's definition. Refuses while anything still
+    /// references the alias. The canvas keeps the type, so Save As can restore it.
+    fn confirm_delete(&mut self, inline: bool, cx: &mut Context<Self>) {
+        self.delete_prompt = None;
+        let Some(file_path) = self.file_path.clone() else {
+            return;
+        };
+        let root = project::find_project_root(&file_path);
 
-    fn generate_preview_code(&self, ast: &TypeAstNode) -> String {
-        let type_str = self.ast_to_rust_string(ast);
-        
-        format!(
-            "// Auto-generated Rust type alias\n\
-             pub type {} = {};\n\n\
-             // Usage example:\n\
-             // let value: {} = ...;",
-            self.display_name,
-            type_str,
-            self.display_name
-        )
-    }
+        // Other files get the definition as saved, not whatever is on the canvas
+        let asset = match self.saved_asset(&file_path) {
+            Ok(asset) => asset,
+            Err(e) => {
+                self.set_error(format!("Cannot read the saved alias: {}", e));
+                cx.notify();
+                return;
+            }
+        };
 
-    fn ast_to_rust_string(&self, ast: &TypeAstNode) -> String {
-        match ast {
-            TypeAstNode::Primitive { name } => name.clone(),
-            TypeAstNode::Path { path } => path.clone(),
-            TypeAstNode::AliasRef { alias } => alias.clone(),
-            TypeAstNode::Constructor { name, params, .. } => {
-                let params_str = params
-                    .iter()
-                    .map(|p| self.ast_to_rust_string(p))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                format!("{}<{}>", name, params_str)
+        if let Some(root) = &root {
+            if inline {
+                let result = project::inline_alias_into_usages(root, &asset.display_name, &asset.ast);
+                let rewritten = match &result {
+                    Ok(changed) => changed.clone(),
+                    Err(failure) => failure.rewritten.clone(),
+                };
+                for path in rewritten {
+                    cx.emit(AliasFileEvent::Saved(path));
+                }
+                if let Err(failure) = result {
+                    let rewritten: Vec<String> = failure
+                        .rewritten
+                        .iter()
+                        .map(|path| path.strip_prefix(root).unwrap_or(path).display().to_string())
+                        .collect();
+                    self.set_error(if rewritten.is_empty() {
+                        format!("Inlining failed, nothing was changed or deleted: {}", failure.message)
+                    } else {
+                        format!(
+                            "Inlining failed, the alias was not deleted: {}. Already rewritten: {}",
+                            failure.message,
+                            rewritten.join(", ")
+                        )
+                    });
+                    cx.notify();
+                    return;
+                }
             }
-            TypeAstNode::Tuple { elements } => {
-                let elements_str = elements
-                    .iter()
-                    .map(|e| self.ast_to_rust_string(e))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                format!("({})", elements_str)
+            let remaining = project::find_usages(root, &asset.display_name);
+            if !remaining.is_empty() {
+                self.set_error(format!("{} is still used by {} aliases", asset.display_name, remaining.len()));
+                self.delete_prompt = Some(remaining);
+                cx.notify();
+                return;
             }
-            TypeAstNode::FnPointer { params, return_type } => {
-                let params_str = params
-                    .iter()
-                    .map(|p| self.ast_to_rust_string(p))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                format!("fn({}) -> {}", params_str, self.ast_to_rust_string(return_type))
+        }
+
+        if let Err(e) = self.files.remove(&file_path) {
+            self.set_error(format!("Failed to delete: {}", e));
+            cx.notify();
+            return;
+        }
+        if let Err(e) = codegen::remove_generated_code(&file_path, &asset) {
+            self.set_warning(EditorDiagnostic::warning(e));
+        }
+        // Assets live in their own `<TypeName>/` directory; drop it once empty
+        if file_path.file_name().and_then(|n| n.to_str()) == Some(project::ALIAS_FILE_NAME) {
+            if let Some(dir) = file_path.parent() {
+                let _ = std::fs::remove_dir(dir);
             }
         }
+
+        self.file_path = None;
+        self.dirty = true;
+        self.alias_index = None;
+        cx.emit(AliasFileEvent::Deleted(file_path));
+        cx.notify();
     }
-}
 
-impl Render for VisualAliasEditor {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Update preview if needed
-        if self.preview_needs_update {
-            self.update_preview(window, cx);
-            self.preview_needs_update = false;
+    /// Rewrite every generated `.rs` file in the project from the saved alias files
+    fn regenerate_all(&mut self, _: &RegenerateAll, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(root) = self.file_path.as_deref().and_then(project::find_project_root) else {
+            self.set_error("This alias is not inside a project");
+            cx.notify();
+            return;
+        };
+
+        let report = codegen::regenerate_project(&root);
+        self.diagnostics = report
+            .failed
+            .iter()
+            .map(|(path, e)| {
+                let file = path.strip_prefix(&root).unwrap_or(path).to_path_buf();
+                EditorDiagnostic::InFile(file, Box::new(EditorDiagnostic::error(format!("Not regenerated: {}", e))))
+            })
+            .collect();
+        self.diagnostics.insert(
+            0,
+            EditorDiagnostic::info(format!(
+                "Regenerated {} files, {} unchanged, {} failed",
+                report.written.len(),
+                report.unchanged.len(),
+                report.failed.len()
+            )),
+        );
+        cx.notify();
+    }
+
+    /// Compile the generated code with `cargo check` in a scratch crate.
+    /// Runs in the background; results are cached per generated module so repeat checks are instant.
+    fn run_type_check(&mut self, cx: &mut Context<Self>) {
+        if self.type_check_running {
+            return;
         }
-        
-        // Check for pending slot selection from click handler
-        let pending_selection = if let Ok(mut guard) = self.pending_slot_selection.lock() {
-            guard.take()
-        } else {
-            None
+
+        let asset = match self.build_asset() {
+            Ok(asset) => asset,
+            Err(e) => {
+                self.set_error(e);
+                cx.notify();
+                return;
+            }
         };
-        
-        if let Some((block_id, slot_idx)) = pending_selection {
-            // Special case: empty BlockId indicates empty state click (add root)
-            if block_id.0.is_empty() {
-                // Open type picker for root (no target slot)
-                cx.emit(ShowTypePickerRequest {
-                    target_slot: None,
-                });
+        // Include the rest of the project's aliases so references resolve
+        let mut assets: Vec<AliasAsset> = self
+            .alias_index
+            .as_ref()
+            .map(|index| index.load_assets())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|other| other.display_name != asset.display_name)
+            .collect();
+        assets.push(asset);
+        let code = codegen::generate_module_code(&assets, &self.type_config);
+        let manifest = compile_check::scratch_manifest(
+            self.file_path.as_deref().and_then(project::find_project_root).as_deref(),
+        );
+        // The module also depends on the other aliases, names and export options, so key on all of it
+        let key = compile_check::code_hash(&manifest, &code);
+        if let Some(diagnostics) = self.type_check_cache.get(&key).cloned() {
+            self.show_diagnostics(&diagnostics);
+            cx.notify();
+            return;
+        }
+
+        self.type_check_running = true;
+        cx.notify();
+
+        // A failed run isn't cached, so fixing the project's manifest and checking again works
+        let check = cx.background_spawn(async move { compile_check::cargo_check(&manifest, &code) });
+        cx.spawn(async move |this, cx| {
+            let result = check.await;
+            this.update(cx, |editor, cx| {
+                editor.type_check_running = false;
+                match result {
+                    Ok(diagnostics) => {
+                        editor.show_diagnostics(&diagnostics);
+                        editor.type_check_cache.insert(key, diagnostics);
+                    }
+                    Err(e) => editor.set_error(e),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Populate the error and warning banners from diagnostics
+    fn show_diagnostics(&mut self, diagnostics: &[EditorDiagnostic]) {
+        self.diagnostics = diagnostics.to_vec();
+    }
+
+    /// Replace the errors in the banner with `message`, keeping warnings
+    fn set_error(&mut self, message: impl Into<String>) {
+        self.clear_errors();
+        self.diagnostics.push(EditorDiagnostic::error(message));
+    }
+
+    /// Replace the warnings in the banner with `diagnostic`, keeping errors
+    fn set_warning(&mut self, diagnostic: EditorDiagnostic) {
+        self.clear_warnings();
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Report that an operation went fine, replacing any warnings
+    fn set_info(&mut self, message: impl Into<String>) {
+        self.set_warning(EditorDiagnostic::info(message));
+    }
+
+    fn clear_errors(&mut self) {
+        self.diagnostics.retain(|d| !d.is_error());
+    }
+
+    fn clear_warnings(&mut self) {
+        self.diagnostics.retain(EditorDiagnostic::is_error);
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(EditorDiagnostic::is_error)
+    }
+
+    /// The alias as last saved at `path`, read through the file provider
+    fn saved_asset(&self, path: &Path) -> Result<AliasAsset, String> {
+        let json = self.files.read_to_string(path).map_err(|e| e.to_string())?;
+        schema::parse_alias_asset(&json).map(|parsed| parsed.asset)
+    }
+
+    /// Build the asset for the current canvas state
+    fn build_asset(&self) -> Result<AliasAsset, String> {
+        let root_block = self.canvas.root_block().ok_or("Cannot save empty type")?;
+        let ast = root_block
+            .to_ast()
+            .ok_or("Type has empty slots - fill all slots before saving")?;
+
+        let mut meta = self.meta.clone();
+        self.export_options.write_to_meta(&mut meta);
+        let marks = self.imported_marks();
+        let mut imported = Vec::new();
+        root_block.walk_paths(&mut |path, block| {
+            if marks.contains(block.id()) {
+                imported.push(path.to_vec());
+            }
+        });
+        import::set_imported_paths(&mut meta, &imported);
+
+        Ok(AliasAsset {
+            schema_version: schema::CURRENT_SCHEMA_VERSION,
+            type_kind: ui_types_common::TypeKind::Alias,
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            description: if self.description.is_empty() {
+                None
             } else {
-                self.select_slot(block_id, slot_idx, cx);
+                Some(self.description.clone())
+            },
+            ast,
+            meta,
+        })
+    }
+
+    /// Imported blocks whose subtree is unchanged since the import. Editing a block or
+    /// anything below it clears its marker; undoing the edit brings it back.
+    fn imported_marks(&self) -> HashSet<BlockId> {
+        let mut marks = HashSet::new();
+        if let Some(root) = self.canvas.root_block() {
+            root.walk(&mut |block| {
+                let unchanged = self
+                    .imported_blocks
+                    .get(block.id())
+                    .zip(block.to_ast())
+                    .is_some_and(|(imported, current)| type_ast::structural_eq(imported, &current));
+                if unchanged {
+                    marks.insert(block.id().clone());
+                }
+            });
+        }
+        marks
+    }
+
+    /// Set the project-level reserved names checked on save
+    pub fn set_reserved_names(&mut self, names: Vec<String>) {
+        self.reserved_names = names;
+    }
+
+    /// Find a std or project reserved name that the alias `name` or `display_name` would shadow
+    fn shadowed_name(&self) -> Option<String> {
+        let reserved = STD_TYPE_NAMES
+            .iter()
+            .copied()
+            .chain(self.reserved_names.iter().map(String::as_str));
+
+        for reserved_name in reserved {
+            let normalized = reserved_name.replace('_', "");
+            for candidate in [&self.display_name, &self.name] {
+                if candidate.replace('_', "").eq_ignore_ascii_case(&normalized) {
+                    return Some(reserved_name.to_string());
+                }
             }
         }
-        
-        v_flex()
-            .size_full()
-            .bg(cx.theme().background)
-            .child(
-                // Top toolbar
-                h_flex()
-                    .w_full()
-                    .px_4()
-                    .py_3()
-                    .gap_4()
-                    .bg(cx.theme().secondary.opacity(0.5))
-                    .border_b_2()
-                    .border_color(cx.theme().border)
-                    .items_center()
-                    .child(
-                        // Icon and title
+        None
+    }
+
+    /// Save even though the alias name shadows a reserved name
+    fn save_anyway(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.shadow_override = self.shadowed_name();
+        self.save(&Save, window, cx);
+    }
+
+    fn save(&mut self, _: &Save, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_save(window, cx);
+        self.settle_save_target(cx);
+    }
+
+    /// Validate and write the alias, stopping at the first prompt that needs an answer
+    fn run_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        // New aliases have nowhere to go yet
+        if self.file_path.is_none() {
+            self.save_as(&SaveAs, window, cx);
+            return;
+        }
+
+        if let Some(shadowed) = self.shadowed_name() {
+            if self.shadow_override.as_deref() != Some(shadowed.as_str()) {
+                self.set_warning(EditorDiagnostic::ShadowsName {
+                    name: self.display_name.clone(),
+                    shadowed,
+                });
+                cx.notify();
+                return;
+            }
+        }
+        self.clear_warnings();
+
+        let diagnostics = self.validate();
+        if diagnostics.iter().any(EditorDiagnostic::is_error) {
+            self.show_diagnostics(&diagnostics);
+            cx.notify();
+            return;
+        }
+
+        if self.setting(EditorSetting::DedupeOnSave, cx) {
+            if let Some((subtree, count)) = self.dedupe_candidate() {
+                self.propose_dedupe(subtree, count, window, cx);
+                return;
+            }
+        }
+
+        let generated = match self.generated_file() {
+            Ok(generated) => generated,
+            Err(e) => {
+                self.set_error(e);
+                cx.notify();
+                return;
+            }
+        };
+        if let Some((path, code)) = &generated {
+            // Show the file before it's first created, or every time if the setting asks for it
+            if self.setting(EditorSetting::ConfirmGeneratedCode, cx) || !self.files.exists(path) {
+                self.codegen_review_input.update(cx, |input, cx| {
+                    input.set_value(code, window, cx);
+                });
+                self.pending_codegen = generated;
+                cx.notify();
+                return;
+            }
+        }
+        self.finish_save(generated, cx);
+    }
+
+    /// Whether `subtree` uses the alias's generic params or `Self`, which a standalone
+    /// alias could not name
+    fn uses_alias_scope(&self, subtree: &TypeAstNode) -> bool {
+        let generics = codegen::declared_generics(&self.meta);
+        let mut names = Vec::new();
+        type_ast::collect_type_names(subtree, &mut names);
+        codegen::collect_const_generics(subtree, &mut names);
+        names.iter().any(|name| generics.contains(name)) || type_ast::uses_self_type(subtree)
+    }
+
+    /// The largest repeated subtree worth extracting, skipping those that use the
+    /// alias's own scope
+    fn dedupe_candidate(&self) -> Option<(TypeAstNode, usize)> {
+        let ast = self.canvas.root_block()?.to_ast()?;
+        type_ast::repeated_subtrees(&ast, DEDUPE_MIN_NODES)
+            .into_iter()
+            .find(|(subtree, _)| {
+                !self.uses_alias_scope(subtree)
+                    && !self
+                        .dedupe_declined
+                        .iter()
+                        .any(|declined| type_ast::structural_eq(declined, subtree))
+            })
+    }
+
+    /// Hold the save and show the extraction it would make
+    fn propose_dedupe(&mut self, subtree: TypeAstNode, count: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let default_name = helper_alias_name(&self.display_name, &subtree);
+        let name_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("Name for the helper alias");
+            state.set_value(&default_name, window, cx);
+            state
+        });
+        self.dedupe_prompt = Some(DedupeProposal { subtree, count, name_input });
+        cx.notify();
+    }
+
+    /// Run every validation pass over a freshly opened alias, adding its findings to
+    /// the load messages, and collect the repairs on offer
+    fn validate_loaded(&mut self) {
+        let Some(root) = self.canvas.root_block() else {
+            return;
+        };
+        self.repairs = repair::find_repairs(&root.to_ast_with_holes(), &self.meta, &self.type_config);
+        for diagnostic in self.validate() {
+            if !self.diagnostics.contains(&diagnostic) {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    /// Apply `repairs` to the tree and meta as one undo step, then validate again.
+    /// Repairs that no longer apply to the current tree are skipped.
+    fn apply_repairs(&mut self, repairs: Vec<Repair>, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let Some(root) = self.canvas.root_block() else {
+            self.repairs.clear();
+            cx.notify();
+            return;
+        };
+        let mut ast = root.to_ast_with_holes();
+        let current = repair::find_repairs(&ast, &self.meta, &self.type_config);
+        let generics = codegen::declared_generics(&self.meta);
+        for repair in repairs.iter().filter(|repair| current.contains(repair)) {
+            ast = repair.apply(&ast, &mut self.meta);
+        }
+
+        self.canvas.set_root_block(Some(TypeBlock::from_ast(&ast)));
+        if codegen::declared_generics(&self.meta) != generics {
+            if let Some(serial) = self.canvas.last_snapshot() {
+                self.generics_undo.push((serial, generics));
+            }
+        }
+        self.repairs = repair::find_repairs(&ast, &self.meta, &self.type_config);
+        self.selected_slot = None;
+        let diagnostics = self.validate();
+        self.show_diagnostics(&diagnostics);
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    /// Apply every repair that keeps all the types the user wrote
+    fn apply_safe_repairs(&mut self, cx: &mut Context<Self>) {
+        let safe = self.repairs.iter().filter(|repair| repair.is_safe()).cloned().collect();
+        self.apply_repairs(safe, cx);
+    }
+
+    /// Keep the proposed subtree inline and carry on saving
+    fn skip_dedupe(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(proposal) = self.dedupe_prompt.take() {
+            self.dedupe_declined.push(proposal.subtree);
+        }
+        self.save(&Save, window, cx);
+    }
+
+    /// Write the proposed subtree as a new alias next to this one, point every occurrence
+    /// at it, then carry on saving. The replacement is one undo step; the new file stays.
+    fn confirm_dedupe(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(proposal) = self.dedupe_prompt.take() else {
+            return;
+        };
+        let helper_name = proposal.name_input.read(cx).value().trim().to_string();
+        if let Err(e) = self.create_helper_alias(&helper_name, &proposal.subtree, cx) {
+            self.set_error(e);
+            self.dedupe_prompt = Some(proposal);
+            cx.notify();
+            return;
+        }
+
+        let replacement = TypeAstNode::AliasRef { alias: helper_name };
+        if self.canvas.replace_matching(&proposal.subtree, &replacement) > 0 {
+            self.preview_needs_update = true;
+            self.dirty = true;
+        }
+        self.refresh_alias_index();
+        self.save(&Save, window, cx);
+    }
+
+    /// Write a new alias holding `subtree` next to this one, plus its generated code once
+    /// the subtree has no empty slots. Fails without writing if the name is taken.
+    fn create_helper_alias(&mut self, helper_name: &str, subtree: &TypeAstNode, cx: &mut Context<Self>) -> Result<PathBuf, String> {
+        let Some(file_path) = self.file_path.clone() else {
+            return Err("Not saved yet - use Save As to pick a location".to_string());
+        };
+        let path = duplicate_path(&file_path, helper_name);
+        if !is_valid_type_name(helper_name) {
+            return Err(format!("\"{}\" is not a valid type name", helper_name));
+        }
+        if self.alias_name_taken(helper_name) {
+            return Err(format!("An alias named `{}` already exists", helper_name));
+        }
+        if self.files.exists(&path) {
+            return Err(format!("{:?} already exists", path));
+        }
+
+        let asset = AliasAsset {
+            schema_version: schema::CURRENT_SCHEMA_VERSION,
+            type_kind: ui_types_common::TypeKind::Alias,
+            name: to_snake_case(helper_name),
+            display_name: helper_name.to_string(),
+            description: Some(format!("Extracted from {}", self.display_name)),
+            ast: subtree.clone(),
+            meta: serde_json::Value::Object(serde_json::Map::new()),
+        };
+        let result = schema::alias_asset_to_json(&asset, &serde_json::Map::new(), self.type_config.json_style)
+            .and_then(|json| self.files.write(&path, &json).map_err(|e| e.to_string()))
+            .and_then(|_| {
+                if !codegen::is_complete(subtree) {
+                    // The new editor generates the code once its slots are filled
+                    return Ok(());
+                }
+                let (code_path, code) =
+                    codegen::generated_file(&path, &asset, &ExportOptions::default().with_template(&self.type_config));
+                self.files.write(&code_path, &code).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            return Err(format!("Failed to create {}: {}", helper_name, e));
+        }
+        cx.emit(AliasFileEvent::Created(path.clone()));
+        Ok(path)
+    }
+
+    fn alias_name_taken(&self, name: &str) -> bool {
+        name == self.display_name || self.alias_index.as_ref().is_some_and(|index| index.contains(name))
+    }
+
+    /// Make a block dragged out of this editor's canvas into an alias of its own and ask
+    /// the host to open it. With `replace`, the block becomes a reference to the new alias.
+    /// Hosts call this from their dock's drop handler, e.g. with `window.modifiers().alt`.
+    pub fn extract_dragged_block(&mut self, drag: &DraggedBlock, replace: bool, cx: &mut Context<Self>) -> Result<PathBuf, String> {
+        if drag.source != cx.entity_id() {
+            return Err("The block was dragged from another editor".to_string());
+        }
+        if self.uses_alias_scope(&drag.ast) {
+            let message = "The block uses this alias's generic params or Self, so it can't be its own alias".to_string();
+            self.set_error(message.clone());
+            cx.notify();
+            return Err(message);
+        }
+        let base = helper_alias_name(&self.display_name, &drag.ast);
+        let name = std::iter::once(base.clone())
+            .chain((2..).map(|n| format!("{}{}", base, n)))
+            .find(|name| {
+                !self.alias_name_taken(name)
+                    && !self.file_path.as_deref().is_some_and(|file_path| self.files.exists(&duplicate_path(file_path, name)))
+            })
+            .unwrap_or(base);
+
+        let result = self.create_helper_alias(&name, &drag.ast, cx);
+        match &result {
+            Ok(path) => {
+                if replace && !self.read_only && self.canvas.replace_block(&drag.block_id, TypeBlock::alias(name)) {
+                    self.selected_slot = None;
+                    self.preview_needs_update = true;
+                    self.dirty = true;
+                }
+                self.refresh_alias_index();
+                cx.emit(OpenAliasRequest { path: path.clone() });
+            }
+            Err(e) => self.set_error(e.clone()),
+        }
+        cx.notify();
+        result
+    }
+
+    /// Write the asset, plus the generated code when given, and report the result
+    fn finish_save(&mut self, generated: Option<(PathBuf, String)>, cx: &mut Context<Self>) {
+        let diagnostics = self.validate();
+        match self.write_files(generated) {
+            Ok(Some(file_path)) => {
+                // Warnings don't block saving but stay visible
+                self.show_diagnostics(&diagnostics);
+                self.dirty = false;
+                self.refresh_alias_index();
+                // The saved side of the comparison is now the new file
+                self.preview_needs_update |= self.saved_preview_input.is_some();
+                eprintln!("✅ Saved type alias to {:?}", file_path);
+                cx.emit(AliasFileEvent::Saved(file_path));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.set_error(e);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Ask for a new location and save there, leaving the original file untouched
+    fn save_as(&mut self, _: &SaveAs, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let directory = self
+            .file_path
+            .as_deref()
+            .and_then(Path::parent)
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        let chosen = cx.prompt_for_new_path(&directory);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(path))) = chosen.await else {
+                return;
+            };
+            this.update_in(cx, |editor, window, cx| editor.save_to(path, window, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Retarget the editor at `path` and save. The new location is kept while a prompt
+    /// holds the save, and dropped again if the save fails or is cancelled.
+    fn save_to(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.file_path.replace(path.clone());
+        let was_dirty = std::mem::replace(&mut self.dirty, true);
+        // A second Save As while the first is held still falls back to the original file
+        let (previous, was_dirty) = match self.pending_save_target.take() {
+            Some(held) => (held.previous, held.was_dirty),
+            None => (current, was_dirty),
+        };
+        self.type_config = project::ProjectTypeConfig::for_file(&path);
+        self.pending_save_target = Some(SaveTarget { is_new: !self.files.exists(&path), path, previous, was_dirty });
+        // A successful save clears the dirty flag
+        self.save(&Save, window, cx);
+        cx.notify();
+    }
+
+    /// Whether a save is held waiting on the user: the code review, the dedupe offer or
+    /// the shadowed-name warning
+    fn save_prompt_pending(&self) -> bool {
+        self.pending_codegen.is_some()
+            || self.dedupe_prompt.is_some()
+            || self
+                .shadowed_name()
+                .is_some_and(|shadowed| self.shadow_override.as_deref() != Some(shadowed.as_str()))
+    }
+
+    /// Keep or roll back the Save As location once its save has finished. Nothing
+    /// changes while a prompt still holds the save.
+    fn settle_save_target(&mut self, cx: &mut Context<Self>) {
+        // A read-only editor can't answer prompts, so its held save is over
+        if self.save_prompt_pending() && !self.read_only {
+            return;
+        }
+        let Some(target) = self.pending_save_target.take() else {
+            return;
+        };
+        if self.dirty {
+            // Saving failed or was cancelled; keep pointing at the original file
+            self.dirty = target.was_dirty;
+            self.file_path = target.previous;
+            self.type_config = self
+                .file_path
+                .as_deref()
+                .map(project::ProjectTypeConfig::for_file)
+                .unwrap_or_default();
+            self.refresh_alias_index();
+        } else {
+            recent::record_opened(&target.path, cx);
+            if target.is_new {
+                cx.emit(AliasFileEvent::Created(target.path));
+            }
+        }
+    }
+
+    /// Path and contents of the generated code for the current canvas state
+    fn generated_file(&self) -> Result<Option<(PathBuf, String)>, String> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(None);
+        };
+        let asset = self.build_asset()?;
+        Ok(Some(codegen::generated_file(file_path, &asset, &self.codegen_options())))
+    }
+
+    /// Export options from the meta plus the project's alias template
+    fn codegen_options(&self) -> ExportOptions {
+        self.export_options.clone().with_template(&self.type_config)
+    }
+
+    /// Write the alias asset and, when given, the generated code. Returns the asset path.
+    fn write_files(&self, generated: Option<(PathBuf, String)>) -> Result<Option<PathBuf>, String> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(None);
+        };
+
+        let asset = self.build_asset()?;
+        let json = schema::alias_asset_to_json(&asset, &self.extra_fields, self.type_config.json_style)?;
+        self.files.write(file_path, &json).map_err(|e| format!("Failed to save: {}", e))?;
+        if let Some((path, code)) = generated {
+            self.files
+                .write(&path, &code)
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        }
+
+        Ok(Some(file_path.clone()))
+    }
+
+    /// Finish the save held for review. The asset is saved either way;
+    /// `write_code` decides whether the reviewed `.rs` is written too.
+    fn confirm_codegen(&mut self, write_code: bool, cx: &mut Context<Self>) {
+        let Some(generated) = self.pending_codegen.take() else {
+            return;
+        };
+        self.finish_save(write_code.then_some(generated), cx);
+        self.settle_save_target(cx);
+    }
+
+    /// Show the alias file in the system file manager, plus the generated code
+    /// when it is written somewhere else
+    fn reveal_in_folder(&mut self, _: &RevealInFolder, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file_path) = &self.file_path else {
+            self.set_warning(EditorDiagnostic::warning("Save the alias before revealing it"));
+            cx.notify();
+            return;
+        };
+
+        cx.reveal_path(file_path);
+        let generated = codegen::output_path(&self.meta, file_path);
+        if generated.exists() && generated.parent() != file_path.parent() {
+            cx.reveal_path(&generated);
+        }
+    }
+
+    fn toggle_smart_defaults(&mut self, _: &ToggleSmartDefaults, window: &mut Window, cx: &mut Context<Self>) {
+        EditorSettings::update_global(cx, |settings| settings.smart_defaults = !settings.smart_defaults);
+        window.refresh();
+    }
+
+    /// Switch the type picker between grouping by category and by crate
+    fn toggle_group_by_crate(&mut self, _: &ToggleGroupByCrate, window: &mut Window, cx: &mut Context<Self>) {
+        EditorSettings::update_global(cx, |settings| settings.group_by_crate = !settings.group_by_crate);
+        window.refresh();
+    }
+
+    /// Flip the canvas between top-down and left-to-right where the direction is set:
+    /// the file's override if it has one, else the global default
+    fn toggle_layout_direction(&mut self, _: &ToggleLayoutDirection, window: &mut Window, cx: &mut Context<Self>) {
+        let flipped = |direction| match direction {
+            LayoutDirection::TopDown => LayoutDirection::LeftToRight,
+            LayoutDirection::LeftToRight => LayoutDirection::TopDown,
+        };
+        match self.file_layout_direction() {
+            Some(direction) if !self.read_only => self.set_file_layout_direction(Some(flipped(direction)), cx),
+            _ => {
+                EditorSettings::update_global(cx, |settings| settings.layout_direction = flipped(settings.layout_direction));
+                window.refresh();
+            }
+        }
+    }
+
+    /// The layout direction the file pins, or `None` when it follows the global default
+    fn file_layout_direction(&self) -> Option<LayoutDirection> {
+        self.meta
+            .get(LAYOUT_META_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Pin the layout direction for this file, or follow the global default again with `None`
+    fn set_file_layout_direction(&mut self, direction: Option<LayoutDirection>, cx: &mut Context<Self>) {
+        if self.read_only || self.file_layout_direction() == direction {
+            return;
+        }
+        if let Some(map) = self.meta.as_object_mut() {
+            match direction.and_then(|direction| serde_json::to_value(direction).ok()) {
+                Some(value) => {
+                    map.insert(LAYOUT_META_KEY.to_string(), value);
+                }
+                None => {
+                    map.remove(LAYOUT_META_KEY);
+                }
+            }
+        }
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn toggle_high_contrast(&mut self, _: &ToggleHighContrast, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_setting(EditorSetting::HighContrast, window, cx);
+    }
+
+    fn toggle_sizes(&mut self, _: &ToggleSizes, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_setting(EditorSetting::ShowSizes, window, cx);
+    }
+
+    /// The file's value for `setting` if it overrides it, else the global default
+    fn setting(&self, setting: EditorSetting, cx: &App) -> bool {
+        settings::file_override(&self.meta, setting).unwrap_or_else(|| EditorSettings::global(cx).get(setting))
+    }
+
+    /// Flip a toggle where it is set: the file's override if it has one, else the global default
+    fn toggle_setting(&mut self, setting: EditorSetting, window: &mut Window, cx: &mut Context<Self>) {
+        match settings::file_override(&self.meta, setting) {
+            Some(value) if !self.read_only => self.set_file_setting(setting, Some(!value), cx),
+            _ => {
+                EditorSettings::update_global(cx, |settings| settings.set(setting, !settings.get(setting)));
+                // Other open editors pick the setting up on their next render
+                window.refresh();
+            }
+        }
+    }
+
+    /// Override `setting` for this file, or follow the global default again with `None`
+    fn set_file_setting(&mut self, setting: EditorSetting, value: Option<bool>, cx: &mut Context<Self>) {
+        if self.read_only || settings::file_override(&self.meta, setting) == value {
+            return;
+        }
+        settings::set_file_override(&mut self.meta, setting, value);
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn toggle_settings(&mut self, _: &ToggleSettings, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_settings = !self.show_settings;
+        cx.notify();
+    }
+
+    fn toggle_preview(&mut self, _: &TogglePreview, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_preview = !self.show_preview;
+        cx.notify();
+    }
+
+    /// Describe the alias in plain English, e.g. "`Scores` is a map from strings to 32-bit integers."
+    pub fn explain(&self) -> String {
+        match self.canvas.root_block().and_then(|root| root.to_ast()) {
+            Some(ast) => explain::explain_alias(&self.display_name, &ast),
+            None => "Fill every slot to see an explanation of this type.".to_string(),
+        }
+    }
+
+    /// Write the canvas as an SVG next to the alias file, mirroring the last rendered layout
+    pub fn export_svg(&self) -> Result<PathBuf, String> {
+        let file_path = self.file_path.as_ref().ok_or("Save the alias before exporting it")?;
+        let root = self.canvas.root_block().ok_or("Nothing to export - the canvas is empty")?;
+        let layout = BlockLayout {
+            blocks: self.canvas.block_bounds(),
+            slots: self.canvas.slot_bounds(),
+        };
+        let svg = svg_export::tree_to_svg(root, &layout).ok_or("The canvas has not been drawn yet")?;
+
+        let path = file_path.with_extension("svg");
+        std::fs::write(&path, svg).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        Ok(path)
+    }
+
+    fn export_svg_action(&mut self, _: &ExportSvg, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Err(e) = self.export_svg() {
+            self.set_error(e);
+            cx.notify();
+        }
+    }
+
+    /// Pick another alias file and ask the host to compare this file against it
+    fn compare_with_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(left) = self.file_path.clone() else {
+            self.set_error("Save the alias before comparing it");
+            cx.notify();
+            return;
+        };
+        let chosen = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = chosen.await else {
+                return;
+            };
+            let Some(right) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |_, cx| cx.emit(CompareAliasRequest { left, right }))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Pick a `.rs` file and list its `type` aliases for import
+    fn import_rust_file(&mut self, _: &ImportRustFile, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let chosen = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = chosen.await else {
+                return;
+            };
+            let Some(source) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |editor, cx| {
+                match import::aliases_from_rust_file(&source) {
+                    Ok(result) if result.aliases.is_empty() => {
+                        editor.set_error(format!("No importable type aliases in {:?}", source));
+                    }
+                    Ok(result) => {
+                        editor.rust_import = Some(RustImport {
+                            selected: vec![true; result.aliases.len()],
+                            source,
+                            result,
+                        });
+                    }
+                    Err(e) => editor.set_error(e),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Write the selected aliases from the import bar as asset files, one per type.
+    /// Aliases go next to the current file, or next to the `.rs` file for an unsaved alias.
+    fn confirm_rust_import(&mut self, cx: &mut Context<Self>) {
+        let Some(rust_import) = self.rust_import.take() else {
+            return;
+        };
+
+        let mut created = Vec::new();
+        let mut failed = Vec::new();
+        let mut normalized = Vec::new();
+        for (alias, _) in rust_import
+            .result
+            .aliases
+            .iter()
+            .zip(&rust_import.selected)
+            .filter(|(_, selected)| **selected)
+        {
+            let path = match &self.file_path {
+                Some(file_path) => duplicate_path(file_path, &alias.name),
+                None => rust_import
+                    .source
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join(&alias.name)
+                    .join(project::ALIAS_FILE_NAME),
+            };
+            if self.files.exists(&path) {
+                failed.push(format!("{} ({:?} already exists)", alias.name, path));
+                continue;
+            }
+
+            let mut asset = alias.to_asset(to_snake_case(&alias.name));
+            let (ast, renamed) = self.type_config.normalize_primitives(&asset.ast);
+            asset.ast = ast;
+            for rename in renamed {
+                if !normalized.contains(&rename) {
+                    normalized.push(rename);
+                }
+            }
+            let result = schema::alias_asset_to_json(&asset, &serde_json::Map::new(), self.type_config.json_style)
+                .and_then(|json| self.files.write(&path, &json).map_err(|e| e.to_string()));
+            match result {
+                Ok(()) => {
+                    cx.emit(AliasFileEvent::Created(path.clone()));
+                    created.push(path);
+                }
+                Err(e) => failed.push(format!("{} ({})", alias.name, e)),
+            }
+        }
+
+        if let [only] = created.as_slice() {
+            cx.emit(OpenAliasRequest { path: only.clone() });
+        }
+        self.diagnostics = vec![EditorDiagnostic::info(format!("Imported {} alias(es)", created.len()))];
+        if !normalized.is_empty() {
+            self.diagnostics.push(EditorDiagnostic::PrimitivesNormalized(normalized));
+        }
+        if !failed.is_empty() {
+            self.diagnostics.push(EditorDiagnostic::error(format!("Failed to import:\n{}", failed.join("\n"))));
+        }
+        cx.notify();
+    }
+
+    /// Show the name prompt for duplicating this alias
+    fn duplicate_alias(&mut self, _: &DuplicateAlias, window: &mut Window, cx: &mut Context<Self>) {
+        if self.file_path.is_none() {
+            self.set_error("Save the alias before duplicating it");
+            cx.notify();
+            return;
+        }
+
+        let default_name = format!("{}Copy", self.display_name);
+        let input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("Name for the duplicated alias");
+            state.set_value(&default_name, window, cx);
+            state
+        });
+        self.duplicate_name_input = Some(input);
+        cx.notify();
+    }
+
+    fn cancel_duplicate(&mut self, cx: &mut Context<Self>) {
+        self.duplicate_name_input = None;
+        cx.notify();
+    }
+
+    /// Write a copy of the current alias under the entered name and ask the host to open it.
+    /// The current editor's file and state are left untouched.
+    fn confirm_duplicate(&mut self, cx: &mut Context<Self>) {
+        let Some(input) = &self.duplicate_name_input else {
+            return;
+        };
+        let new_display_name = input.read(cx).value().trim().to_string();
+
+        if !is_valid_type_name(&new_display_name) {
+            self.set_error(format!("\"{}\" is not a valid type name", new_display_name));
+            cx.notify();
+            return;
+        }
+
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+        let new_path = duplicate_path(file_path, &new_display_name);
+        if self.files.exists(&new_path) {
+            self.set_error(format!("{:?} already exists", new_path));
+            cx.notify();
+            return;
+        }
+
+        let mut asset = match self.build_asset() {
+            Ok(asset) => asset,
+            Err(e) => {
+                self.set_error(e);
+                cx.notify();
+                return;
+            }
+        };
+        asset.name = to_snake_case(&new_display_name);
+        asset.display_name = new_display_name;
+
+        let result = schema::alias_asset_to_json(&asset, &self.extra_fields, self.type_config.json_style)
+            .and_then(|json| self.files.write(&new_path, &json).map_err(|e| e.to_string()));
+
+        match result {
+            Ok(()) => {
+                self.duplicate_name_input = None;
+                cx.emit(AliasFileEvent::Created(new_path.clone()));
+                cx.emit(OpenAliasRequest { path: new_path });
+            }
+            Err(e) => {
+                self.set_error(format!("Failed to duplicate: {}", e));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Empty a filled slot. The canvas snapshots the whole tree first, so undo
+    /// brings back the removed subtree with all of its children.
+    fn clear_slot(&mut self, parent_id: BlockId, slot_idx: usize, cx: &mut Context<Self>) {
+        if self.canvas.clear_slot(&parent_id, slot_idx).is_some() {
+            self.selected_slot = Some((parent_id, slot_idx));
+            self.preview_needs_update = true;
+            self.dirty = true;
+            cx.notify();
+        }
+    }
+
+    fn undo(&mut self, _: &Undo, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let snapshot = self.canvas.last_snapshot();
+        if self.canvas.undo() {
+            if self.generics_undo.last().map(|(serial, _)| *serial) == snapshot {
+                if let Some((_, generics)) = self.generics_undo.pop() {
+                    let current = codegen::declared_generics(&self.meta);
+                    for (now, before) in current.iter().zip(&generics).filter(|(now, before)| now != before) {
+                        codegen::rename_generic_bounds(&mut self.meta, now, before);
+                    }
+                    codegen::set_declared_generics(&mut self.meta, &generics);
+                }
+            }
+            self.selected_slot = None;
+            self.preview_needs_update = true;
+            self.dirty = true;
+            cx.notify();
+        }
+    }
+
+    /// Rename a declared generic param and every use of it in the tree, as one undo step
+    pub fn rename_generic_param(&mut self, from: &str, to: &str, cx: &mut Context<Self>) -> Result<usize, EditorDiagnostic> {
+        if self.read_only {
+            return Err(EditorDiagnostic::error("This alias is read-only"));
+        }
+        let to = to.trim();
+        let mut generics = codegen::declared_generics(&self.meta);
+        let Some(index) = generics.iter().position(|param| param == from) else {
+            return Err(EditorDiagnostic::error(format!("`{}` is not a declared generic param", from)));
+        };
+        if to == from {
+            return Ok(0);
+        }
+        if syn::parse_str::<syn::Ident>(to).is_err() {
+            return Err(EditorDiagnostic::error(format!("`{}` is not a valid identifier", to)));
+        }
+        if generics.iter().any(|param| param == to) {
+            return Err(EditorDiagnostic::error(format!("`{}` is already a generic param", to)));
+        }
+        if ui_types_common::PRIMITIVES.contains(&to) {
+            return Err(EditorDiagnostic::error(format!("`{}` is a primitive type", to)));
+        }
+
+        let count = self.canvas.rename_type_param(from, to);
+        if let Some(serial) = self.canvas.last_snapshot() {
+            self.generics_undo.push((serial, generics.clone()));
+        }
+        generics[index] = to.to_string();
+        codegen::set_declared_generics(&mut self.meta, &generics);
+        codegen::rename_generic_bounds(&mut self.meta, from, to);
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+        Ok(count)
+    }
+
+    fn toggle_palette(&mut self, _: &TogglePalette, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        // Open the centered type picker with no target slot
+        cx.emit(self.picker_request(self.selected_slot.clone(), Vec::new()));
+    }
+
+
+
+    /// Add a block to the canvas
+    fn add_block_to_canvas(&mut self, block: TypeBlock, cx: &mut Context<Self>) {
+        if self.canvas.root_block().is_none() {
+            // No root block yet - place as root
+            self.replace_root(block, cx);
+        } else if let Some((parent_id, slot_idx)) = &self.selected_slot {
+            // Slot is selected - fill it
+            if self.canvas.fill_slot(parent_id.clone(), *slot_idx, block) {
+                self.clear_errors();
+                self.selected_slot = None;
+                self.pending_block = None;
+                self.dirty = true;
+            } else {
+                self.set_error("Failed to fill slot");
+            }
+        } else {
+            // Has root but no slot selected - store as pending; the placement banner prompts the user
+            self.pending_block = Some(block);
+            self.clear_errors();
+        }
+        self.preview_needs_update = true;
+        cx.notify();
+    }
+    
+    /// Hide a block's lint warning until the file is reopened
+    fn dismiss_warning(&mut self, block_id: BlockId, cx: &mut Context<Self>) {
+        self.dismissed_warnings.insert(block_id);
+        self.preview_needs_update = true;
+        cx.notify();
+    }
+
+    /// Select a clicked block; clicking a const value also reopens its value field
+    fn select_block(&mut self, block_id: BlockId, window: &mut Window, cx: &mut Context<Self>) {
+        let parent = self.canvas.root_block().and_then(|root| root.parent_of(&block_id));
+        if let Some((parent_id, slot_idx)) = parent.filter(|(parent_id, slot_idx)| self.is_const_slot(parent_id, *slot_idx)) {
+            self.edit_const_arg(parent_id, slot_idx, window, cx);
+        }
+        self.canvas.select_block(Some(block_id));
+        cx.notify();
+    }
+
+    /// Swap a smart pointer or cell for another of its family, keeping the inner type
+    fn replace_constructor(&mut self, block_id: BlockId, name: String, cx: &mut Context<Self>) {
+        if self.canvas.replace_constructor(&block_id, &name) {
+            self.preview_needs_update = true;
+            self.dirty = true;
+            cx.notify();
+        }
+    }
+
+    /// Add an empty tuple element or fn param at `index` and select it for filling
+    fn insert_slot(&mut self, block_id: BlockId, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if self.canvas.insert_slot(&block_id, index) {
+            self.preview_needs_update = true;
+            self.dirty = true;
+            self.select_slot(block_id, index, window, cx);
+            cx.notify();
+        }
+    }
+
+    /// Act on a choice from the empty canvas
+    fn handle_empty_choice(&mut self, choice: EmptyStateChoice, window: &mut Window, cx: &mut Context<Self>) {
+        match choice {
+            EmptyStateChoice::PickRoot => cx.emit(self.picker_request(None, Vec::new())),
+            EmptyStateChoice::Template(index) => {
+                if let Some(template) = examples::templates().get(index) {
+                    self.replace_root(template.to_block(cx), cx);
+                }
+            }
+            EmptyStateChoice::Example(index) => {
+                if let Some(block) = examples::example_block(index) {
+                    self.replace_root(block, cx);
+                }
+            }
+            EmptyStateChoice::ImportRust => self.import_rust_file(&ImportRustFile, window, cx),
+            EmptyStateChoice::Callback => self.build_callback(&BuildCallback, window, cx),
+        }
+    }
+
+    /// Open the callback wizard at its first step
+    fn build_callback(&mut self, _: &BuildCallback, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.callback_wizard = Some(CallbackWizard::Arguments(1));
+        cx.notify();
+    }
+
+    fn set_callback_arguments(&mut self, count: usize, cx: &mut Context<Self>) {
+        if let Some(CallbackWizard::Arguments(arguments)) = &mut self.callback_wizard {
+            *arguments = count.min(MAX_CALLBACK_ARGUMENTS);
+        }
+        cx.notify();
+    }
+
+    /// Make an empty fn pointer with the chosen arity the root and start picking its types
+    fn start_callback_slots(&mut self, cx: &mut Context<Self>) {
+        let Some(CallbackWizard::Arguments(arguments)) = self.callback_wizard else {
+            return;
+        };
+        let fn_ptr = TypeBlock::fn_pointer(arguments);
+        let fn_id = fn_ptr.id().clone();
+        self.callback_wizard = Some(CallbackWizard::Slots { fn_id, arguments, remaining: (0..=arguments).collect() });
+        self.replace_root(fn_ptr, cx);
+        self.pick_callback_slot(cx);
+    }
+
+    /// Open the picker for the wizard's current slot. Waits while the replace bar is
+    /// up, and gives up once the fn pointer has left the canvas.
+    fn pick_callback_slot(&mut self, cx: &mut Context<Self>) {
+        let Some(CallbackWizard::Slots { fn_id, remaining, .. }) = &self.callback_wizard else {
+            return;
+        };
+        let Some(&index) = remaining.first() else {
+            return;
+        };
+        if self.canvas.root_block().and_then(|root| root.find_block(fn_id)).is_none() {
+            if self.pending_root_replacement.is_none() {
+                self.callback_wizard = None;
+                self.set_error("The callback's fn pointer is no longer on the canvas");
+                cx.notify();
+            }
+            return;
+        }
+        let target = (fn_id.clone(), index);
+        self.selected_slot = Some(target.clone());
+        cx.emit(self.picker_request(Some(target), Vec::new()));
+        cx.notify();
+    }
+
+    /// Step the wizard past `filled` when it is the slot being asked for. Returns whether
+    /// the wizard took the step, so guided fill leaves the picker alone.
+    fn advance_callback_wizard(&mut self, filled: Option<&(BlockId, usize)>, cx: &mut Context<Self>) -> bool {
+        let Some(CallbackWizard::Slots { fn_id, remaining, .. }) = &mut self.callback_wizard else {
+            return false;
+        };
+        let Some((parent_id, index)) = filled else {
+            return false;
+        };
+        if parent_id != fn_id || remaining.first() != Some(index) {
+            return false;
+        }
+        remaining.remove(0);
+        if remaining.is_empty() {
+            let fn_id = fn_id.clone();
+            self.callback_wizard = Some(CallbackWizard::Wrap { fn_id });
+            self.selected_slot = None;
+        } else {
+            self.pick_callback_slot(cx);
+        }
+        true
+    }
+
+    /// Leave the return slot empty, making the callback return `()`
+    fn skip_callback_return(&mut self, cx: &mut Context<Self>) {
+        if let Some(CallbackWizard::Slots { fn_id, remaining, .. }) = &self.callback_wizard {
+            if remaining.len() == 1 {
+                self.callback_wizard = Some(CallbackWizard::Wrap { fn_id: fn_id.clone() });
+                self.selected_slot = None;
+            }
+        }
+        cx.notify();
+    }
+
+    /// Close the wizard, first turning the fn pointer into `Box<dyn fn_trait(..) -> R>`
+    /// when a trait is given. The wrap is one undo step.
+    fn finish_callback(&mut self, fn_trait: Option<&str>, cx: &mut Context<Self>) {
+        let Some(CallbackWizard::Wrap { fn_id }) = self.callback_wizard.take() else {
+            return;
+        };
+        let Some(fn_trait) = fn_trait else {
+            cx.notify();
+            return;
+        };
+        let Some(TypeBlock::FnPointer { params, return_type, .. }) =
+            self.canvas.root_block().filter(|root| root.id() == &fn_id).cloned()
+        else {
+            self.set_error("The callback is no longer the root type; wrap it by hand");
+            cx.notify();
+            return;
+        };
+
+        let output_slot = params.len();
+        let mut callable = TypeBlock::constructor(fn_trait, output_slot + 1);
+        for (index, param) in params.into_iter().enumerate() {
+            if let Some(param) = param {
+                callable.set_slot(index, *param);
+            }
+        }
+        callable.set_slot(output_slot, return_type.map(|block| *block).unwrap_or_else(|| TypeBlock::primitive("()")));
+        let mut boxed = TypeBlock::constructor("Box", 1);
+        boxed.set_slot(0, callable);
+
+        self.canvas.set_root_block(Some(boxed));
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    /// Make `block` the root. An existing tree is only discarded after the user
+    /// confirms in the replace bar; every root change should go through here.
+    fn replace_root(&mut self, block: TypeBlock, cx: &mut Context<Self>) {
+        if self.canvas.root_block().is_some() {
+            self.pending_root_replacement = Some(block);
+            cx.notify();
+            return;
+        }
+        self.canvas.set_root_block(Some(block));
+        self.dirty = true;
+        self.clear_errors();
+        self.pending_block = None;
+        self.selected_slot = None;
+        self.preview_needs_update = true;
+        cx.notify();
+    }
+
+    /// Discard the current tree for the root waiting in the replace bar. Undo brings it back.
+    fn confirm_root_replacement(&mut self, cx: &mut Context<Self>) {
+        let Some(block) = self.pending_root_replacement.take() else {
+            return;
+        };
+        self.canvas.set_root_block(Some(block));
+        self.dirty = true;
+        self.clear_errors();
+        self.pending_block = None;
+        self.selected_slot = None;
+        self.preview_needs_update = true;
+        self.pick_callback_slot(cx);
+        cx.notify();
+    }
+
+    /// Swap the selected primitive for the next one in its group (`i32` → `i64`)
+    fn cycle_primitive(&mut self, _: &CyclePrimitive, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            cx.propagate();
+            return;
+        }
+        let Some(block_id) = self.canvas.selected_block().cloned() else {
+            cx.propagate();
+            return;
+        };
+        if self.canvas.cycle_primitive(&block_id) {
+            self.preview_needs_update = true;
+            self.dirty = true;
+            cx.notify();
+        }
+    }
+
+    /// Drop the block waiting to be placed
+    fn cancel_placement(&mut self, _: &CancelPlacement, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_root_replacement.take().is_some() {
+            cx.notify();
+            return;
+        }
+        if self.pending_block.take().is_none() {
+            if !self.selected_slots.is_empty() {
+                // Drop the bulk selection, keeping the slot clicked last
+                self.selected_slots.clear();
+                cx.notify();
+                return;
+            }
+            if !self.setting(EditorSetting::OpenPickerOnClick, cx) && self.selected_slot.take().is_some() {
+                // Deselect the slot chosen in select-only mode
+                cx.notify();
+                return;
+            }
+            // Nothing pending - let Escape reach whoever else handles it
+            cx.propagate();
+            return;
+        }
+        cx.notify();
+    }
+
+    /// Canvas slot clicks, handed to `select_slot` as each click is dispatched
+    fn slot_click_handler(editor: WeakEntity<Self>) -> SlotHandler {
+        Arc::new(move |block_id: BlockId, slot_idx: usize, window: &mut Window, cx: &mut App| {
+            editor.update(cx, |this, cx| this.select_slot(block_id, slot_idx, window, cx)).ok();
+        })
+    }
+
+    /// Select a slot to fill. Depending on the setting this also opens the type picker;
+    /// const slots always open their value field.
+    fn select_slot(&mut self, parent_id: BlockId, slot_idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if window.modifiers().shift && self.pending_block.is_none() && self.is_bulk_fillable(&parent_id, slot_idx) {
+            self.toggle_bulk_slot(parent_id, slot_idx, cx);
+            return;
+        }
+        self.selected_slots.clear();
+        self.selected_slot = Some((parent_id.clone(), slot_idx));
+        
+        if self.is_const_slot(&parent_id, slot_idx) {
+            self.edit_const_arg(parent_id, slot_idx, window, cx);
+        } else if let Some(block) = self.pending_block.take() {
+            // If we have a pending block, fill the slot immediately
+            self.add_block_to_canvas(block, cx);
+        } else if self.setting(EditorSetting::OpenPickerOnClick, cx) {
+            // Open the centered type picker for this slot
+            cx.emit(self.picker_request(Some((parent_id, slot_idx)), Vec::new()));
+        } else {
+            // Select only; the slot banner says how to fill it
+            cx.notify();
+        }
+    }
+
+    /// Whether a slot can join a bulk fill: empty and not a const slot
+    fn is_bulk_fillable(&self, parent_id: &BlockId, slot_idx: usize) -> bool {
+        !self.is_const_slot(parent_id, slot_idx)
+            && self
+                .canvas
+                .root_block()
+                .and_then(|root| root.find_block(parent_id))
+                .is_some_and(|parent| slot_idx < parent.slot_count() && parent.get_slot(slot_idx).is_none())
+    }
+
+    /// Add an empty slot to the bulk selection, or remove it if already there
+    fn toggle_bulk_slot(&mut self, parent_id: BlockId, slot_idx: usize, cx: &mut Context<Self>) {
+        let slot = (parent_id, slot_idx);
+        if self.selected_slots.is_empty() {
+            // The plainly selected slot starts the bulk selection
+            self.selected_slots.extend(
+                self.selected_slot
+                    .clone()
+                    .filter(|(parent_id, slot_idx)| self.is_bulk_fillable(parent_id, *slot_idx)),
+            );
+        }
+        if let Some(index) = self.selected_slots.iter().position(|selected| *selected == slot) {
+            self.selected_slots.remove(index);
+            self.selected_slot = self.selected_slots.last().cloned();
+        } else {
+            self.selected_slots.push(slot.clone());
+            self.selected_slot = Some(slot);
+        }
+        cx.notify();
+    }
+
+    /// Slots a picker confirmation for `target_slot` fills: the bulk selection when the
+    /// target is part of it, otherwise just the target
+    fn fill_targets(&self, target_slot: Option<&(BlockId, usize)>) -> Vec<(BlockId, usize)> {
+        match target_slot {
+            Some(slot) if self.selected_slots.contains(slot) => self.selected_slots.clone(),
+            Some(slot) => vec![slot.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// What a slot accepts, if its constructor constrains it
+    fn slot_constraint(&self, parent_id: &BlockId, slot_idx: usize) -> Option<project::SlotConstraint> {
+        match self.canvas.root_block()?.find_block(parent_id)? {
+            TypeBlock::Constructor { name, .. } => self.type_config.slot_constraint(name, slot_idx),
+            _ => None,
+        }
+    }
+
+    /// Picker request for a slot, carrying what the slot accepts
+    fn picker_request(&self, target_slot: Option<(BlockId, usize)>, slot_chain: Vec<(BlockId, usize)>) -> ShowTypePickerRequest {
+        let slot_constraint = target_slot
+            .as_ref()
+            .and_then(|(parent_id, slot_idx)| self.slot_constraint(parent_id, *slot_idx));
+        ShowTypePickerRequest {
+            target_slots: self.fill_targets(target_slot.as_ref()),
+            target_slot,
+            slot_chain,
+            slot_constraint,
+            type_config: self.type_config.clone(),
+        }
+    }
+
+    /// Select a slot without opening the picker, e.g. from the breadcrumb
+    fn focus_slot(&mut self, parent_id: BlockId, slot_idx: usize, cx: &mut Context<Self>) {
+        self.selected_slot = Some((parent_id, slot_idx));
+        cx.notify();
+    }
+
+    /// Settings panel: each toggle's effective value, and whether this file overrides it
+    fn render_settings_panel(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+        let row = |label: String, value: String, overridden: bool| {
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(div().w(px(220.0)).text_sm().text_color(cx.theme().foreground).child(label))
+                .child(
+                    div()
+                        .w(px(160.0))
+                        .text_xs()
+                        .text_color(if overridden { cx.theme().foreground } else { cx.theme().muted_foreground })
+                        .child(if overridden { format!("{} (this file)", value) } else { format!("{} (default)", value) })
+                )
+        };
+        let variant = |selected: bool| if selected { ButtonVariant::Secondary } else { ButtonVariant::Ghost };
+
+        let panel = v_flex()
+            .w_full()
+            .px_4()
+            .py_2()
+            .gap_1()
+            .bg(cx.theme().secondary.opacity(0.3))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .child(if self.read_only {
+                                "⚙ Settings - overrides can't be changed while the file is read-only"
+                            } else {
+                                "⚙ Settings - overrides are saved in this file, everything else follows the defaults"
+                            })
+                    )
+                    .child(
+                        Button::new("close_settings_btn")
+                            .with_variant(ButtonVariant::Ghost)
+                            .child("Close")
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.show_settings = false;
+                                cx.notify();
+                            }))
+                    )
+            )
+            .children(EditorSetting::ALL.iter().enumerate().map(|(i, &setting)| {
+                let file_value = settings::file_override(&self.meta, setting);
+                let value = if self.setting(setting, cx) { "On" } else { "Off" };
+                row(setting.label().to_string(), value.to_string(), file_value.is_some())
+                    .when(!self.read_only, |this| this
+                        .child(
+                            Button::new(("setting_on_btn", i))
+                                .with_variant(variant(file_value == Some(true)))
+                                .child("On")
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_file_setting(setting, Some(true), cx);
+                                }))
+                        )
+                        .child(
+                            Button::new(("setting_off_btn", i))
+                                .with_variant(variant(file_value == Some(false)))
+                                .child("Off")
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_file_setting(setting, Some(false), cx);
+                                }))
+                        )
+                        .child(
+                            Button::new(("setting_default_btn", i))
+                                .with_variant(variant(file_value.is_none()))
+                                .child("Default")
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_file_setting(setting, None, cx);
+                                }))
+                        )
+                    )
+            }))
+            .child({
+                let file_value = self.file_layout_direction();
+                let value = match file_value.unwrap_or(EditorSettings::global(cx).layout_direction) {
+                    LayoutDirection::TopDown => "Top-down",
+                    LayoutDirection::LeftToRight => "Left-to-right",
+                };
+                row("Layout".to_string(), value.to_string(), file_value.is_some())
+                    .when(!self.read_only, |this| this
+                        .child(
+                            Button::new("settings_layout_top_down_btn")
+                                .with_variant(variant(file_value == Some(LayoutDirection::TopDown)))
+                                .child("Top-down")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.set_file_layout_direction(Some(LayoutDirection::TopDown), cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("settings_layout_left_to_right_btn")
+                                .with_variant(variant(file_value == Some(LayoutDirection::LeftToRight)))
+                                .child("Left-to-right")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.set_file_layout_direction(Some(LayoutDirection::LeftToRight), cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("settings_layout_default_btn")
+                                .with_variant(variant(file_value.is_none()))
+                                .child("Default")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.set_file_layout_direction(None, cx);
+                                }))
+                        )
+                    )
+            });
+        Some(panel)
+    }
+
+    /// Bar for the callback wizard's current step
+    fn render_callback_wizard(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let wizard = self.callback_wizard.as_ref()?;
+        let bar = h_flex()
+            .w_full()
+            .px_4()
+            .py_2()
+            .gap_2()
+            .items_center()
+            .bg(cx.theme().accent.opacity(0.15))
+            .border_b_1()
+            .border_color(cx.theme().border);
+        let foreground = cx.theme().foreground;
+        let text = |text: String| div().flex_1().text_sm().text_color(foreground).child(text);
+        let cancel = Button::new("cancel_callback_btn")
+            .with_variant(ButtonVariant::Ghost)
+            .child("Cancel")
+            .on_click(cx.listener(|this, _, _window, cx| {
+                this.callback_wizard = None;
+                cx.notify();
+            }));
+
+        let bar = match wizard {
+            CallbackWizard::Arguments(arguments) => {
+                let arguments = *arguments;
+                bar.child(text(if self.canvas.root_block().is_some() {
+                    "🪝 How many arguments does the callback take? It will replace the current type.".to_string()
+                } else {
+                    "🪝 How many arguments does the callback take?".to_string()
+                }))
+                .child(
+                    Button::new("callback_fewer_btn")
+                        .with_variant(ButtonVariant::Ghost)
+                        .child("−")
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.set_callback_arguments(arguments.saturating_sub(1), cx);
+                        }))
+                )
+                .child(div().text_sm().font_bold().child(arguments.to_string()))
+                .child(
+                    Button::new("callback_more_btn")
+                        .with_variant(ButtonVariant::Ghost)
+                        .child("+")
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.set_callback_arguments(arguments + 1, cx);
+                        }))
+                )
+                .child(
+                    Button::new("callback_next_btn")
+                        .with_variant(ButtonVariant::Primary)
+                        .child("Next")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.start_callback_slots(cx);
+                        }))
+                )
+            }
+            CallbackWizard::Slots { arguments, remaining, .. } => {
+                let index = remaining.first().copied().unwrap_or(*arguments);
+                let step = arguments + 2 - remaining.len();
+                let slot = if index < *arguments {
+                    format!("argument {}", index + 1)
+                } else {
+                    "the return type".to_string()
+                };
+                bar.child(text(format!("🪝 Step {} of {}: pick {}", step, arguments + 1, slot)))
+                    .child(
+                        Button::new("callback_pick_btn")
+                            .with_variant(ButtonVariant::Primary)
+                            .child("Pick…")
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.pick_callback_slot(cx);
+                            }))
+                    )
+                    .when(remaining.len() == 1, |this| this.child(
+                        Button::new("callback_no_return_btn")
+                            .with_variant(ButtonVariant::Secondary)
+                            .child("Returns nothing")
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.skip_callback_return(cx);
+                            }))
+                    ))
+            }
+            CallbackWizard::Wrap { .. } => bar
+                .child(text("🪝 Callback ready. Box it as a trait object so closures can be stored too?".to_string()))
+                .child(
+                    Button::new("callback_box_fn_btn")
+                        .with_variant(ButtonVariant::Primary)
+                        .child("Box<dyn Fn>")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.finish_callback(Some("dyn Fn"), cx);
+                        }))
+                )
+                .child(
+                    Button::new("callback_box_fn_mut_btn")
+                        .with_variant(ButtonVariant::Secondary)
+                        .child("Box<dyn FnMut>")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.finish_callback(Some("dyn FnMut"), cx);
+                        }))
+                )
+                .child(
+                    Button::new("callback_keep_fn_btn")
+                        .with_variant(ButtonVariant::Ghost)
+                        .child("Keep fn pointer")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.finish_callback(None, cx);
+                        }))
+                ),
+        };
+        Some(bar.when(!matches!(wizard, CallbackWizard::Wrap { .. }), |this| this.child(cancel)))
+    }
+
+    /// Pill with the alias's stability marker from the meta, e.g. `unstable since 1.2.0`
+    fn render_stability_badge(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let stability = codegen::Stability::from_meta(&self.meta);
+        let label = stability.label()?;
+        let color = match stability.level.as_deref() {
+            Some("deprecated") => cx.theme().danger,
+            Some("unstable" | "experimental") => cx.theme().warning,
+            _ => cx.theme().accent,
+        };
+        Some(
+            div()
+                .px_2()
+                .py_0p5()
+                .rounded(px(8.0))
+                .border_1()
+                .border_color(color)
+                .bg(color.opacity(0.15))
+                .text_xs()
+                .text_color(cx.theme().foreground)
+                .child(label)
+        )
+    }
+
+    /// `HashMap › value › Vec › [empty]` for the selected slot. Each block name selects the
+    /// slot holding it and each slot label selects that slot.
+    fn render_slot_breadcrumb(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let (parent_id, slot_idx) = self.selected_slot.clone()?;
+        let path = self.canvas.slot_path(&parent_id, slot_idx);
+        if path.is_empty() {
+            return None;
+        }
+        let target = self
+            .canvas
+            .root_block()
+            .and_then(|root| root.find_block(&parent_id))
+            .and_then(|parent| parent.get_slot(slot_idx))
+            .map(TypeBlock::display_name)
+            .unwrap_or_else(|| "[empty]".to_string());
+
+        let muted = cx.theme().muted_foreground;
+        let separator = || div().text_color(muted).child("›");
+        let segment = |id: SharedString, text: String, slot: Option<(BlockId, usize)>, cx: &mut Context<Self>| {
+            div()
+                .id(id)
+                .px_1()
+                .rounded(px(4.0))
+                .text_color(cx.theme().foreground)
+                .when_some(slot, |this, (parent_id, slot_idx)| {
+                    this.cursor_pointer()
+                        .hover(|this| this.bg(cx.theme().accent.opacity(0.2)))
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.focus_slot(parent_id.clone(), slot_idx, cx);
+                        }))
+                })
+                .child(text)
+        };
+
+        let mut crumbs = h_flex().gap_1().items_center().text_xs();
+        let mut holder: Option<(BlockId, usize)> = None;
+        for (i, SlotPathSegment { block_id, block_name, slot_index, slot_label, has_siblings }) in path.into_iter().enumerate() {
+            crumbs = crumbs.child(segment(format!("crumb-block-{}", i).into(), block_name, holder.take(), cx));
+            if has_siblings {
+                crumbs = crumbs
+                    .child(separator())
+                    .child(segment(format!("crumb-slot-{}", i).into(), slot_label, Some((block_id.clone(), slot_index)), cx));
+            }
+            crumbs = crumbs.child(separator());
+            holder = Some((block_id, slot_index));
+        }
+        Some(crumbs.child(
+            div()
+                .px_1()
+                .font_semibold()
+                .text_color(cx.theme().accent)
+                .child(target)
+        ))
+    }
+
+    fn focus_next(&mut self, _: &FocusNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.move_focus(1);
+        cx.notify();
+    }
+
+    fn focus_previous(&mut self, _: &FocusPrevious, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.move_focus(-1);
+        cx.notify();
+    }
+
+    /// Act on the focused stop as a click would: empty slots open for filling, blocks are selected
+    fn activate_focused(&mut self, _: &ActivateFocused, window: &mut Window, cx: &mut Context<Self>) {
+        match self.canvas.focused().cloned() {
+            Some(FocusTarget::Slot(parent_id, slot_idx)) if !self.read_only => {
+                self.select_slot(parent_id, slot_idx, window, cx)
+            }
+            Some(FocusTarget::Block(block_id)) if !self.read_only => self.select_block(block_id, window, cx),
+            _ => cx.propagate(),
+        }
+    }
+
+    /// Label of the focused block or slot, for the host to pass on to assistive tech
+    pub fn focused_label(&self) -> Option<String> {
+        self.canvas.focused_label()
+    }
+
+    /// Status line announcing the focused block or slot while tabbing through the canvas
+    fn render_focus_status(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let label = self.canvas.focused_label()?;
+        Some(
+            h_flex()
+                .w_full()
+                .px_2()
+                .gap_2()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child("Focus:")
+                .child(div().text_color(cx.theme().foreground).child(label))
+                .child(div().flex_1())
+                .child("Tab / Shift+Tab to move, Enter to activate")
+        )
+    }
+
+    fn toggle_picker_on_click(&mut self, _: &TogglePickerOnClick, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_setting(EditorSetting::OpenPickerOnClick, window, cx);
+    }
+
+    /// Fill the selected slot, or the empty canvas, with a Rust type from the clipboard
+    fn paste_type(&mut self, _: &PasteType, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            cx.propagate();
+            return;
+        }
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            cx.propagate();
+            return;
+        };
+        let text = text.trim();
+        let parsed = if text.starts_with('{') {
+            self.ast_from_json(text)
+        } else if text.starts_with(share::SHARE_PREFIX) {
+            self.ast_from_share_string(text)
+        } else {
+            import::type_from_rust(text).map(|ast| self.normalize_primitives(&ast))
+        };
+        match parsed {
+            Ok(ast) => self.add_block_to_canvas(TypeBlock::from_ast(&ast), cx),
+            Err(e) => {
+                self.set_error(format!("Clipboard does not hold a type: {}", e));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Parse a `TypeAstNode` JSON fragment against this alias's generics and the project types
+    fn ast_from_json(&mut self, json: &str) -> Result<TypeAstNode, String> {
+        let (ast, renamed) = import::ast_from_json(json, &self.type_config, &codegen::declared_generics(&self.meta))?;
+        if !renamed.is_empty() {
+            self.set_warning(EditorDiagnostic::PrimitivesNormalized(renamed));
+        }
+        Ok(ast)
+    }
+
+    /// Decode a share string and check its types like imported AST JSON
+    fn ast_from_share_string(&mut self, text: &str) -> Result<TypeAstNode, String> {
+        let ast = share::decode_share_string(text)?;
+        let (ast, renamed) = import::check_imported_ast(&ast, &self.type_config, &codegen::declared_generics(&self.meta))?;
+        if !renamed.is_empty() {
+            self.set_warning(EditorDiagnostic::PrimitivesNormalized(renamed));
+        }
+        Ok(ast)
+    }
+
+    /// Copy the finished tree to the clipboard as a share string
+    fn copy_share_link(&mut self, _: &CopyShareLink, _window: &mut Window, cx: &mut Context<Self>) {
+        match self.canvas.root_block().and_then(TypeBlock::to_ast) {
+            Some(ast) => {
+                cx.write_to_clipboard(ClipboardItem::new_string(share::encode_share_string(&ast)));
+                self.set_info("Share link copied");
+            }
+            None => self.set_warning(EditorDiagnostic::warning("Fill every required slot before sharing")),
+        }
+        cx.notify();
+    }
+
+    /// Load a share string from the clipboard as the whole tree, asking before replacing
+    /// a non-empty canvas. A bad string leaves the canvas as it was.
+    fn paste_share_link(&mut self, _: &PasteShareLink, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            self.set_error("Clipboard is empty");
+            cx.notify();
+            return;
+        };
+        match self.ast_from_share_string(&text) {
+            Ok(ast) => self.replace_root(TypeBlock::from_ast(&ast), cx),
+            Err(e) => {
+                self.set_error(format!("Could not load share link: {}", e));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Copy the tree to the clipboard in the text DSL, empty slots included as `_`
+    fn copy_dsl(&mut self, _: &CopyDsl, _window: &mut Window, cx: &mut Context<Self>) {
+        match self.canvas.root_block() {
+            Some(root) => {
+                cx.write_to_clipboard(ClipboardItem::new_string(dsl::to_dsl(&root.to_ast_with_holes())));
+                self.set_info("Type copied as DSL");
+            }
+            None => self.set_warning(EditorDiagnostic::warning("Nothing to copy - the canvas is empty")),
+        }
+        cx.notify();
+    }
+
+    /// Load the whole tree from DSL text on the clipboard, asking before replacing a
+    /// non-empty canvas. Text that does not parse leaves the canvas as it was.
+    fn paste_dsl(&mut self, _: &PasteDsl, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            self.set_error("Clipboard is empty");
+            cx.notify();
+            return;
+        };
+        let parsed = dsl::from_dsl(&text).and_then(|ast| {
+            import::check_imported_ast(&ast, &self.type_config, &codegen::declared_generics(&self.meta))
+        });
+        match parsed {
+            Ok((ast, renamed)) => {
+                if !renamed.is_empty() {
+                    self.set_warning(EditorDiagnostic::PrimitivesNormalized(renamed));
+                }
+                self.replace_root(TypeBlock::from_ast(&ast), cx);
+            }
+            Err(e) => {
+                self.set_error(format!("Could not load DSL: {}", e));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Apply the project's primitive name table to an imported type, reporting any renames
+    fn normalize_primitives(&mut self, ast: &TypeAstNode) -> TypeAstNode {
+        let (ast, renamed) = self.type_config.normalize_primitives(ast);
+        if !renamed.is_empty() {
+            self.set_warning(EditorDiagnostic::PrimitivesNormalized(renamed));
+        }
+        ast
+    }
+
+    /// Load a `TypeAstNode` JSON file into the selected slot or the empty canvas.
+    /// Pasting AST JSON does the same from the clipboard.
+    fn import_ast_json(&mut self, _: &ImportAstJson, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let chosen = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = chosen.await else {
+                return;
+            };
+            let Some(source) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |editor, cx| {
+                let parsed = std::fs::read_to_string(&source)
+                    .map_err(|e| format!("Failed to read {:?}: {}", source, e))
+                    .and_then(|json| editor.ast_from_json(&json));
+                match parsed {
+                    Ok(ast) => editor.add_block_to_canvas(TypeBlock::from_ast(&ast), cx),
+                    Err(e) => editor.set_error(e),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Fill the selected slot, or the empty canvas, with the type last added from the picker
+    fn repeat_last_type(&mut self, _: &RepeatLastType, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            cx.propagate();
+            return;
+        }
+        let Some(item) = self.last_picked.clone() else {
+            cx.propagate();
+            return;
+        };
+        if self.selected_slots.is_empty() {
+            self.add_block_to_canvas(item.to_block(cx), cx);
+        } else {
+            self.add_type_from_picker(&item, self.fill_targets(self.selected_slot.as_ref()), cx);
+        }
+    }
+    
+    fn is_const_slot(&self, parent_id: &BlockId, slot_idx: usize) -> bool {
+        let mut is_const = false;
+        if let Some(root) = self.canvas.root_block() {
+            root.walk(&mut |block| {
+                if block.id() == parent_id {
+                    is_const = block.is_const_slot(slot_idx);
+                }
+            });
+        }
+        is_const
+    }
+
+    /// Open the value field for a const slot, pre-filled with its current value
+    fn edit_const_arg(&mut self, parent_id: BlockId, slot_idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let mut current = String::new();
+        if let Some(root) = self.canvas.root_block() {
+            root.walk(&mut |block| {
+                if block.id() == &parent_id {
+                    if let Some(TypeBlock::ConstArg { expr, .. }) = block.get_slot(slot_idx) {
+                        current = expr.clone();
+                    }
+                }
+            });
+        }
+        let input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("Const value, e.g. 16 or N");
+            input.set_value(&current, window, cx);
+            input
+        });
+        self.const_arg_input = Some((parent_id, slot_idx, input));
+        cx.notify();
+    }
+
+    /// Put the value field's expression in its const slot
+    fn apply_const_arg(&mut self, cx: &mut Context<Self>) {
+        let Some((parent_id, slot_idx, input)) = self.const_arg_input.clone() else {
+            return;
+        };
+        let expr = input.read(cx).value().trim().to_string();
+        if expr.is_empty() {
+            self.set_error("Enter a const value");
+        } else if syn::parse_str::<syn::Expr>(&expr).is_err() {
+            self.set_error(format!("`{}` is not a valid const expression", expr));
+        } else if self.canvas.fill_slot(parent_id, slot_idx, TypeBlock::const_arg(expr)) {
+            self.const_arg_input = None;
+            self.selected_slot = None;
+            self.clear_errors();
+            self.preview_needs_update = true;
+            self.dirty = true;
+        } else {
+            self.set_error("Failed to fill slot");
+        }
+        cx.notify();
+    }
+
+    /// Open the name field for a constructor's lifetime param, pre-filled with its name
+    fn edit_lifetime(&mut self, block_id: BlockId, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let mut current = String::new();
+        if let Some(root) = self.canvas.root_block() {
+            root.walk(&mut |block| {
+                if let TypeBlock::Constructor { id, lifetimes, .. } = block {
+                    if id == &block_id {
+                        current = lifetimes.get(index).map(|l| format!("'{}", l)).unwrap_or_default();
+                    }
+                }
+            });
+        }
+        let input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("Lifetime, e.g. 'a or 'static");
+            input.set_value(&current, window, cx);
+            input
+        });
+        self.lifetime_input = Some((block_id, index, input));
+        cx.notify();
+    }
+
+    /// Rename the lifetime param to the name field's value
+    fn apply_lifetime(&mut self, cx: &mut Context<Self>) {
+        let Some((block_id, index, input)) = self.lifetime_input.clone() else {
+            return;
+        };
+        let name = input.read(cx).value().trim().trim_start_matches('\'').to_string();
+        if name.is_empty() {
+            self.set_error("Enter a lifetime name");
+        } else if syn::parse_str::<syn::Lifetime>(&format!("'{}", name)).is_err() {
+            self.set_error(format!("`'{}` is not a valid lifetime", name));
+        } else if self.canvas.set_lifetime(&block_id, index, &name) {
+            self.lifetime_input = None;
+            self.clear_errors();
+            self.preview_needs_update = true;
+            self.dirty = true;
+        } else {
+            self.set_error("Failed to rename the lifetime");
+        }
+        cx.notify();
+    }
+
+    /// Place a type confirmed in the picker into each of `target_slots`, each slot getting
+    /// its own block, or onto the canvas when there are none. Slots whose constraint rejects
+    /// the type are left empty and reported.
+    pub fn add_type_from_picker(&mut self, type_item: &crate::TypeItem, target_slots: Vec<(BlockId, usize)>, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.last_picked = Some(type_item.clone());
+        let target_slot_filled = target_slots.last().cloned();
+        
+        if target_slots.is_empty() {
+            // No slot specified - add to canvas
+            self.add_block_to_canvas(type_item.to_block(cx), cx);
+        } else {
+            let mut accepted = Vec::new();
+            let mut rejected = Vec::new();
+            for (parent_id, slot_idx) in target_slots {
+                match self.slot_constraint(&parent_id, slot_idx).filter(|c| !type_item.fits(c)) {
+                    Some(constraint) => rejected.push((parent_id, slot_idx, constraint)),
+                    None => accepted.push((parent_id, slot_idx)),
+                }
+            }
+            let filled = self.canvas.fill_slots(&accepted, || type_item.to_block(cx));
+            self.selected_slots.clear();
+            if !filled.is_empty() {
+                self.selected_slot = None;
+                self.dirty = true;
+            }
+
+            self.clear_errors();
+            for (parent_id, slot_idx, constraint) in rejected {
+                let constructor = match self.canvas.root_block().and_then(|root| root.find_block(&parent_id)) {
+                    Some(TypeBlock::Constructor { name, .. }) => name.clone(),
+                    _ => continue,
+                };
+                self.diagnostics.push(EditorDiagnostic::SlotConstraintViolated {
+                    constructor,
+                    slot: slot_idx,
+                    found: type_item.name().to_string(),
+                    expected: constraint.describe(),
+                });
+            }
+            if filled.len() < accepted.len() {
+                self.diagnostics.push(EditorDiagnostic::error("Failed to fill slot"));
+            }
+        }
+        self.preview_needs_update = true;
+        if !self.advance_callback_wizard(target_slot_filled.as_ref(), cx) {
+            self.continue_fill(target_slot_filled, cx);
+        }
+        cx.notify();
+    }
+
+    /// In guided-fill mode, select the next empty slot and reopen the picker for it
+    fn continue_fill(&mut self, filled: Option<(BlockId, usize)>, cx: &mut Context<Self>) {
+        if !self.continue_filling || self.has_errors() {
+            self.fill_chain.clear();
+            return;
+        }
+
+        match self.canvas.root_block().and_then(TypeBlock::first_empty_slot) {
+            Some(next) if self.is_const_slot(&next.0, next.1) => {
+                // Const values are typed into the value field, which the picker can't fill
+                self.fill_chain.clear();
+                self.selected_slot = Some(next);
+            }
+            Some(next) => {
+                self.fill_chain.extend(filled);
+                self.selected_slot = Some(next.clone());
+                cx.emit(self.picker_request(Some(next), self.fill_chain.clone()));
+            }
+            None => self.fill_chain.clear(),
+        }
+    }
+
+    fn set_continue_filling(&mut self, continue_filling: bool, cx: &mut Context<Self>) {
+        self.continue_filling = continue_filling;
+        self.fill_chain.clear();
+        cx.notify();
+    }
+    
+    /// Enable or disable the duplicate-param lint
+    pub fn set_lint_duplicate_params(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.lint_duplicate_params = enabled;
+        self.preview_needs_update = true;
+        cx.notify();
+    }
+
+    /// Set or clear the nesting depth limit, overriding the project config
+    pub fn set_max_nesting_depth(&mut self, limit: Option<usize>, cx: &mut Context<Self>) {
+        self.max_nesting_depth = limit;
+        self.preview_needs_update = true;
+        cx.notify();
+    }
+
+    /// Non-blocking lint warnings for blocks in the current tree
+    fn block_warnings(&self) -> HashMap<BlockId, String> {
+        let mut warnings = HashMap::new();
+        let Some(root) = self.canvas.root_block() else {
+            return warnings;
+        };
+
+        if self.lint_duplicate_params {
+            let mut flagged = Vec::new();
+            root.find_duplicate_params(&mut flagged);
+            for id in flagged {
+                warnings.insert(id, "identical params".to_string());
+            }
+        }
+
+        if let Some(limit) = self.max_nesting_depth {
+            // Badge the first constructor past the limit
+            let stats = self.canvas.stats();
+            if let Some((id, _)) = stats.deepest_path.get(limit) {
+                warnings.insert(id.clone(), format!("nested {} deep", stats.max_depth));
+            }
+        }
+
+        for finding in placement::check_placements(root) {
+            warnings.insert(finding.block, finding.rule.badge.to_string());
+        }
+
+        if let Some(index) = &self.alias_index {
+            root.walk(&mut |block| {
+                if let TypeBlock::AliasRef { id, alias, .. } = block {
+                    if !index.contains(alias) && *alias != self.display_name {
+                        warnings.insert(id.clone(), "unresolved alias".to_string());
+                    }
+                }
+            });
+        }
+
+        warnings.retain(|id, _| !self.dismissed_warnings.contains(id));
+        warnings
+    }
+
+    /// Open or close the find/replace bar
+    fn toggle_find_replace(&mut self, _: &FindReplace, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        if self.replace_inputs.take().is_none() {
+            let find = cx.new(|cx| InputState::new(window, cx).placeholder("Find type, e.g. String"));
+            let replace = cx.new(|cx| InputState::new(window, cx).placeholder("Replace with, e.g. Arc<str>"));
+            self.replace_inputs = Some((find, replace));
+        }
+        self.replace_status = None;
+        cx.notify();
+    }
+
+    /// Parse the find and replace inputs as Rust types
+    fn replace_patterns(&self, cx: &App) -> Result<(TypeAstNode, TypeAstNode), String> {
+        let (find, replace) = self.replace_inputs.as_ref().ok_or("Find/replace is not open")?;
+        let pattern = import::type_from_rust(&find.read(cx).value())?;
+        let replacement = import::type_from_rust(&replace.read(cx).value())?;
+        Ok((pattern, replacement))
+    }
+
+    /// Show or hide the flatten-tuples bar with a preview of both flatten depths
+    fn toggle_flatten_tuples(&mut self, _: &FlattenTuples, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        if self.flatten_preview.take().is_none() {
+            match self.canvas.root_block().and_then(|root| root.to_ast()) {
+                Some(ast) => {
+                    let render = |all_levels| {
+                        codegen::ast_to_rust_string(&type_ast::flatten_tuples(&ast, all_levels))
+                    };
+                    self.flatten_preview = Some((render(false), render(true)));
+                }
+                None => {
+                    self.set_error("Complete the type before flattening tuples");
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    fn apply_flatten_tuples(&mut self, all_levels: bool, cx: &mut Context<Self>) {
+        self.flatten_preview = None;
+        let Some(ast) = self.canvas.root_block().and_then(|root| root.to_ast()) else {
+            return;
+        };
+
+        let flat = type_ast::flatten_tuples(&ast, all_levels);
+        if type_ast::structural_eq(&ast, &flat) {
+            self.set_warning(EditorDiagnostic::warning("No nested tuples to flatten"));
+        } else {
+            self.canvas.set_root_block(Some(TypeBlock::from_ast(&flat)));
+            self.preview_needs_update = true;
+            self.dirty = true;
+        }
+        cx.notify();
+    }
+
+    /// Show what a replacement would produce without changing the canvas
+    fn preview_replace(&mut self, cx: &mut Context<Self>) {
+        self.replace_status = Some(match self.replace_patterns(cx) {
+            Ok((pattern, replacement)) => match self.canvas.root_block() {
+                Some(root) => {
+                    let mut scratch = BlockCanvas::with_root(root.clone());
+                    let count = scratch.replace_matching(&pattern, &replacement);
+                    let result = scratch
+                        .root_block()
+                        .and_then(|root| root.to_ast())
+                        .map(|ast| codegen::ast_to_rust_string(&ast))
+                        .unwrap_or_else(|| "(incomplete type)".to_string());
+                    format!("{} match(es) → {}", count, result)
+                }
+                None => "Canvas is empty".to_string(),
+            },
+            Err(e) => e,
+        });
+        cx.notify();
+    }
+
+    /// Replace every match in the canvas
+    fn apply_replace(&mut self, cx: &mut Context<Self>) {
+        self.replace_status = Some(match self.replace_patterns(cx) {
+            Ok((pattern, replacement)) => {
+                let count = self.canvas.replace_matching(&pattern, &replacement);
+                self.preview_needs_update = true;
+                self.dirty |= count > 0;
+                format!("Replaced {} occurrence(s)", count)
+            }
+            Err(e) => e,
+        });
+        cx.notify();
+    }
+
+    /// Update the preview input with current code
+    fn update_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.preview_needs_update = false;
+        let diagnostics = self.validate();
+        self.validity = if diagnostics.iter().any(EditorDiagnostic::is_error) {
+            Some(Severity::Error)
+        } else if diagnostics.iter().any(EditorDiagnostic::is_warning) {
+            Some(Severity::Warning)
+        } else {
+            None
+        };
+        let state = ValidityChanged {
+            valid: self.validity != Some(Severity::Error),
+            complete: self.is_complete(),
+        };
+        if self.reported_validity != Some(state) {
+            self.reported_validity = Some(state);
+            cx.emit(state);
+        }
+        self.canvas.set_block_warnings(self.block_warnings());
+        // Walks every subtree, so it runs with the preview rather than every frame
+        self.canvas.set_imported_blocks(self.imported_marks());
+
+        let code = if let Some(root) = self.canvas.root_block() {
+            if let Some(ast) = root.to_ast() {
+                self.generate_preview_code(&ast)
+            } else {
+                "// Fill all slots to see generated code".to_string()
+            }
+        } else {
+            "// Click to add a type or use the Add Type button".to_string()
+        };
+        
+        self.preview_input.update(cx, |input, cx| {
+            input.set_value(&code, window, cx);
+        });
+        if let Some(saved_input) = &self.saved_preview_input {
+            let saved = self.saved_preview_code();
+            saved_input.update(cx, |input, cx| {
+                input.set_value(&saved, window, cx);
+            });
+        }
+    }
+
+    /// Open the preview find bar, or focus it if already open
+    fn find_in_preview(&mut self, _: &FindInPreview, window: &mut Window, cx: &mut Context<Self>) {
+        let input = match &self.preview_search {
+            Some(search) => search.input.clone(),
+            None => {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder("Find in generated code"));
+                cx.subscribe_in(&input, window, Self::on_preview_search_changed).detach();
+                self.preview_search = Some(PreviewSearch { input: input.clone(), current: 0 });
+                input
+            }
+        };
+        self.show_preview = true;
+        window.focus(&input.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn close_preview_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.preview_search = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn on_preview_search_changed(
+        &mut self,
+        _input: &Entity<InputState>,
+        event: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let (InputEvent::Change { .. }, Some(search)) = (event, &mut self.preview_search) {
+            search.current = 0;
+            self.reveal_preview_match(window, cx);
+            cx.notify();
+        }
+    }
+
+    fn find_next_in_preview(&mut self, _: &FindNextInPreview, window: &mut Window, cx: &mut Context<Self>) {
+        self.step_preview_match(1, window, cx);
+    }
+
+    fn find_previous_in_preview(&mut self, _: &FindPreviousInPreview, window: &mut Window, cx: &mut Context<Self>) {
+        self.step_preview_match(-1, window, cx);
+    }
+
+    /// Move the highlighted match forward or back, wrapping at either end
+    fn step_preview_match(&mut self, step: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.preview_search_matches(cx).len();
+        let Some(search) = &mut self.preview_search else {
+            cx.propagate();
+            return;
+        };
+        if count > 0 {
+            search.current = (search.current as isize + step).rem_euclid(count as isize) as usize;
+        }
+        self.reveal_preview_match(window, cx);
+        cx.notify();
+    }
+
+    /// Put the preview cursor on the current match so the code view scrolls to it
+    fn reveal_preview_match(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(search) = &self.preview_search else {
+            return;
+        };
+        let matches = self.preview_search_matches(cx);
+        let Some((line_index, range)) = matches.get(search.current).cloned() else {
+            return;
+        };
+        let code = self.preview_input.read(cx).value().to_string();
+        let line = code.lines().nth(line_index).unwrap_or_default();
+        let position = ui::input::Position {
+            line: line_index as u32,
+            character: line[..range.start].chars().count() as u32,
+        };
+        self.preview_input.update(cx, |input, cx| {
+            input.set_cursor_position(position, window, cx);
+        });
+    }
+
+    fn preview_search_matches(&self, cx: &App) -> Vec<(usize, std::ops::Range<usize>)> {
+        let Some(search) = &self.preview_search else {
+            return Vec::new();
+        };
+        let query = search.input.read(cx).value().to_string();
+        preview_matches(&self.preview_input.read(cx).value(), &query)
+    }
+
+    /// Find bar and the matching preview lines, the current match emphasised. Clicking
+    /// a line makes it current.
+    fn render_preview_search(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let search = self.preview_search.as_ref()?;
+        let code = self.preview_input.read(cx).value().to_string();
+        let lines: Vec<&str> = code.lines().collect();
+        let matches = self.preview_search_matches(cx);
+        let current = search.current.min(matches.len().saturating_sub(1));
+        let highlight = cx.theme().warning;
+
+        let rows: Vec<_> = matches.iter().enumerate().take(50).map(|(index, (line_index, range))| {
+            let line = lines.get(*line_index).copied().unwrap_or_default();
+            let is_current = index == current;
+            h_flex()
+                .id(SharedString::from(format!("preview_match_{}", index)))
+                .px_2()
+                .gap_2()
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .when(is_current, |this| this.bg(highlight.opacity(0.15)))
+                .hover(|this| this.bg(cx.theme().accent.opacity(0.1)))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    if let Some(search) = &mut this.preview_search {
+                        search.current = index;
+                    }
+                    this.reveal_preview_match(window, cx);
+                    cx.notify();
+                }))
+                .child(
+                    div()
+                        .w(px(32.0))
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("{}", line_index + 1))
+                )
+                .child(
+                    h_flex()
+                        .text_color(cx.theme().foreground)
+                        .child(line[..range.start].to_string())
+                        .child(
+                            div()
+                                .rounded(px(2.0))
+                                .bg(highlight.opacity(if is_current { 0.6 } else { 0.3 }))
+                                .child(line[range.clone()].to_string())
+                        )
+                        .child(line[range.end..].to_string())
+                )
+        }).collect();
+
+        Some(
+            v_flex()
+                .w_full()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().flex_1().child(TextInput::new(&search.input)))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(if matches.is_empty() {
+                                    "No matches".to_string()
+                                } else {
+                                    format!("{} of {}", current + 1, matches.len())
+                                })
+                        )
+                        .child(
+                            Button::new("preview_search_prev_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("▲")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.find_previous_in_preview(&FindPreviousInPreview, window, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("preview_search_next_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("▼")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.find_next_in_preview(&FindNextInPreview, window, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("preview_search_close_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("✕")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.close_preview_search(window, cx);
+                                }))
+                        )
+                )
+                .when(!matches.is_empty(), |this| {
+                    this.child(
+                        v_flex()
+                            .id("preview_search_results")
+                            .max_h(px(160.0))
+                            .overflow_y_scroll()
+                            .font_family("monospace")
+                            .text_xs()
+                            .children(rows)
+                    )
+                })
+        )
+    }
+
+    /// Show the code as last saved beside the live preview, or go back to the live preview only
+    fn toggle_saved_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.saved_preview_input.take().is_none() {
+            self.saved_preview_input = Some(code_input("rust", window, cx));
+            self.preview_needs_update = true;
+        }
+        cx.notify();
+    }
+
+    /// Generated code of the saved alias file, laid out like the live preview
+    fn saved_preview_code(&self) -> String {
+        let Some(file_path) = &self.file_path else {
+            return "// Not saved yet".to_string();
+        };
+        match self.saved_asset(file_path) {
+            Ok(saved) => {
+                let options = ExportOptions::from_meta(&saved.meta).with_template(&self.type_config);
+                with_usage_example(codegen::generate_alias_code(&saved, &options), &saved.display_name)
+            }
+            Err(e) => format!("// Saved file unavailable: {}", e),
+        }
+    }
+
+
+
+    fn generate_preview_code(&self, ast: &TypeAstNode) -> String {
+        let asset = AliasAsset {
+            schema_version: 1,
+            type_kind: ui_types_common::TypeKind::Alias,
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            description: (!self.description.is_empty()).then(|| self.description.clone()),
+            ast: ast.clone(),
+            meta: self.meta.clone(),
+        };
+
+        with_usage_example(codegen::generate_alias_code(&asset, &self.codegen_options()), &self.display_name)
+    }
+
+    fn set_emit_consts(&mut self, emit_consts: bool, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.export_options.emit_consts = emit_consts;
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn set_export_mode(&mut self, mode: ExportMode, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.export_options.mode = mode;
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    /// Step to the next visibility choice, `pub` → `pub(crate)` → `pub(super)` → private
+    fn cycle_visibility(&mut self, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        let next = Visibility::CHOICES
+            .iter()
+            .position(|choice| *choice == self.export_options.visibility)
+            .map(|i| (i + 1) % Visibility::CHOICES.len())
+            .unwrap_or(0);
+        self.export_options.visibility = Visibility::CHOICES[next].clone();
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn set_serde_derive(&mut self, derive: bool, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.export_options.serde.derive = derive;
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn set_serde_transparent(&mut self, transparent: bool, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.export_options.serde.transparent = transparent;
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    fn set_conversions(&mut self, conversions: bool, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.export_options.conversions = conversions;
+        self.preview_needs_update = true;
+        self.dirty = true;
+        cx.notify();
+    }
+
+    /// Open the metadata editor on the current `meta`, or close it. Closing with invalid
+    /// JSON drops the edit and keeps the last valid metadata.
+    fn toggle_meta_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.meta_input.take().is_some() {
+            self.meta_error = None;
+            cx.notify();
+            return;
+        }
+        // Show the export options as they will be saved
+        self.export_options.write_to_meta(&mut self.meta);
+        let json = serde_json::to_string_pretty(&self.meta).unwrap_or_else(|_| "{}".to_string());
+        let input = code_input("json", window, cx);
+        input.update(cx, |input, cx| input.set_value(json, window, cx));
+        cx.subscribe_in(&input, window, Self::on_meta_changed).detach();
+        self.meta_input = Some(input);
+        cx.notify();
+    }
+
+    /// Take valid edits to the metadata as they are typed
+    fn on_meta_changed(
+        &mut self,
+        input: &Entity<InputState>,
+        event: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputEvent::Change { .. }) {
+            return;
+        }
+        let text = input.read(cx).value().to_string();
+        match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(meta) if meta.is_object() => {
+                self.export_options = ExportOptions::from_meta(&meta);
+                self.meta = meta;
+                self.meta_error = None;
+                self.dirty = true;
+                self.preview_needs_update = true;
+            }
+            Ok(_) => self.meta_error = Some("metadata must be a JSON object".to_string()),
+            Err(e) => self.meta_error = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn on_serde_rename_changed(
+        &mut self,
+        input: &Entity<InputState>,
+        event: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(event, InputEvent::Change { .. }) && !self.read_only {
+            let rename = input.read(cx).value().trim().to_string();
+            let rename = (!rename.is_empty()).then_some(rename);
+            // Loading a file sets the field too, which is not an edit
+            if self.export_options.serde.rename == rename {
+                return;
+            }
+            self.export_options.serde.rename = rename;
+            self.preview_needs_update = true;
+            self.dirty = true;
+            cx.notify();
+        }
+    }
+}
+
+/// Read-only Rust code view state, set up like the script editor
+fn code_input(language: &'static str, window: &mut Window, cx: &mut Context<VisualAliasEditor>) -> Entity<InputState> {
+    use ui::input::TabSize;
+    cx.new(|cx| {
+        InputState::new(window, cx)
+            .code_editor(language)
+            .line_number(true)
+            .minimap(true)
+            .tab_size(TabSize {
+                tab_size: 4,
+                hard_tabs: false,
+            })
+    })
+}
+
+/// Preview code followed by a usage line for the alias
+fn with_usage_example(code: String, display_name: &str) -> String {
+    format!(
+        "{}\n\
+         // Usage example:\n\
+         // let value: {} = ...;",
+        code, display_name
+    )
+}
+
+/// Code view shared by the preview panel and the generated code review
+fn code_view(input: &Entity<InputState>) -> TextInput {
+    TextInput::new(input)
+        .h_full()
+        .w_full()
+        .appearance(false)
+        .font_family("monospace")
+        .font(gpui::Font {
+            family: "Jetbrains Mono".to_string().into(),
+            weight: gpui::FontWeight::NORMAL,
+            style: gpui::FontStyle::Normal,
+            features: gpui::FontFeatures::default(),
+            fallbacks: Some(gpui::FontFallbacks::from_fonts(vec!["monospace".to_string()])),
+        })
+        .text_size(px(14.0))
+}
+
+/// Check that a name can be used as a Rust type identifier
+fn is_valid_type_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+/// Convert a display name like `EntityId` into the asset name form `entity_id`
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Blocks marked as imported in `meta`, keyed by their id in the loaded tree
+fn imported_snapshot(root: Option<&TypeBlock>, meta: &serde_json::Value) -> HashMap<BlockId, TypeAstNode> {
+    let Some(root) = root else {
+        return HashMap::new();
+    };
+    import::imported_paths(meta)
+        .iter()
+        .filter_map(|path| root.block_at_path(path))
+        .filter_map(|block| Some((block.id().clone(), block.to_ast()?)))
+        .collect()
+}
+
+/// Suggested name for a helper alias extracted from `alias`, e.g. `ConfigHashMap`
+fn helper_alias_name(alias: &str, subtree: &TypeAstNode) -> String {
+    let kind = match subtree {
+        TypeAstNode::Constructor { name, .. } => name.rsplit("::").next().unwrap_or(name),
+        TypeAstNode::Tuple { .. } => "Tuple",
+        TypeAstNode::FnPointer { .. } => "Fn",
+        _ => "Part",
+    };
+    let kind: String = kind.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    format!("{}{}", alias, kind)
+}
+
+/// Path for a duplicated alias. Aliases stored as `<TypeName>/alias.json` get a sibling
+/// type directory, any other file gets a sibling file with the same extension.
+fn duplicate_path(file_path: &std::path::Path, new_name: &str) -> PathBuf {
+    let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let parent = file_path.parent().unwrap_or(std::path::Path::new(""));
+
+    if file_name == "alias.json" {
+        parent
+            .parent()
+            .unwrap_or(std::path::Path::new(""))
+            .join(new_name)
+            .join(file_name)
+    } else {
+        let mut path = parent.join(new_name);
+        if let Some(ext) = file_path.extension() {
+            path.set_extension(ext);
+        }
+        path
+    }
+}
+
+impl Render for VisualAliasEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Regenerate the preview once edits settle
+        if self.preview_needs_update && self.preview_task.is_none() {
+            self.preview_task = Some(cx.spawn_in(window, async move |this, cx| {
+                cx.background_executor().timer(PREVIEW_DEBOUNCE).await;
+                this.update_in(cx, |editor, window, cx| {
+                    editor.preview_task = None;
+                    editor.update_preview(window, cx);
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        
+        self.canvas.set_high_contrast(self.setting(EditorSetting::HighContrast, cx));
+        self.canvas.set_show_sizes(self.setting(EditorSetting::ShowSizes, cx));
+        self.canvas.set_direction(self.file_layout_direction().unwrap_or(EditorSettings::global(cx).layout_direction));
+        self.canvas.set_drag_source(Some(cx.entity_id()));
+        
+        v_flex()
+            .size_full()
+            .relative()
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::save))
+            .on_action(cx.listener(Self::save_as))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::toggle_palette))
+            .on_action(cx.listener(Self::duplicate_alias))
+            .on_action(cx.listener(Self::toggle_preview))
+            .on_action(cx.listener(Self::run_validation))
+            .on_action(cx.listener(Self::run_project_validation))
+            .on_action(cx.listener(Self::regenerate_all))
+            .on_action(cx.listener(Self::find_usages))
+            .on_action(cx.listener(Self::toggle_dependencies))
+            .on_action(cx.listener(Self::toggle_settings))
+            .on_action(cx.listener(Self::build_callback))
+            .on_action(cx.listener(Self::delete_alias))
+            .on_action(cx.listener(Self::import_ast_json))
+            .on_action(cx.listener(Self::copy_share_link))
+            .on_action(cx.listener(Self::paste_share_link))
+            .on_action(cx.listener(Self::copy_dsl))
+            .on_action(cx.listener(Self::paste_dsl))
+            .on_action(cx.listener(Self::revert_to_saved))
+            .on_action(cx.listener(Self::find_in_preview))
+            .on_action(cx.listener(Self::quick_switch))
+            .on_action(cx.listener(Self::find_next_in_preview))
+            .on_action(cx.listener(Self::find_previous_in_preview))
+            .on_action(cx.listener(Self::focus_next))
+            .on_action(cx.listener(Self::focus_previous))
+            .on_action(cx.listener(Self::activate_focused))
+            .on_action(cx.listener(Self::toggle_find_replace))
+            .on_action(cx.listener(Self::toggle_flatten_tuples))
+            .on_action(cx.listener(Self::toggle_high_contrast))
+            .on_action(cx.listener(Self::toggle_sizes))
+            .on_action(cx.listener(Self::export_svg_action))
+            .on_action(cx.listener(Self::paste_type))
+            .on_action(cx.listener(Self::repeat_last_type))
+            .on_action(cx.listener(Self::toggle_picker_on_click))
+            .on_action(cx.listener(Self::reveal_in_folder))
+            .on_action(cx.listener(Self::cancel_placement))
+            .on_action(cx.listener(Self::toggle_smart_defaults))
+            .on_action(cx.listener(Self::toggle_group_by_crate))
+            .on_action(cx.listener(Self::toggle_layout_direction))
+            .on_action(cx.listener(Self::import_rust_file))
+            .on_action(cx.listener(Self::cycle_primitive))
+            .on_action(cx.listener(Self::request_save_all))
+            .bg(cx.theme().background)
+            .child(
+                // Top toolbar
+                h_flex()
+                    .w_full()
+                    .px_4()
+                    .py_3()
+                    .gap_4()
+                    .bg(cx.theme().secondary.opacity(0.5))
+                    .border_b_2()
+                    .border_color(cx.theme().border)
+                    .items_center()
+                    .child(
+                        // Icon and title
+                        h_flex()
+                            .gap_3()
+                            .items_center()
+                            .child(div().text_xl().child("🔗"))
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .font_semibold()
+                                    .text_color(cx.theme().foreground)
+                                    .child(if !self.display_name.is_empty() {
+                                        self.display_name.clone()
+                                    } else {
+                                        "New Type Alias".to_string()
+                                    })
+                            )
+                            .children(self.render_stability_badge(cx))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(match &self.file_path {
+                                        Some(path) => path.display().to_string(),
+                                        None => "Not saved".to_string(),
+                                    })
+                            )
+                    )
+                    .children(self.render_slot_breadcrumb(cx))
+                    .child(
+                        // Spacer
+                        div().flex_1()
+                    )
+                    .child(
+                        // Action buttons
+                        h_flex()
+                            .gap_2()
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("add_type_btn")
+                                    .with_variant(ButtonVariant::Secondary)
+                                    .child("🎨 Add Type")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_palette(&TogglePalette, window, cx);
+                                    }))
+                            ))
+                            .child(
+                                Checkbox::new("continue_filling_checkbox")
+                                    .label("Guided fill")
+                                    .checked(self.continue_filling)
+                                    .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                        this.set_continue_filling(*checked, cx);
+                                    }))
+                            )
+                            .child(
+                                Checkbox::new("smart_defaults_checkbox")
+                                    .label("Smart defaults")
+                                    .checked(EditorSettings::global(cx).smart_defaults)
+                                    .on_click(cx.listener(|this, _checked: &bool, window, cx| {
+                                        this.toggle_smart_defaults(&ToggleSmartDefaults, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Checkbox::new("group_by_crate_checkbox")
+                                    .label("Group by crate")
+                                    .checked(EditorSettings::global(cx).group_by_crate)
+                                    .on_click(cx.listener(|this, _checked: &bool, window, cx| {
+                                        this.toggle_group_by_crate(&ToggleGroupByCrate, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Checkbox::new("picker_on_click_checkbox")
+                                    .label("Picker on click")
+                                    .checked(self.setting(EditorSetting::OpenPickerOnClick, cx))
+                                    .on_click(cx.listener(|this, _checked: &bool, window, cx| {
+                                        this.toggle_picker_on_click(&TogglePickerOnClick, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Checkbox::new("show_sizes_checkbox")
+                                    .label("Sizes")
+                                    .checked(self.setting(EditorSetting::ShowSizes, cx))
+                                    .on_click(cx.listener(|this, _checked: &bool, window, cx| {
+                                        this.toggle_sizes(&ToggleSizes, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("toggle_layout_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child(match self.canvas.direction() {
+                                        LayoutDirection::TopDown => "↧ Top-down",
+                                        LayoutDirection::LeftToRight => "↦ Left-to-right",
+                                    })
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_layout_direction(&ToggleLayoutDirection, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("toggle_preview_btn")
+                                    .with_variant(if self.show_preview {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .child(if self.show_preview { "📋 Hide Preview" } else { "📋 Show Preview" })
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_preview(&TogglePreview, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("export_svg_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🖼 Export SVG")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.export_svg_action(&ExportSvg, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("find_usages_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🔍 Usages")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.find_usages(&FindUsages, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("dependencies_btn")
+                                    .with_variant(if self.show_dependencies {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .child("🧩 Dependencies")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_dependencies(&ShowDependencies, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("settings_btn")
+                                    .with_variant(if self.show_settings {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .child(match settings::override_count(&self.meta) {
+                                        0 => "⚙ Settings".to_string(),
+                                        count => format!("⚙ Settings ({})", count),
+                                    })
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_settings(&ToggleSettings, window, cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("build_callback_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🪝 Callback…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.build_callback(&BuildCallback, window, cx);
+                                    }))
+                            ))
+                            .when(!self.read_only && self.file_path.is_some(), |this| this.child(
+                                Button::new("revert_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("↺ Revert")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.revert_to_saved(&RevertToSaved, window, cx);
+                                    }))
+                            ))
+                            .when(!self.read_only && self.file_path.is_some(), |this| this.child(
+                                Button::new("delete_alias_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🗑 Delete…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.delete_alias(&DeleteAlias, window, cx);
+                                    }))
+                            ))
+                            .child(
+                                Button::new("compare_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("⇄ Compare…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.compare_with_file(window, cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("import_rs_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("📥 Import .rs…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.import_rust_file(&ImportRustFile, window, cx);
+                                    }))
+                            ))
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("import_ast_json_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("📥 Import AST JSON…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.import_ast_json(&ImportAstJson, window, cx);
+                                    }))
+                            ))
+                            .child(
+                                Button::new("copy_share_link_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🔗 Copy share link")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.copy_share_link(&CopyShareLink, window, cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("paste_share_link_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("🔗 Paste share link")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.paste_share_link(&PasteShareLink, window, cx);
+                                    }))
+                            ))
+                            .child(
+                                Button::new("copy_dsl_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("✏️ Copy as DSL")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.copy_dsl(&CopyDsl, window, cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("paste_dsl_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("✏️ Paste DSL")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.paste_dsl(&PasteDsl, window, cx);
+                                    }))
+                            ))
+                            .child(
+                                Button::new("duplicate_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("📄 Duplicate")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.duplicate_alias(&DuplicateAlias, window, cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("flatten_tuples_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("⇲ Flatten Tuples")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_flatten_tuples(&FlattenTuples, window, cx);
+                                    }))
+                            ))
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("meta_editor_btn")
+                                    .with_variant(if self.meta_input.is_some() {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .child("{ } Metadata")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_meta_editor(window, cx);
+                                    }))
+                            ))
+                            .child(Divider::vertical().h(px(24.0)))
+                            .child(
+                                Button::new("validate_btn")
+                                    .with_variant(ButtonVariant::Secondary)
+                                    .child("✔ Validate")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_validation(&Validate, window, cx);
+                                    }))
+                            )
+                            .child(
+                                Button::new("type_check_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child(if self.type_check_running { "⏳ Checking…" } else { "🦀 Type Check" })
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.run_type_check(cx);
+                                    }))
+                            )
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("save_btn")
+                                    // Errors keep the button clickable so saving still reports them
+                                    .with_variant(match self.validity {
+                                        None | Some(Severity::Info) => ButtonVariant::Success,
+                                        Some(Severity::Warning) => ButtonVariant::Warning,
+                                        Some(Severity::Error) => ButtonVariant::Ghost,
+                                    })
+                                    .child("💾 Save")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.save(&Save, window, cx);
+                                    }))
+                            ))
+                            .when(!self.read_only, |this| this.child(
+                                Button::new("save_as_btn")
+                                    .with_variant(ButtonVariant::Ghost)
+                                    .child("Save As…")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.save_as(&SaveAs, window, cx);
+                                    }))
+                            ))
+                    )
+            )
+            .when(self.read_only, |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().muted_foreground.opacity(0.15))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(div().text_sm().child("🔒"))
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(cx.theme().foreground)
+                                .child("Read-only")
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("This alias is locked. Duplicate it to make an editable copy.")
+                        )
+                )
+            })
+            .when_some(self.duplicate_name_input.clone(), |this, input| {
+                // Name prompt for the duplicate
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child("Duplicate as:")
+                        )
+                        .child(div().w(px(240.0)).child(TextInput::new(&input)))
+                        .child(
+                            Button::new("confirm_duplicate_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Create")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.confirm_duplicate(cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_duplicate_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.cancel_duplicate(cx);
+                                }))
+                        )
+                )
+            })
+            .when_some(self.dedupe_prompt.as_ref(), |this, proposal| {
+                // Extraction proposed by save for a subtree repeated in the tree
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .gap_1()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(cx.theme().foreground)
+                                        .child(format!(
+                                            "`{}` appears {} times. Extract it into a helper alias?",
+                                            codegen::ast_to_rust_string(&proposal.subtree),
+                                            proposal.count
+                                        ))
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child("Each occurrence becomes a reference to the new alias.")
+                                )
+                        )
+                        .child(div().w(px(200.0)).child(TextInput::new(&proposal.name_input)))
+                        .child(
+                            Button::new("confirm_dedupe_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Extract & Save")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.confirm_dedupe(window, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("skip_dedupe_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Save As Is")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.skip_dedupe(window, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_dedupe_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.dedupe_prompt = None;
+                                    this.settle_save_target(cx);
+                                    cx.notify();
+                                }))
+                        )
+                )
+            })
+            .when(!self.repairs.is_empty() && !self.read_only, |this| {
+                // Repairs found by validating the alias when it was opened
+                let safe_count = self.repairs.iter().filter(|repair| repair.is_safe()).count();
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_sm()
+                                        .font_semibold()
+                                        .text_color(cx.theme().foreground)
+                                        .child(format!("🔧 {} issue(s) can be repaired", self.repairs.len()))
+                                )
+                                .when(safe_count > 0, |this| {
+                                    this.child(
+                                        Button::new("fix_all_safe_btn")
+                                            .with_variant(ButtonVariant::Primary)
+                                            .child(format!("Fix all safe issues ({})", safe_count))
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.apply_safe_repairs(cx);
+                                            }))
+                                    )
+                                })
+                                .child(
+                                    Button::new("dismiss_repairs_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Dismiss")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.repairs.clear();
+                                            cx.notify();
+                                        }))
+                                )
+                        )
+                        .children(self.repairs.iter().enumerate().map(|(index, repair)| {
+                            let repair = repair.clone();
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_xs()
+                                        .text_color(cx.theme().foreground)
+                                        .child(repair.to_string())
+                                )
+                                .when(!repair.is_safe(), |this| {
+                                    this.child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().warning)
+                                            .child("loses types")
+                                    )
+                                })
+                                .child(
+                                    Button::new(("apply_repair_btn", index))
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Apply")
+                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                            this.apply_repairs(vec![repair.clone()], cx);
+                                        }))
+                                )
+                        }))
+                )
+            })
+            .when(self.revert_prompt, |this| {
+                // Confirmation before reverting discards unsaved changes
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().warning.opacity(0.15))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child("Revert to the saved file? Unsaved changes will be discarded.")
+                        )
+                        .child(
+                            Button::new("confirm_revert_btn")
+                                .with_variant(ButtonVariant::Warning)
+                                .child("Revert")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.confirm_revert(window, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_revert_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Keep Changes")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.revert_prompt = false;
+                                    cx.notify();
+                                }))
+                        )
+                )
+            })
+            .when_some(self.pending_root_replacement.as_ref().map(TypeBlock::display_name), |this, name| {
+                // Confirmation before a new root discards the current tree
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().warning.opacity(0.15))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child(format!("Replace the whole type with {}? The current tree will be discarded.", name))
+                        )
+                        .child(
+                            Button::new("confirm_replace_root_btn")
+                                .with_variant(ButtonVariant::Warning)
+                                .child("Replace")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.confirm_root_replacement(cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_replace_root_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Keep Current")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.pending_root_replacement = None;
+                                    if matches!(this.callback_wizard, Some(CallbackWizard::Slots { .. })) {
+                                        this.callback_wizard = None;
+                                    }
+                                    cx.notify();
+                                }))
+                        )
+                )
+            })
+            .when_some(self.rust_import.as_ref(), |this, rust_import| {
+                // Import bar - pick which aliases from the `.rs` file to write as assets
+                let selected_count = rust_import.selected.iter().filter(|s| **s).count();
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child(format!("Import from {}", rust_import.source.display()))
+                        )
+                        .children(rust_import.result.aliases.iter().enumerate().map(|(i, alias)| {
+                            Checkbox::new(("import_alias", i))
+                                .label(alias.name.clone())
+                                .checked(rust_import.selected[i])
+                                .on_click(cx.listener(move |this, checked: &bool, _window, cx| {
+                                    if let Some(rust_import) = &mut this.rust_import {
+                                        rust_import.selected[i] = *checked;
+                                    }
+                                    cx.notify();
+                                }))
+                        }))
+                        .children(rust_import.result.skipped.iter().map(|skipped| {
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("Skipped: {}", skipped))
+                        }))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Button::new("confirm_import_btn")
+                                        .with_variant(ButtonVariant::Primary)
+                                        .child(format!("Import {} Selected", selected_count))
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_rust_import(cx);
+                                        }))
+                                )
+                                .child(
+                                    Button::new("cancel_import_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Cancel")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.rust_import = None;
+                                            cx.notify();
+                                        }))
+                                )
+                        )
+                )
+            })
+            .when_some(self.delete_prompt.as_ref(), |this, usages| {
+                // Delete bar - confirm, or inline into the aliases that still use this one
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().danger.opacity(0.1))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child(if usages.is_empty() {
+                                    format!("Delete {} and its generated code?", self.display_name)
+                                } else {
+                                    format!(
+                                        "{} is used by {} aliases. Deleting it would break them:",
+                                        self.display_name,
+                                        usages.len()
+                                    )
+                                })
+                        )
+                        .children(usages.iter().map(|usage| {
+                            div()
+                                .text_xs()
+                                .font_family("monospace")
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("{} = {}", usage.display_name, usage.rust_type))
+                        }))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .when(usages.is_empty(), |this| this.child(
+                                    Button::new("confirm_delete_btn")
+                                        .with_variant(ButtonVariant::Danger)
+                                        .child("Delete")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_delete(false, cx);
+                                        }))
+                                ))
+                                .when(!usages.is_empty(), |this| this.child(
+                                    Button::new("inline_and_delete_btn")
+                                        .with_variant(ButtonVariant::Danger)
+                                        .child(format!("Inline into {} and Delete", usages.len()))
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_delete(true, cx);
+                                        }))
+                                ))
+                                .child(
+                                    Button::new("cancel_delete_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Cancel")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.delete_prompt = None;
+                                            cx.notify();
+                                        }))
+                                )
+                        )
+                )
+            })
+            .children(self.render_callback_wizard(cx))
+            .children(self.render_settings_panel(cx))
+            .when(self.show_dependencies, |this| {
+                // Dependencies panel - aliases open on click, external paths are listed as is
+                let (aliases, paths) = self.dependencies();
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_sm()
+                                        .text_color(cx.theme().foreground)
+                                        .child(if aliases.is_empty() && paths.is_empty() {
+                                            format!("{} has no dependencies", self.display_name)
+                                        } else {
+                                            format!("{} depends on", self.display_name)
+                                        })
+                                )
+                                .child(
+                                    Button::new("close_dependencies_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Close")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.show_dependencies = false;
+                                            cx.notify();
+                                        }))
+                                )
+                        )
+                        .children(aliases.into_iter().enumerate().map(|(i, alias)| {
+                            let unresolved = self.alias_index.as_ref().is_some_and(|index| !index.contains(&alias));
+                            h_flex()
+                                .id(("dependency_alias", i))
+                                .gap_2()
+                                .px_2()
+                                .rounded(px(4.0))
+                                .cursor_pointer()
+                                .hover(|style| style.bg(cx.theme().accent.opacity(0.2)))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child("alias")
+                                )
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_bold()
+                                        .text_color(cx.theme().foreground)
+                                        .child(alias.clone())
+                                )
+                                .when(unresolved, |this| this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().warning)
+                                        .child("unresolved")
+                                ))
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.open_alias_ref(alias.clone(), cx);
+                                }))
+                        }))
+                        .children(paths.into_iter().map(|path| {
+                            h_flex()
+                                .gap_2()
+                                .px_2()
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child("path")
+                                )
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_family("monospace")
+                                        .text_color(cx.theme().foreground)
+                                        .child(path)
+                                )
+                        }))
+                )
+            })
+            .when_some(self.usages.as_ref(), |this, usages| {
+                // Usages bar - files referencing this alias, click to open
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_sm()
+                                        .text_color(cx.theme().foreground)
+                                        .child(match usages.len() {
+                                            0 => format!("No aliases use {}", self.display_name),
+                                            1 => format!("1 alias uses {}", self.display_name),
+                                            n => format!("{} aliases use {}", n, self.display_name),
+                                        })
+                                )
+                                .child(
+                                    Button::new("close_usages_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Close")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.usages = None;
+                                            cx.notify();
+                                        }))
+                                )
+                        )
+                        .children(usages.iter().enumerate().map(|(i, usage)| {
+                            let path = usage.path.clone();
+                            h_flex()
+                                .id(("usage", i))
+                                .gap_2()
+                                .px_2()
+                                .rounded(px(4.0))
+                                .cursor_pointer()
+                                .hover(|style| style.bg(cx.theme().accent.opacity(0.2)))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_bold()
+                                        .text_color(cx.theme().foreground)
+                                        .child(usage.display_name.clone())
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .font_family("monospace")
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(usage.rust_type.clone())
+                                )
+                                .on_click(cx.listener(move |_this, _, _window, cx| {
+                                    cx.emit(OpenAliasRequest { path: path.clone() });
+                                }))
+                        }))
+                )
+            })
+            .when_some(self.pending_block.as_ref().map(TypeBlock::display_name), |this, name| {
+                // Placement banner while a block waits for a slot
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().accent.opacity(0.15))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child(format!("Placing: {} — click a slot or press Esc", name))
+                        )
+                        .child(
+                            Button::new("cancel_placement_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.cancel_placement(&CancelPlacement, window, cx);
+                                }))
+                        )
+                )
+            })
+            .when(
+                self.pending_block.is_none()
+                    && self.selected_slot.is_some()
+                    && (!self.setting(EditorSetting::OpenPickerOnClick, cx) || self.selected_slots.len() > 1),
+                |this| {
+                    // Select-only mode or a bulk selection: say how to fill the selected slots
+                    this.child(
                         h_flex()
-                            .gap_3()
+                            .w_full()
+                            .px_4()
+                            .py_2()
+                            .gap_2()
                             .items_center()
-                            .child(div().text_xl().child("🔗"))
+                            .bg(cx.theme().accent.opacity(0.15))
+                            .border_b_1()
+                            .border_color(cx.theme().border)
                             .child(
                                 div()
-                                    .text_lg()
-                                    .font_semibold()
+                                    .flex_1()
+                                    .text_sm()
                                     .text_color(cx.theme().foreground)
-                                    .child(if !self.display_name.is_empty() {
-                                        self.display_name.clone()
-                                    } else {
-                                        "New Type Alias".to_string()
+                                    .child(match (&self.last_picked, self.selected_slots.len()) {
+                                        (Some(item), count) if count > 1 => format!(
+                                            "{} slots selected — repeat {} or open the picker to fill them all",
+                                            count,
+                                            item.name()
+                                        ),
+                                        (None, count) if count > 1 => {
+                                            format!("{} slots selected — open the picker to fill them all", count)
+                                        }
+                                        (Some(item), _) => format!(
+                                            "Slot selected — paste a type, repeat {} or open the picker",
+                                            item.name()
+                                        ),
+                                        (None, _) => "Slot selected — paste a type or open the picker".to_string(),
                                     })
                             )
-                    )
-                    .child(
-                        // Spacer
-                        div().flex_1()
-                    )
-                    .child(
-                        // Action buttons
-                        h_flex()
-                            .gap_2()
                             .child(
-                                Button::new("add_type_btn")
+                                Button::new("open_picker_for_slot_btn")
                                     .with_variant(ButtonVariant::Secondary)
-                                    .child("🎨 Add Type")
+                                    .child("Pick Type")
                                     .on_click(cx.listener(|this, _, window, cx| {
                                         this.toggle_palette(&TogglePalette, window, cx);
                                     }))
                             )
-                            .child(
-                                Button::new("toggle_preview_btn")
-                                    .with_variant(if self.show_preview {
-                                        ButtonVariant::Secondary
-                                    } else {
-                                        ButtonVariant::Ghost
-                                    })
-                                    .child(if self.show_preview { "📋 Hide Preview" } else { "📋 Show Preview" })
-                                    .on_click(cx.listener(|this, _, _window, cx| {
-                                        this.show_preview = !this.show_preview;
-                                        cx.notify();
-                                    }))
-                            )
-                            .child(Divider::vertical().h(px(24.0)))
-                            .child(
-                                Button::new("save_btn")
-                                    .with_variant(ButtonVariant::Primary)
-                                    .child("💾 Save")
-                                    .on_click(cx.listener(|this, _, window, cx| {
-                                        this.save(&Save, window, cx);
-                                    }))
-                            )
                     )
+                },
             )
+            .when_some(self.meta_input.clone(), |this, input| {
+                // Metadata panel - raw JSON of `meta`
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_1()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_semibold()
+                                        .text_color(cx.theme().foreground)
+                                        .child("Metadata")
+                                )
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_xs()
+                                        .text_color(match &self.meta_error {
+                                            Some(_) => cx.theme().danger,
+                                            None => cx.theme().muted_foreground,
+                                        })
+                                        .child(match &self.meta_error {
+                                            Some(e) => format!("Invalid JSON: {}", e),
+                                            None => "Saved with the alias; unknown keys are kept as they are. \"stability\" and \"since\" mark API status, \"cfg\" adds #[cfg(...)]".to_string(),
+                                        })
+                                )
+                                .child(
+                                    Button::new("close_meta_editor_btn")
+                                        .with_variant(ButtonVariant::Ghost)
+                                        .child("Close")
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.toggle_meta_editor(window, cx);
+                                        }))
+                                )
+                        )
+                        .child(div().h(px(200.0)).w_full().child(code_view(&input)))
+                )
+            })
+            .when(self.canvas.root_block().is_some(), |this| {
+                // Collapsible plain-English explanation of the type
+                let expanded = self.show_explanation;
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .px_4()
+                        .py_1()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .id("explain_header")
+                                .cursor_pointer()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(if expanded { "▾ Explain this type" } else { "▸ Explain this type" })
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.show_explanation = !this.show_explanation;
+                                    cx.notify();
+                                }))
+                        )
+                        .when(expanded, |this| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().foreground)
+                                    .child(self.explain())
+                            )
+                        })
+                )
+            })
+            .when_some(self.flatten_preview.clone(), |this, (one_level, all_levels)| {
+                // Flatten tuples bar - previews both depths, nothing changes until one is applied
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .text_xs()
+                                .font_family("monospace")
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("One level: {}", one_level))
+                                .child(format!("All levels: {}", all_levels))
+                        )
+                        .child(
+                            Button::new("flatten_one_btn")
+                                .with_variant(ButtonVariant::Secondary)
+                                .child("Flatten One Level")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_flatten_tuples(false, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("flatten_all_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Flatten All")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_flatten_tuples(true, cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_flatten_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.toggle_flatten_tuples(&FlattenTuples, window, cx);
+                                }))
+                        )
+                )
+            })
+            .when_some(self.const_arg_input.clone(), |this, (_, _, input)| {
+                // Const value bar
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground).child("Const value:"))
+                        .child(div().w(px(200.0)).child(TextInput::new(&input)))
+                        .child(
+                            Button::new("apply_const_arg_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Set")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_const_arg(cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_const_arg_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.const_arg_input = None;
+                                    cx.notify();
+                                }))
+                        )
+                )
+            })
+            .when_some(self.lifetime_input.clone(), |this, (_, _, input)| {
+                // Lifetime name bar
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground).child("Lifetime:"))
+                        .child(div().w(px(200.0)).child(TextInput::new(&input)))
+                        .child(
+                            Button::new("apply_lifetime_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Set")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_lifetime(cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("cancel_lifetime_btn")
+                                .with_variant(ButtonVariant::Ghost)
+                                .child("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.lifetime_input = None;
+                                    cx.notify();
+                                }))
+                        )
+                )
+            })
+            .when_some(self.replace_inputs.clone(), |this, (find, replace)| {
+                // Find/replace bar
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .gap_2()
+                        .items_center()
+                        .bg(cx.theme().secondary.opacity(0.3))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(div().w(px(200.0)).child(TextInput::new(&find)))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground).child("→"))
+                        .child(div().w(px(200.0)).child(TextInput::new(&replace)))
+                        .child(
+                            Button::new("preview_replace_btn")
+                                .with_variant(ButtonVariant::Secondary)
+                                .child("Preview")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.preview_replace(cx);
+                                }))
+                        )
+                        .child(
+                            Button::new("apply_replace_btn")
+                                .with_variant(ButtonVariant::Primary)
+                                .child("Replace All")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_replace(cx);
+                                }))
+                        )
+                        .children(self.replace_status.clone().map(|status| {
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(status)
+                        }))
+                )
+            })
             .child(
                 // Main content area - resizable canvas and preview
                 h_resizable("alias-editor-horizontal", self.horizontal_resizable_state.clone())
                     .child(
                         resizable_panel()
                             .child(
-                                // Canvas container
+                                // Canvas container - clicking it focuses the editor so its bindings apply
                                 v_flex()
                                     .size_full()
                                     .p_4()
                                     .gap_4()
-                                    .when(self.error_message.is_some(), |this| {
-                                        let error = self.error_message.as_ref().unwrap();
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _, window, _cx| {
+                                        window.focus(&this.focus_handle);
+                                    }))
+                                    .when(self.has_errors(), |this| {
+                                        let errors: Vec<String> = self
+                                            .diagnostics
+                                            .iter()
+                                            .filter(|d| d.is_error())
+                                            .map(ToString::to_string)
+                                            .collect();
                                         this.child(
                                             div()
                                                 .w_full()
                                                 .p_4()
-                                                .bg(hsla(0.0, 0.8, 0.5, 0.1))
+                                                .bg(cx.theme().danger.opacity(0.1))
                                                 .border_2()
-                                                .border_color(hsla(0.0, 0.8, 0.6, 1.0))
+                                                .border_color(cx.theme().danger)
                                                 .rounded(px(8.0))
                                                 .child(
                                                     h_flex()
@@ -448,36 +5105,156 @@ impl Render for VisualAliasEditor {
                                                                 .child("⚠️")
                                                         )
                                                         .child(
-                                                            div()
+                                                            v_flex()
                                                                 .text_sm()
-                                                                .text_color(hsla(0.0, 0.8, 0.5, 1.0))
-                                                                .child(error.clone())
+                                                                .text_color(cx.theme().danger)
+                                                                .children(errors.into_iter().map(|error| div().child(error)))
                                                         )
                                                 )
                                         )
                                     })
+                                    .when(self.diagnostics.iter().any(EditorDiagnostic::is_warning), |this| {
+                                        let warnings: Vec<String> = self
+                                            .diagnostics
+                                            .iter()
+                                            .filter(|d| d.is_warning())
+                                            .map(ToString::to_string)
+                                            .collect();
+                                        // Only a shadowed name can be overridden from the banner
+                                        let can_override = self
+                                            .diagnostics
+                                            .iter()
+                                            .any(|d| matches!(d, EditorDiagnostic::ShadowsName { .. }));
+                                        this.child(
+                                            h_flex()
+                                                .w_full()
+                                                .p_4()
+                                                .gap_2()
+                                                .items_center()
+                                                .bg(cx.theme().warning.opacity(0.1))
+                                                .border_2()
+                                                .border_color(cx.theme().warning)
+                                                .rounded(px(8.0))
+                                                .child(
+                                                    div()
+                                                        .text_base()
+                                                        .child("⚠️")
+                                                )
+                                                .child(
+                                                    v_flex()
+                                                        .flex_1()
+                                                        .text_sm()
+                                                        .text_color(cx.theme().warning)
+                                                        .children(warnings.into_iter().map(|warning| div().child(warning)))
+                                                )
+                                                .when(can_override, |this| {
+                                                    this.child(
+                                                        Button::new("save_anyway_btn")
+                                                            .with_variant(ButtonVariant::Ghost)
+                                                            .child("Save Anyway")
+                                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                                this.save_anyway(window, cx);
+                                                            }))
+                                                    )
+                                                })
+                                        )
+                                    })
+                                    .when(self.diagnostics.iter().any(EditorDiagnostic::is_info), |this| {
+                                        let notices: Vec<String> = self
+                                            .diagnostics
+                                            .iter()
+                                            .filter(|d| d.is_info())
+                                            .map(ToString::to_string)
+                                            .collect();
+                                        this.child(
+                                            v_flex()
+                                                .w_full()
+                                                .px_4()
+                                                .py_2()
+                                                .bg(cx.theme().accent.opacity(0.1))
+                                                .border_1()
+                                                .border_color(cx.theme().border)
+                                                .rounded(px(8.0))
+                                                .text_sm()
+                                                .text_color(cx.theme().foreground)
+                                                .children(notices.into_iter().map(|notice| div().child(notice)))
+                                        )
+                                    })
                                     .child({
-                                        // Canvas - fills remaining space
-                                        // Create a handler that stores slot clicks in shared state
-                                        let pending = self.pending_slot_selection.clone();
-                                        let slot_handler = Arc::new(move |block_id: BlockId, slot_idx: usize| {
-                                            if let Ok(mut guard) = pending.lock() {
-                                                *guard = Some((block_id, slot_idx));
+                                        // Canvas - fills remaining space. Click handlers update the
+                                        // editor as the click is dispatched, so none can be lost or
+                                        // handled twice between frames.
+                                        let editor = cx.entity().downgrade();
+                                        let slot_handler = Self::slot_click_handler(editor.clone());
+                                        let empty_handler: EmptyStateHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |choice: EmptyStateChoice, window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.handle_empty_choice(choice, window, cx)).ok();
                                             }
                                         });
-                                        
-                                        // Create handler for empty state click - opens type picker for root
-                                        let pending_empty = self.pending_slot_selection.clone();
-                                        let empty_handler = Arc::new(move || {
-                                            // Signal that we want to add a root type (no specific slot)
-                                            // Use empty BlockId as sentinel value
-                                            if let Ok(mut guard) = pending_empty.lock() {
-                                                *guard = Some((BlockId(Arc::from("")), 0));
+                                        let dismiss_handler: BlockHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |block_id: BlockId, _window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.dismiss_warning(block_id, cx)).ok();
+                                            }
+                                        });
+                                        let alias_handler: AliasHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |alias: String, _window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.open_alias_ref(alias, cx)).ok();
                                             }
                                         });
+                                        let clear_handler: SlotHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |parent_id: BlockId, slot_idx: usize, _window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.clear_slot(parent_id, slot_idx, cx)).ok();
+                                            }
+                                        });
+                                        let select_handler: BlockHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |block_id: BlockId, window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.select_block(block_id, window, cx)).ok();
+                                            }
+                                        });
+                                        let swap_handler: ConstructorHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |block_id: BlockId, name: String, _window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.replace_constructor(block_id, name, cx)).ok();
+                                            }
+                                        });
+                                        let insert_handler: SlotHandler = Arc::new({
+                                            let editor = editor.clone();
+                                            move |block_id: BlockId, index: usize, window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.insert_slot(block_id, index, window, cx)).ok();
+                                            }
+                                        });
+                                        let lifetime_handler: SlotHandler = Arc::new(
+                                            move |block_id: BlockId, index: usize, window: &mut Window, cx: &mut App| {
+                                                editor.update(cx, |this, cx| this.edit_lifetime(block_id, index, window, cx)).ok();
+                                            }
+                                        );
                                         
-                                        self.canvas.render_with_handlers(cx, Some(slot_handler), Some(empty_handler))
+                                        // Read-only canvases only keep navigation to referenced aliases
+                                        let handlers = if self.read_only {
+                                            CanvasHandlers { alias_open: Some(alias_handler), ..Default::default() }
+                                        } else {
+                                            CanvasHandlers {
+                                                slot_click: Some(slot_handler),
+                                                empty_click: Some(empty_handler),
+                                                dismiss_warning: Some(dismiss_handler),
+                                                alias_open: Some(alias_handler),
+                                                slot_clear: Some(clear_handler),
+                                                block_select: Some(select_handler),
+                                                constructor_swap: Some(swap_handler),
+                                                slot_insert: Some(insert_handler),
+                                                lifetime_edit: Some(lifetime_handler),
+                                            }
+                                        };
+                                        self.canvas.render_with_handlers(cx, handlers)
                                     })
+                                    .children(self.render_focus_status(cx))
+                                    // Blocks dropped on the canvas stay put; only the host's dock extracts them
+                                    .on_drop(cx.listener(|_, _: &DraggedBlock, _window, _cx| {}))
                             )
                     )
                     .when(self.show_preview, |this| {
@@ -508,29 +5285,128 @@ impl Render for VisualAliasEditor {
                                                         .text_color(cx.theme().foreground)
                                                         .child("📋 Code Preview")
                                                 )
+                                                .when(self.preview_needs_update, |this| {
+                                                    this.child(
+                                                        div()
+                                                            .ml_2()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().warning)
+                                                            .child("● out of date")
+                                                    )
+                                                })
+                                                .child(div().flex_1())
+                                                .child(
+                                                    Button::new("visibility_btn")
+                                                        .with_variant(ButtonVariant::Ghost)
+                                                        .child(format!("{} ▾", self.export_options.visibility.label()))
+                                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                                            this.cycle_visibility(cx);
+                                                        }))
+                                                )
+                                                .child(
+                                                    Checkbox::new("compare_saved_checkbox")
+                                                        .label("Compare saved")
+                                                        .checked(self.saved_preview_input.is_some())
+                                                        .on_click(cx.listener(|this, _checked: &bool, window, cx| {
+                                                            this.toggle_saved_preview(window, cx);
+                                                        }))
+                                                )
+                                                .child(
+                                                    Checkbox::new("emit_consts_checkbox")
+                                                        .label("Emit consts")
+                                                        .checked(self.export_options.emit_consts)
+                                                        .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                                            this.set_emit_consts(*checked, cx);
+                                                        }))
+                                                )
+                                                .child(
+                                                    Checkbox::new("export_struct_checkbox")
+                                                        .label("As struct")
+                                                        .checked(self.export_options.mode == ExportMode::Struct)
+                                                        .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                                            let mode = if *checked { ExportMode::Struct } else { ExportMode::Alias };
+                                                            this.set_export_mode(mode, cx);
+                                                        }))
+                                                )
                                         )
-                                        .child(
+                                        .children(self.render_preview_search(cx))
+                                        .when(self.export_options.mode == ExportMode::Struct, |this| {
+                                            // Serde attributes and conversions only apply to struct exports
+                                            this.child(
+                                                h_flex()
+                                                    .w_full()
+                                                    .px_4()
+                                                    .py_2()
+                                                    .gap_3()
+                                                    .items_center()
+                                                    .border_b_1()
+                                                    .border_color(cx.theme().border)
+                                                    .child(
+                                                        Checkbox::new("conversions_checkbox")
+                                                            .label("From/Into")
+                                                            .checked(self.export_options.conversions)
+                                                            .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                                                this.set_conversions(*checked, cx);
+                                                            }))
+                                                    )
+                                                    .child(
+                                                        Checkbox::new("serde_derive_checkbox")
+                                                            .label("Serde")
+                                                            .checked(self.export_options.serde.derive)
+                                                            .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                                                this.set_serde_derive(*checked, cx);
+                                                            }))
+                                                    )
+                                                    .when(self.export_options.serde.derive, |this| {
+                                                        this.child(
+                                                            Checkbox::new("serde_transparent_checkbox")
+                                                                .label("Transparent")
+                                                                .checked(self.export_options.serde.transparent)
+                                                                .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                                                    this.set_serde_transparent(*checked, cx);
+                                                                }))
+                                                        )
+                                                        .child(
+                                                            div()
+                                                                .flex_1()
+                                                                .child(TextInput::new(&self.serde_rename_input))
+                                                        )
+                                                    })
+                                            )
+                                        })
+                                        .child(match &self.saved_preview_input {
+                                            // Saved and live code side by side
+                                            Some(saved_input) => {
+                                                let pane = |title: &'static str, input: &Entity<InputState>| {
+                                                    v_flex()
+                                                        .flex_1()
+                                                        .h_full()
+                                                        .child(
+                                                            div()
+                                                                .px_2()
+                                                                .text_xs()
+                                                                .font_semibold()
+                                                                .text_color(cx.theme().muted_foreground)
+                                                                .child(title)
+                                                        )
+                                                        .child(div().flex_1().w_full().child(code_view(input)))
+                                                };
+                                                h_flex()
+                                                    .flex_1()
+                                                    .w_full()
+                                                    .p_2()
+                                                    .gap_2()
+                                                    .child(pane("Saved", saved_input))
+                                                    .child(div().w(px(1.0)).h_full().bg(cx.theme().border))
+                                                    .child(pane("Live", &self.preview_input))
+                                            }
                                             // Code input - fills remaining space
-                                            div()
+                                            None => div()
                                                 .flex_1()
                                                 .w_full()
                                                 .p_2()
-                                                .child(
-                                                    TextInput::new(&self.preview_input)
-                                                        .h_full()
-                                                        .w_full()
-                                                        .appearance(false)
-                                                        .font_family("monospace")
-                                                        .font(gpui::Font {
-                                                            family: "Jetbrains Mono".to_string().into(),
-                                                            weight: gpui::FontWeight::NORMAL,
-                                                            style: gpui::FontStyle::Normal,
-                                                            features: gpui::FontFeatures::default(),
-                                                            fallbacks: Some(gpui::FontFallbacks::from_fonts(vec!["monospace".to_string()])),
-                                                        })
-                                                        .text_size(px(14.0))
-                                                )
-                                        )
+                                                .child(code_view(&self.preview_input)),
+                                        })
                                 )
                         )
                     })
@@ -561,6 +5437,73 @@ impl Render for VisualAliasEditor {
                         )
                 )
             })
+            .when_some(self.pending_codegen.as_ref().map(|(path, _)| path.clone()), |this, path| {
+                // Review of the generated file before save writes it
+                this.child(
+                    div()
+                        .absolute()
+                        .inset_0()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .bg(gpui::black().opacity(0.5))
+                        .child(
+                            v_flex()
+                                .w(px(760.0))
+                                .h(px(520.0))
+                                .gap_2()
+                                .p_4()
+                                .bg(cx.theme().background)
+                                .rounded(px(8.0))
+                                .border_1()
+                                .border_color(cx.theme().border)
+                                .shadow_lg()
+                                .child(
+                                    div()
+                                        .text_base()
+                                        .font_semibold()
+                                        .text_color(cx.theme().foreground)
+                                        .child("Review generated code")
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(format!("Will be written to {}", path.display()))
+                                )
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .w_full()
+                                        .border_1()
+                                        .border_color(cx.theme().border)
+                                        .rounded(px(4.0))
+                                        .child(code_view(&self.codegen_review_input))
+                                )
+                                .child(
+                                    h_flex()
+                                        .justify_end()
+                                        .gap_2()
+                                        .child(
+                                            Button::new("skip_codegen_btn")
+                                                .with_variant(ButtonVariant::Ghost)
+                                                .child("Save Without .rs")
+                                                .on_click(cx.listener(|this, _, _window, cx| {
+                                                    this.confirm_codegen(false, cx);
+                                                }))
+                                        )
+                                        .child(
+                                            Button::new("confirm_codegen_btn")
+                                                .with_variant(ButtonVariant::Primary)
+                                                .child("Write .rs")
+                                                .on_click(cx.listener(|this, _, _window, cx| {
+                                                    this.confirm_codegen(true, cx);
+                                                }))
+                                        )
+                                )
+                        )
+                )
+            })
     }
 }
 
@@ -572,6 +5515,12 @@ impl Focusable for VisualAliasEditor {
 
 impl EventEmitter<PanelEvent> for VisualAliasEditor {}
 impl EventEmitter<ShowTypePickerRequest> for VisualAliasEditor {}
+impl EventEmitter<OpenAliasRequest> for VisualAliasEditor {}
+impl EventEmitter<AliasFileEvent> for VisualAliasEditor {}
+impl EventEmitter<SaveAllRequest> for VisualAliasEditor {}
+impl EventEmitter<CompareAliasRequest> for VisualAliasEditor {}
+impl EventEmitter<ShowRecentFilesRequest> for VisualAliasEditor {}
+impl EventEmitter<ValidityChanged> for VisualAliasEditor {}
 
 impl Panel for VisualAliasEditor {
     fn panel_name(&self) -> &'static str {
@@ -579,12 +5528,13 @@ impl Panel for VisualAliasEditor {
     }
 
     fn title(&self, _window: &Window, _cx: &App) -> gpui::AnyElement {
-        if !self.display_name.is_empty() {
-            format!("🔗 {}", self.display_name)
+        let name = if !self.display_name.is_empty() {
+            self.display_name.as_str()
         } else {
-            "🔗 New Type Alias".to_string()
-        }
-        .into_any_element()
+            "New Type Alias"
+        };
+        format!("🔗 {}{}", name, if self.dirty { " •" } else { "" })
+            .into_any_element()
     }
 
     fn dump(&self, _cx: &App) -> ui::dock::PanelState {
@@ -594,3 +5544,113 @@ impl Panel for VisualAliasEditor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Mutex;
+
+    /// Alias files kept in memory, so tests never touch the disk
+    #[derive(Default)]
+    struct MemoryFiles(Mutex<HashMap<PathBuf, String>>);
+
+    impl FileProvider for MemoryFiles {
+        fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+            self.0.lock().unwrap().get(path).cloned().ok_or_else(|| std::io::ErrorKind::NotFound.into())
+        }
+
+        fn write(&self, path: &Path, contents: &str) -> std::io::Result<()> {
+            self.0.lock().unwrap().insert(path.to_path_buf(), contents.to_string());
+            Ok(())
+        }
+
+        fn remove(&self, path: &Path) -> std::io::Result<()> {
+            self.0.lock().unwrap().remove(path).map(|_| ()).ok_or_else(|| std::io::ErrorKind::NotFound.into())
+        }
+    }
+
+    /// `(_, _, _)`, with the picker opening on every slot click
+    const EMPTY_TRIPLE: &str = r#"{
+        "schemaVersion": 2,
+        "typeKind": "alias",
+        "name": "triple",
+        "displayName": "Triple",
+        "ast": {
+            "nodeKind": "Tuple",
+            "elements": [
+                { "nodeKind": "None", "name": "" },
+                { "nodeKind": "None", "name": "" },
+                { "nodeKind": "None", "name": "" }
+            ]
+        },
+        "meta": { "export": { "emitConsts": false }, "editorSettings": { "openPickerOnClick": true } }
+    }"#;
+
+    #[gpui::test]
+    fn rapid_slot_clicks_are_each_handled_once(cx: &mut TestAppContext) {
+        cx.update(ui::init);
+        let path = PathBuf::from("Triple/alias.json");
+        let files = Arc::new(MemoryFiles::default());
+        files.write(&path, EMPTY_TRIPLE).unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| VisualAliasEditor::new_with_provider(path, files, window, cx));
+
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|_, cx| {
+            let requests = requests.clone();
+            cx.subscribe(&editor, move |_, request: &ShowTypePickerRequest, _| {
+                requests.borrow_mut().push(request.target_slot.clone());
+            })
+            .detach();
+        });
+
+        let root_id = editor.read_with(cx, |editor, _| editor.canvas.root_block().unwrap().id().clone());
+        let handler = VisualAliasEditor::slot_click_handler(editor.downgrade());
+        let clicks = [0, 2, 1, 2];
+        // Every click is dispatched before the next frame is drawn
+        cx.update(|window, cx| {
+            for slot_idx in clicks {
+                handler(root_id.clone(), slot_idx, window, cx);
+            }
+        });
+        cx.run_until_parked();
+
+        let expected: Vec<_> = clicks.iter().map(|&slot_idx| Some((root_id.clone(), slot_idx))).collect();
+        assert_eq!(*requests.borrow(), expected);
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.selected_slot, Some((root_id.clone(), 2)));
+            assert!(editor.selected_slots.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    fn dragged_block_using_a_generic_param_is_not_extracted(cx: &mut TestAppContext) {
+        cx.update(ui::init);
+        let path = PathBuf::from("Triple/alias.json");
+        let files = Arc::new(MemoryFiles::default());
+        let generic = EMPTY_TRIPLE.replace(r#""meta": {"#, r#""meta": { "generics": ["T"],"#);
+        files.write(&path, &generic).unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| VisualAliasEditor::new_with_provider(path, files.clone(), window, cx));
+
+        let result = editor.update(cx, |editor, cx| {
+            let drag = DraggedBlock {
+                source: cx.entity_id(),
+                block_id: editor.canvas.root_block().unwrap().id().clone(),
+                ast: TypeAstNode::Constructor {
+                    name: "Vec".to_string(),
+                    params: vec![TypeAstNode::Path { path: "T".to_string() }],
+                    lifetimes: Vec::new(),
+                    const_generics: Vec::new(),
+                },
+                label: "Vec<T>".to_string(),
+            };
+            editor.extract_dragged_block(&drag, true, cx)
+        });
+
+        assert!(result.is_err());
+        // Nothing was written besides the alias itself
+        assert_eq!(files.0.lock().unwrap().len(), 1);
+    }
+}